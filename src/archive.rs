@@ -0,0 +1,555 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    providers::ExtractTarget,
+};
+use bzip2::read::BzDecoder;
+use flate2::read::GzDecoder;
+use liblzma::read::XzDecoder;
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::{Component, Path, PathBuf},
+};
+use tar::Archive;
+use zip::ZipArchive;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const XZ_MAGIC: &[u8] = b"\xfd7zXZ\x00";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// How much of a body that is not an archive is quoted in errors.
+const SNIPPET_LEN: usize = 200;
+
+/// Whether `head`, the start of a download, is a compressed tarball or a zip
+/// archive.
+pub fn looks_like_archive(head: &[u8]) -> bool {
+    [GZIP_MAGIC, BZIP2_MAGIC, XZ_MAGIC, ZIP_MAGIC]
+        .iter()
+        .any(|magic| head.starts_with(magic))
+}
+
+/// The start of `head` as one line of text, to show what came back instead
+/// of an archive.
+pub fn body_snippet(head: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&head[..head.len().min(SNIPPET_LEN)]);
+    let snippet = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if head.len() > SNIPPET_LEN {
+        format!("{}...", snippet)
+    } else {
+        snippet
+    }
+}
+
+/// Extract `targets` from a repository archive, detecting gzip, bzip2 and xz
+/// tarballs and zip archives by their leading magic bytes.
+///
+/// Archives are expected to wrap the repository in a single top-level
+/// directory (as GitHub's tarball and zipball endpoints do); that directory is
+/// stripped before matching each target's path prefix.
+///
+/// A target with an empty path takes the whole archive instead (as for
+/// release assets); if everything sits in one directory, that directory is
+/// unwrapped afterwards.
+///
+/// Returns the number of files (non-directory entries) unpacked per target.
+pub fn extract_archive<R: Read>(reader: R, targets: &[ExtractTarget]) -> SkillsResult<Vec<usize>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader
+        .fill_buf()
+        .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+
+    let files = if magic.starts_with(ZIP_MAGIC) {
+        extract_zip(reader, targets)?
+    } else if magic.starts_with(GZIP_MAGIC) {
+        extract_tar(GzDecoder::new(reader), targets)?
+    } else if magic.starts_with(BZIP2_MAGIC) {
+        extract_tar(BzDecoder::new(reader), targets)?
+    } else if magic.starts_with(XZ_MAGIC) {
+        extract_tar(XzDecoder::new(reader), targets)?
+    } else {
+        return Err(SkillsError::InvalidArchive(
+            "Unrecognized archive format (expected a gzip, bzip2 or xz tarball, or zip)"
+                .to_string(),
+        ));
+    };
+
+    for target in targets.iter().filter(|target| target.path.is_empty()) {
+        unwrap_single_directory(&target.dest_dir)?;
+    }
+    Ok(files)
+}
+
+/// Move the contents of the only entry of `dir` up into `dir` when that entry
+/// is a directory.
+fn unwrap_single_directory(dir: &Path) -> io::Result<()> {
+    let entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    let [entry] = entries.as_slice() else {
+        return Ok(());
+    };
+    if !entry.file_type()?.is_dir() {
+        return Ok(());
+    }
+
+    // Move the wrapper aside first, in case it holds an entry of its own name.
+    let wrapper = dir.join(".unwrap.tmp");
+    fs::rename(entry.path(), &wrapper)?;
+    for child in fs::read_dir(&wrapper)? {
+        let child = child?;
+        fs::rename(child.path(), dir.join(child.file_name()))?;
+    }
+    fs::remove_dir(&wrapper)
+}
+
+/// Extract `targets` from an already decompressed tar stream.
+fn extract_tar<R: Read>(reader: R, targets: &[ExtractTarget]) -> SkillsResult<Vec<usize>> {
+    let mut archive = Archive::new(reader);
+    let mut matcher = TargetMatcher::new(targets);
+
+    for entry in archive
+        .entries()
+        .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?
+    {
+        let mut entry = entry.map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+        let entry_path = entry
+            .path()
+            .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+        let is_dir = entry.header().entry_type().is_dir();
+        // Symlinks carry mode 0777; setting it would follow the link.
+        let is_file = entry.header().entry_type().is_file();
+
+        if let Some(dest_path) = matcher.destination(&entry_path, is_dir)? {
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mode = entry.header().mode().ok();
+            entry.unpack(&dest_path)?;
+            if is_file {
+                apply_executable_bits(&dest_path, mode)?;
+            }
+        }
+    }
+
+    matcher.finish()
+}
+
+fn extract_zip<R: Read>(mut reader: R, targets: &[ExtractTarget]) -> SkillsResult<Vec<usize>> {
+    // Zip archives keep their index at the end, so the body must be buffered.
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+    let mut matcher = TargetMatcher::new(targets);
+
+    for idx in 0..archive.len() {
+        let mut file = archive
+            .by_index(idx)
+            .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+        let entry_path = file.name().to_string();
+
+        if let Some(dest_path) = matcher.destination(&entry_path, file.is_dir())? {
+            if file.is_dir() {
+                fs::create_dir_all(&dest_path)?;
+                continue;
+            }
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut out = fs::File::create(&dest_path)?;
+            io::copy(&mut file, &mut out)?;
+            if !file.is_symlink() {
+                apply_executable_bits(&dest_path, file.unix_mode())?;
+            }
+        }
+    }
+
+    matcher.finish()
+}
+
+/// Maps archive entry paths to destination paths for a set of targets.
+struct TargetMatcher<'a> {
+    targets: &'a [ExtractTarget],
+    top_level_dir: Option<String>,
+    found: Vec<bool>,
+    files: Vec<usize>,
+}
+
+impl<'a> TargetMatcher<'a> {
+    fn new(targets: &'a [ExtractTarget]) -> Self {
+        Self {
+            targets,
+            top_level_dir: None,
+            found: vec![false; targets.len()],
+            files: vec![0; targets.len()],
+        }
+    }
+
+    /// Return where `entry_path` should be written, or `None` when it does
+    /// not belong to any target.
+    fn destination(&mut self, entry_path: &str, is_dir: bool) -> SkillsResult<Option<PathBuf>> {
+        if let Some(idx) = self
+            .targets
+            .iter()
+            .position(|target| target.path.is_empty())
+        {
+            return self.accept(idx, entry_path, entry_path, is_dir);
+        }
+
+        if self.top_level_dir.is_none()
+            && let Some(slash_pos) = entry_path.find('/')
+        {
+            self.top_level_dir = Some(entry_path[..slash_pos].to_string());
+        }
+
+        let Some(top_dir) = &self.top_level_dir else {
+            return Ok(None);
+        };
+
+        for (idx, target) in self.targets.iter().enumerate() {
+            let expected_prefix = format!("{}/{}/", top_dir, target.path);
+            let Some(relative) = entry_path.strip_prefix(&expected_prefix) else {
+                continue;
+            };
+            return self.accept(idx, entry_path, relative, is_dir);
+        }
+
+        Ok(None)
+    }
+
+    /// Record `relative` as part of target `idx` and return its destination.
+    fn accept(
+        &mut self,
+        idx: usize,
+        entry_path: &str,
+        relative: &str,
+        is_dir: bool,
+    ) -> SkillsResult<Option<PathBuf>> {
+        let relative = relative.trim_end_matches('/');
+        if relative.is_empty() {
+            return Ok(None);
+        }
+        if !is_safe_relative_path(relative) {
+            return Err(SkillsError::InvalidArchive(format!(
+                "Refusing to extract unsafe path: {entry_path}"
+            )));
+        }
+        self.found[idx] = true;
+        if !is_dir {
+            self.files[idx] += 1;
+        }
+        Ok(Some(self.targets[idx].dest_dir.join(relative)))
+    }
+
+    fn finish(self) -> SkillsResult<Vec<usize>> {
+        let missing_paths: Vec<_> = self
+            .targets
+            .iter()
+            .zip(self.found)
+            .filter_map(|(target, found)| (!found).then_some(target.path.clone()))
+            .collect();
+
+        if !missing_paths.is_empty() {
+            return SkillsError::PathNotFound(missing_paths).into();
+        }
+
+        Ok(self.files)
+    }
+}
+
+/// Set the executable bits from an archive entry's `mode` on the extracted
+/// file, so helper scripts stay runnable whatever the unpacker did with them.
+#[cfg(unix)]
+pub(crate) fn apply_executable_bits(path: &Path, mode: Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(exec_bits) = mode.map(|mode| mode & 0o111).filter(|bits| *bits != 0) else {
+        return Ok(());
+    };
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | exec_bits);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_executable_bits(_path: &Path, _mode: Option<u32>) -> io::Result<()> {
+    Ok(())
+}
+
+/// Reject absolute paths and `..` components so entries cannot escape the
+/// destination directory.
+pub(crate) fn is_safe_relative_path(relative: &str) -> bool {
+    Path::new(relative)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn build_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_zip_strips_top_level_dir() {
+        let temp_dir = std::env::temp_dir().join("skills_test_extract_zip");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let bytes = build_zip(&[
+            ("owner-repo-abc123/README.md", b"readme"),
+            ("owner-repo-abc123/skills/demo/SKILL.md", b"# Demo"),
+            ("owner-repo-abc123/skills/demo/scripts/run.sh", b"echo hi"),
+        ]);
+        let targets = [ExtractTarget {
+            path: "skills/demo".to_string(),
+            dest_dir: temp_dir.clone(),
+        }];
+
+        let files = extract_archive(Cursor::new(bytes), &targets).unwrap();
+        assert_eq!(files, vec![2]);
+
+        assert_eq!(fs::read(temp_dir.join("SKILL.md")).unwrap(), b"# Demo");
+        assert!(temp_dir.join("scripts/run.sh").exists());
+        assert!(!temp_dir.join("README.md").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_extract_tar_keeps_executable_bits() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = std::env::temp_dir().join("skills_test_extract_tar_exec");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for (name, mode, contents) in [
+            (
+                "owner-repo-abc123/skills/demo/SKILL.md",
+                0o644,
+                &b"# Demo"[..],
+            ),
+            (
+                "owner-repo-abc123/skills/demo/run.sh",
+                0o755,
+                &b"echo hi"[..],
+            ),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder.append_data(&mut header, name, contents).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        let targets = [ExtractTarget {
+            path: "skills/demo".to_string(),
+            dest_dir: temp_dir.clone(),
+        }];
+
+        extract_archive(Cursor::new(bytes), &targets).unwrap();
+
+        let mode = |name: &str| {
+            fs::metadata(temp_dir.join(name))
+                .unwrap()
+                .permissions()
+                .mode()
+        };
+        assert_eq!(mode("run.sh") & 0o111, 0o111);
+        assert_eq!(mode("SKILL.md") & 0o111, 0);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_extract_tar_symlink_before_its_target() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = std::env::temp_dir().join("skills_test_extract_tar_symlink");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        let link = |builder: &mut tar::Builder<_>, name: &str, target: &str| {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            header.set_mode(0o777);
+            builder.append_link(&mut header, name, target).unwrap();
+        };
+        link(
+            &mut builder,
+            "owner-repo-abc123/skills/demo/latest.md",
+            "SKILL.md",
+        );
+        link(
+            &mut builder,
+            "owner-repo-abc123/skills/demo/dangling.md",
+            "missing.md",
+        );
+        let mut header = tar::Header::new_gnu();
+        header.set_size(6);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(
+                &mut header,
+                "owner-repo-abc123/skills/demo/SKILL.md",
+                &b"# Demo"[..],
+            )
+            .unwrap();
+        link(
+            &mut builder,
+            "owner-repo-abc123/skills/demo/alias.md",
+            "SKILL.md",
+        );
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        let targets = [ExtractTarget {
+            path: "skills/demo".to_string(),
+            dest_dir: temp_dir.clone(),
+        }];
+
+        extract_archive(Cursor::new(bytes), &targets).unwrap();
+
+        assert_eq!(fs::read(temp_dir.join("latest.md")).unwrap(), b"# Demo");
+        assert!(temp_dir.join("dangling.md").is_symlink());
+        // A link does not pass its 0777 mode on to the file it points to.
+        let mode = fs::metadata(temp_dir.join("SKILL.md"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_whole_archive_unwraps_single_directory() {
+        let temp_dir = std::env::temp_dir().join("skills_test_extract_whole");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let bytes = build_zip(&[
+            ("demo-v1.0/SKILL.md", b"# Demo"),
+            ("demo-v1.0/scripts/run.sh", b"echo hi"),
+        ]);
+        let targets = [ExtractTarget {
+            path: String::new(),
+            dest_dir: temp_dir.clone(),
+        }];
+
+        let files = extract_archive(Cursor::new(bytes), &targets).unwrap();
+        assert_eq!(files, vec![2]);
+        assert_eq!(fs::read(temp_dir.join("SKILL.md")).unwrap(), b"# Demo");
+        assert!(temp_dir.join("scripts/run.sh").exists());
+        assert!(!temp_dir.join("demo-v1.0").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+
+        // Flat archives are extracted as they are.
+        let bytes = build_zip(&[("SKILL.md", b"# Demo"), ("scripts/run.sh", b"echo hi")]);
+        extract_archive(Cursor::new(bytes), &targets).unwrap();
+        assert!(temp_dir.join("SKILL.md").exists());
+        assert!(temp_dir.join("scripts/run.sh").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_zip_missing_path() {
+        let bytes = build_zip(&[("owner-repo-abc123/other/SKILL.md", b"# Other")]);
+        let targets = [ExtractTarget {
+            path: "skills/demo".to_string(),
+            dest_dir: std::env::temp_dir().join("skills_test_extract_zip_missing"),
+        }];
+
+        let result = extract_archive(Cursor::new(bytes), &targets);
+        assert!(matches!(result, Err(SkillsError::PathNotFound(_))));
+    }
+
+    #[test]
+    fn test_extract_bzip2_and_xz_tarballs() {
+        let temp_dir = std::env::temp_dir().join("skills_test_extract_bz2_xz");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(6);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(
+                &mut header,
+                "owner-repo-abc123/demo/SKILL.md",
+                &b"# Demo"[..],
+            )
+            .unwrap();
+        let tar = builder.into_inner().unwrap();
+
+        let mut bz = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::fast());
+        bz.write_all(&tar).unwrap();
+        let mut xz = liblzma::write::XzEncoder::new(Vec::new(), 1);
+        xz.write_all(&tar).unwrap();
+
+        for (name, bytes) in [("bz2", bz.finish().unwrap()), ("xz", xz.finish().unwrap())] {
+            assert!(looks_like_archive(&bytes));
+            let dest_dir = temp_dir.join(name);
+            let targets = [ExtractTarget {
+                path: "demo".to_string(),
+                dest_dir: dest_dir.clone(),
+            }];
+            assert_eq!(
+                extract_archive(Cursor::new(bytes), &targets).unwrap(),
+                vec![1]
+            );
+            assert_eq!(fs::read(dest_dir.join("SKILL.md")).unwrap(), b"# Demo");
+        }
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_rejects_unknown_format() {
+        let result = extract_archive(Cursor::new(b"<html>".to_vec()), &[]);
+        assert!(matches!(result, Err(SkillsError::InvalidArchive(_))));
+    }
+
+    #[test]
+    fn test_body_snippet() {
+        assert!(looks_like_archive(b"\x1f\x8b\x08"));
+        assert!(looks_like_archive(b"PK\x03\x04rest"));
+        assert!(!looks_like_archive(b"<!DOCTYPE html>"));
+
+        let page = b"<html>\n  <title>Proxy login</title>\n</html>";
+        assert_eq!(
+            body_snippet(page),
+            "<html> <title>Proxy login</title> </html>"
+        );
+        assert!(body_snippet(&[b'x'; 300]).ends_with("..."));
+    }
+
+    #[test]
+    fn test_is_safe_relative_path() {
+        assert!(is_safe_relative_path("SKILL.md"));
+        assert!(is_safe_relative_path("scripts/run.sh"));
+        assert!(!is_safe_relative_path("../escape"));
+        assert!(!is_safe_relative_path("scripts/../../escape"));
+        assert!(!is_safe_relative_path("/etc/passwd"));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    errors::SkillsResult,
+    models::{SkillEntry, SkillsConfig},
+    style,
+    utils::{ChecksumAlgorithm, calculate_checksum, ensure_skill_manifest},
+};
+use std::{fs, path::Path};
+
+use super::SkillsPaths;
+
+/// Options controlling a single `adopt` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct AdoptOptions {
+    /// Named root from `[roots]` to scan; `None` scans the default directory.
+    pub root: Option<String>,
+}
+
+/// Record skills that already exist on disk but are missing from
+/// `skills.toml` as local skills, so `sync` can track their checksums.
+pub fn adopt_skills(paths: &SkillsPaths, options: &AdoptOptions) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let mut config = SkillsConfig::from_file(config_path)?;
+    let skills_dir = paths.root_dir(&config, options.root.as_deref())?;
+
+    let mut adopted = Vec::new();
+    for name in find_local_skills(&skills_dir, &config.settings.manifest_names) {
+        if config.skills.contains_key(&name) || config.unresolved.contains_key(&name) {
+            continue;
+        }
+        let checksum = calculate_checksum(&skills_dir.join(&name), ChecksumAlgorithm::default())?;
+        config.skills.insert(
+            name.clone(),
+            SkillEntry::local(checksum, options.root.clone()),
+        );
+        adopted.push(name);
+    }
+
+    if adopted.is_empty() {
+        println!("No untracked skills found in {}", skills_dir.display());
+        return Ok(());
+    }
+
+    config.save(config_path)?;
+    for name in &adopted {
+        println!("{}", style::success(&format!("Adopted '{}'", name)));
+    }
+    println!(
+        "Added {} local skill(s). Install them from a URL to track upstream changes.",
+        adopted.len()
+    );
+
+    Ok(())
+}
+
+/// Names of the skill directories under `dir`: direct children with one of
+/// the `manifest_names`, or `<category>/<skill>` for skills one level further
+/// down. Hidden directories are ignored.
+fn find_local_skills(dir: &Path, manifest_names: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+    for (name, path) in visible_subdirs(dir) {
+        if ensure_skill_manifest(&path, manifest_names).is_ok() {
+            names.push(name);
+            continue;
+        }
+        for (child, child_path) in visible_subdirs(&path) {
+            if ensure_skill_manifest(&child_path, manifest_names).is_ok() {
+                names.push(format!("{}/{}", name, child));
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+fn visible_subdirs(dir: &Path) -> Vec<(String, std::path::PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_dir()))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            (!name.starts_with('.')).then(|| (name, entry.path()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_local_skills() {
+        let dir = std::env::temp_dir().join("skills_test_find_local_skills");
+        fs::remove_dir_all(&dir).ok();
+        for skill in ["demo", "tools/lint", ".install.tmp/hidden"] {
+            fs::create_dir_all(dir.join(skill)).unwrap();
+            fs::write(dir.join(skill).join("SKILL.md"), b"# Skill").unwrap();
+        }
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs").join("AGENT.md"), b"# Agent").unwrap();
+
+        assert_eq!(find_local_skills(&dir, &[]), vec!["demo", "tools/lint"]);
+        assert_eq!(
+            find_local_skills(&dir, &["AGENT.md".to_string()]),
+            vec!["docs"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{errors::SkillsResult, providers::ProviderRegistry, style};
+
+use super::OutputFormat;
+
+/// Print the skills found at `url` without installing anything.
+pub fn browse_skills(
+    url: &str,
+    manifest_names: &[String],
+    output: OutputFormat,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let provider = registry.get(url)?;
+    let listings = provider.browse(url, manifest_names)?;
+
+    if output == OutputFormat::Json {
+        let skills: Vec<_> = listings
+            .iter()
+            .map(|listing| {
+                serde_json::json!({
+                    "name": listing.name,
+                    "has_manifest": listing.has_manifest,
+                    "description": listing.description,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::json!({ "url": url, "skills": skills }));
+        return Ok(());
+    }
+
+    for listing in &listings {
+        if listing.has_manifest {
+            println!("  {}", listing.name);
+        } else {
+            println!("  {} {}", listing.name, style::warning("(no manifest)"));
+        }
+        if let Some(description) = &listing.description {
+            println!("    {}", description);
+        }
+    }
+
+    let skills = listings
+        .iter()
+        .filter(|listing| listing.has_manifest)
+        .count();
+    println!();
+    println!("Found: {} skill(s)", skills);
+
+    Ok(())
+}
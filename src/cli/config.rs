@@ -0,0 +1,45 @@
+use crate::{
+    errors::SkillsResult,
+    models::{Settings, SkillsConfig},
+    style,
+};
+
+use super::SkillsPaths;
+
+/// Print the value of `key` from `[config]`, or its default when unset.
+pub fn config_get(key: &str, paths: &SkillsPaths) -> SkillsResult<()> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+    let value = match config.settings.get(key)? {
+        Some(value) => value,
+        None => Settings::default_value(key)?,
+    };
+    println!("{}", value);
+    Ok(())
+}
+
+/// Validate `value` for `key` and save it under `[config]`.
+pub fn config_set(key: &str, value: &str, paths: &SkillsPaths) -> SkillsResult<()> {
+    let mut config = SkillsConfig::from_file(&paths.manifest)?;
+    config.settings.set(key, value)?;
+    config.save(&paths.manifest)?;
+
+    let shown = match config.settings.get(key)? {
+        Some(value) => value,
+        None => format!("{} (default)", Settings::default_value(key)?),
+    };
+    println!("{}", style::success(&format!("Set {} = {}", key, shown)));
+    Ok(())
+}
+
+/// Print every setting with its effective value and where it comes from.
+pub fn config_list(paths: &SkillsPaths) -> SkillsResult<()> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+    for key in Settings::KEYS {
+        let (value, source) = match config.settings.get(key)? {
+            Some(value) => (value, paths.manifest.display().to_string()),
+            None => (Settings::default_value(key)?, "default".to_string()),
+        };
+        println!("{} = {}  ({})", key, value, source);
+    }
+    Ok(())
+}
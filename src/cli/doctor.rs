@@ -0,0 +1,85 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::SkillsConfig,
+    style,
+};
+use std::{collections::BTreeSet, fs};
+
+use super::{
+    SkillsPaths,
+    recover::{find_interrupted, recover_interrupted},
+};
+
+/// Options controlling a single `doctor` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct DoctorOptions {
+    /// Repair the problems that can be repaired without user input.
+    pub fix: bool,
+}
+
+/// Check `skills.toml` for problems that would break installs or syncs.
+pub fn doctor(paths: &SkillsPaths, options: &DoctorOptions) -> SkillsResult<()> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+
+    let conflicting = report_directory_conflicts(paths, &config);
+
+    let interrupted = find_interrupted(paths, &config)?;
+    let mut problems = conflicting.len();
+    if options.fix {
+        recover_interrupted(&interrupted)?;
+    } else {
+        for install in &interrupted {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "Warning: '{}' is missing but an interrupted download of it was left in {}",
+                    install.name,
+                    install.temp_dirs[0].display()
+                ))
+            );
+        }
+        if !interrupted.is_empty() {
+            eprintln!("  Run `skill doctor --fix` to finish or discard these installs.");
+        }
+        problems += interrupted.len();
+    }
+
+    if problems == 0 {
+        println!("{}", style::success("No problems found."));
+        return Ok(());
+    }
+
+    Err(SkillsError::ProblemsFound(problems))
+}
+
+/// Warn about skills that would share a directory on disk and return their
+/// names so callers can leave them alone until the conflict is resolved.
+pub(super) fn report_directory_conflicts(
+    paths: &SkillsPaths,
+    config: &SkillsConfig,
+) -> BTreeSet<String> {
+    let mut conflicting = BTreeSet::new();
+
+    // Roots written differently (`skills`, `./skills/`, a symlink) may still
+    // be one directory.
+    let root_dir = |root: Option<&str>| {
+        let dir = paths.root_dir(config, root).ok()?;
+        Some(fs::canonicalize(&dir).unwrap_or(dir))
+    };
+    for names in config.directory_conflicts(root_dir) {
+        eprintln!(
+            "{}",
+            style::warning_err(&format!(
+                "Warning: these skills would share one directory: {}",
+                names.join(", ")
+            ))
+        );
+        for name in names {
+            eprintln!("  {}: {}", name, config.source_url(name).unwrap_or("-"));
+            conflicting.insert(name.to_string());
+        }
+        eprintln!("  Uninstall or rename all but one of them to resolve the conflict.");
+    }
+
+    conflicting
+}
@@ -0,0 +1,79 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::ArchiveFormat,
+    providers::{ExtractTarget, ProviderRegistry, ResolveOptions},
+    style,
+    utils::{format_bytes, parse_until_date},
+};
+use std::{fs, path::PathBuf};
+
+/// Options controlling a single `fetch` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Directory to extract the skill directories into.
+    pub output_dir: PathBuf,
+    /// Resolve refs to the last commit at or before this date.
+    pub at: Option<String>,
+    /// Archive format to download.
+    pub archive_format: ArchiveFormat,
+    /// File names that mark a directory as a skill; empty means `SKILL.md`.
+    pub manifest_names: Vec<String>,
+}
+
+/// Download the skill(s) at `url` into `options.output_dir` without touching
+/// `skills.toml` or the skills directory.
+pub fn fetch_skill(
+    url: &str,
+    options: &FetchOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let provider = registry.get(url)?;
+    let resolve_options = ResolveOptions {
+        until: options.at.as_deref().map(parse_until_date).transpose()?,
+        archive_format: options.archive_format,
+        manifest_names: options.manifest_names.clone(),
+        ..Default::default()
+    };
+    let plan = provider.resolve_install_plan(url, &resolve_options)?;
+
+    let targets: Vec<_> = plan
+        .skills
+        .iter()
+        .map(|skill| ExtractTarget {
+            path: skill.path.clone(),
+            dest_dir: options.output_dir.join(&skill.name),
+        })
+        .collect();
+    for target in &targets {
+        if fs::read_dir(&target.dest_dir).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(SkillsError::OutputExists(
+                target.dest_dir.display().to_string(),
+            ));
+        }
+    }
+
+    println!("Downloading {} skill(s)...", targets.len());
+    let stats = provider
+        .fetch_and_extract(&plan.archive_url, &targets)
+        .inspect_err(|_| {
+            for target in &targets {
+                fs::remove_dir_all(&target.dest_dir).ok();
+            }
+        })?;
+
+    for (idx, (skill, target)) in plan.skills.iter().zip(&targets).enumerate() {
+        println!(
+            "{}",
+            style::success(&format!(
+                "Fetched '{}' ({} files) to {}",
+                skill.name,
+                stats.files.get(idx).copied().unwrap_or_default(),
+                target.dest_dir.display()
+            ))
+        );
+        println!("  Commit: {}", skill.sha);
+    }
+    println!("Transferred {}", format_bytes(stats.bytes));
+
+    Ok(())
+}
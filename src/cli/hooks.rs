@@ -0,0 +1,119 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::Hooks,
+    style,
+};
+use std::{path::Path, process::Command};
+
+/// Environment variable holding the name of the skill a hook runs for.
+pub const SKILL_NAME_ENV: &str = "SKILLS_MAN_SKILL_NAME";
+/// Environment variable holding the directory of the skill a hook runs for.
+pub const SKILL_PATH_ENV: &str = "SKILLS_MAN_SKILL_PATH";
+
+/// Runs the `post_install` hook for each written skill and collects failures,
+/// so a failing hook is reported without rolling back the install.
+#[derive(Debug, Default)]
+pub(super) struct PostInstallHook {
+    failures: Vec<(String, Option<i32>)>,
+}
+
+impl PostInstallHook {
+    /// Run the hook, if configured, inside `skill_dir`.
+    pub(super) fn run(&mut self, hooks: &Hooks, name: &str, skill_dir: &Path) {
+        let Some(command) = &hooks.post_install else {
+            return;
+        };
+
+        println!("[{}] Running post_install hook: {}", name, command);
+        let code = match shell(command)
+            .current_dir(skill_dir)
+            .env(SKILL_NAME_ENV, name)
+            .env(SKILL_PATH_ENV, skill_dir)
+            .status()
+        {
+            Ok(status) if status.success() => return,
+            Ok(status) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!("[{}] post_install hook failed ({})", name, status))
+                );
+                status.code()
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!(
+                        "[{}] Failed to run post_install hook: {}",
+                        name, e
+                    ))
+                );
+                None
+            }
+        };
+        self.failures.push((name.to_string(), code));
+    }
+
+    pub(super) fn finish(self) -> SkillsResult<()> {
+        if self.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(SkillsError::HookFailed(self.failures))
+        }
+    }
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_post_install_hook() {
+        let temp_dir = std::env::temp_dir().join("skills_test_post_install");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let mut hook = PostInstallHook::default();
+        hook.run(&Hooks::default(), "demo", &temp_dir);
+        hook.run(
+            &Hooks {
+                post_install: Some(format!("echo \"${SKILL_NAME_ENV}\" > marker")),
+            },
+            "demo",
+            &temp_dir,
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("marker")).unwrap(),
+            "demo\n"
+        );
+        assert!(hook.finish().is_ok());
+
+        let mut hook = PostInstallHook::default();
+        hook.run(
+            &Hooks {
+                post_install: Some("exit 3".to_string()),
+            },
+            "demo",
+            &temp_dir,
+        );
+        assert!(matches!(
+            hook.finish(),
+            Err(SkillsError::HookFailed(failures)) if failures == vec![("demo".to_string(), Some(3))]
+        ));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -1,72 +1,564 @@
 use crate::{
     errors::{SkillsError, SkillsResult},
-    models::{SkillEntry, SkillsConfig},
-    providers::{ExtractTarget, InstallPlan, ProviderRegistry, ResolvedSkill, SkillProvider},
-    utils::{calculate_checksum, ensure_skill_manifest},
+    models::{ArchiveFormat, ManifestEntry, RefKind, SkillEntry, SkillsConfig},
+    providers::{
+        ExtractTarget, InstallPlan, ProviderRegistry, ResolveOptions, ResolvedSkill, SkillProvider,
+    },
+    style,
+    utils::{
+        ChecksumAlgorithm, SKIPPED_CHECKSUM, cacheable_tree_mtime, calculate_checksum,
+        checksum_matches, current_timestamp, ensure_skill_manifest, find_name_collision,
+        is_safe_skill_name, missing_front_matter, move_dir, parse_checksum, parse_until_date,
+        prefixed_name, same_commit, unique_temp_name, verify_skill_signature,
+    },
 };
 use std::{fs, path::Path};
 
-use super::prompt::confirm_action_or_yes;
+use super::{
+    SkillsPaths,
+    hooks::PostInstallHook,
+    prompt::{
+        confirm_action, confirm_action_or_yes, confirm_or_quit, is_interactive, select_items,
+    },
+    recover::recover_before_run,
+};
+
+/// Options controlling a single `install` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Automatically answer yes to prompts.
+    pub yes: bool,
+    /// Resolve refs to the last commit at or before this date.
+    pub at: Option<String>,
+    /// Re-download skills even when they are already up to date.
+    pub refresh: bool,
+    /// Archive format to download.
+    pub archive_format: ArchiveFormat,
+    /// Named root from `[roots]` to install into; `None` uses the default.
+    pub root: Option<String>,
+    /// Try tags or branches first when the URL could be split several ways.
+    pub prefer_ref: Option<RefKind>,
+    /// Keep each skill under its parent directory (`<category>/<skill>`)
+    /// instead of directly in the skills directory.
+    pub nested: bool,
+    /// Prepended to the name of each installed skill, e.g. `acme-` turns
+    /// `frontend-design` into `acme-frontend-design`.
+    pub prefix: Option<String>,
+    /// Algorithm for the recorded checksum; `None` keeps the algorithm of an
+    /// existing entry, or uses the default for new skills.
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    /// Skip collection sub-directories without a SKILL.md instead of failing.
+    pub continue_on_manifest_error: bool,
+    /// Extra file names that mark a directory as a skill; remembered under
+    /// `[config] manifest_names`.
+    pub manifest_names: Vec<String>,
+    /// Confirm the GitHub token can read the repository before resolving.
+    pub token_check: bool,
+    /// Branch or tag the skill already tracks; resolved directly instead of
+    /// guessing how to split the URL.
+    pub ref_name: Option<String>,
+    /// Whether `ref_name` is a branch or a tag (`--branch`/`--tag`). It is
+    /// then only looked up as that kind, and `update` does the same.
+    pub ref_kind: Option<RefKind>,
+    /// Repository path of the skill. With `ref_name`, only the repository
+    /// is taken from the URL.
+    pub path: Option<String>,
+    /// Print a one-line JSON summary of what happened to each skill.
+    pub summary: bool,
+    /// Pick which skills of a collection to install from a list, when
+    /// prompts can be answered and `yes` is not set.
+    pub interactive: bool,
+    /// Ask about each skill of a collection in turn, when prompts can be
+    /// answered and `yes` is not set.
+    pub confirm_each: bool,
+    /// Only install skills already in `skills.toml`, leaving skills new to
+    /// the source for a deliberate install later.
+    pub update_existing_only: bool,
+    /// Fail instead of replacing a skill installed from another source or
+    /// an existing directory that is not in `skills.toml`.
+    pub no_overwrite: bool,
+    /// Record the resolved skills in `skills.toml` and `skills.lock` without
+    /// downloading them, leaving that to `sync`.
+    pub manifest_only: bool,
+    /// Require `name`, `description` and `required_fields` from `[config]`
+    /// in each skill's front matter.
+    pub strict_manifest: bool,
+    /// Record [`SKIPPED_CHECKSUM`] instead of hashing the installed files.
+    pub skip_checksum: bool,
+    /// Trust skills already installed from the URL whose files match their
+    /// checksum, and report them up to date without asking upstream.
+    pub prefer_cache: bool,
+    /// Stop at the first skill of a collection or URL list that fails; the
+    /// rest are skipped. By default every skill is tried and the failures
+    /// are reported together.
+    pub fail_fast: bool,
+    /// What to do with skills whose files were edited since they were
+    /// installed. Without a prompt, [`LocalChanges::Prompt`] overwrites them
+    /// with a warning.
+    pub local_changes: LocalChanges,
+    /// Leave the temp directory of a failed download in place and print
+    /// its path, to inspect what was extracted.
+    pub keep_temp: bool,
+    /// Refuse skills whose files are not covered by a `SHA256SUMS` signed
+    /// with one of the `trusted_keys` in `[config]`.
+    pub verify_signatures: bool,
+    /// Install the path as one skill without looking for skills in its
+    /// subdirectories or requiring a manifest in it.
+    pub no_subdir: bool,
+}
+
+/// What to do with a skill whose files no longer match its checksum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocalChanges {
+    /// Ask when prompts can be answered; each command decides what happens
+    /// otherwise.
+    #[default]
+    Prompt,
+    /// Overwrite them without asking.
+    Discard,
+    /// Leave the skill untouched.
+    Keep,
+}
+
+/// What an install did to one skill, as reported by `--summary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InstallStatus {
+    Installed,
+    Updated,
+    UpToDate,
+    Skipped,
+    Failed,
+}
+
+impl InstallStatus {
+    const ALL: [InstallStatus; 5] = [
+        InstallStatus::Installed,
+        InstallStatus::Updated,
+        InstallStatus::UpToDate,
+        InstallStatus::Skipped,
+        InstallStatus::Failed,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            InstallStatus::Installed => "installed",
+            InstallStatus::Updated => "updated",
+            InstallStatus::UpToDate => "up_to_date",
+            InstallStatus::Skipped => "skipped",
+            InstallStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Per-skill outcomes of one install, for `--summary`.
+#[derive(Debug, Default)]
+struct InstallSummary {
+    skills: Vec<(String, InstallStatus, Option<String>)>,
+}
+
+impl InstallSummary {
+    fn record(&mut self, name: &str, status: InstallStatus) {
+        self.skills.push((name.to_string(), status, None));
+    }
+
+    fn record_failure(&mut self, name: &str, error: &str) {
+        self.skills.push((
+            name.to_string(),
+            InstallStatus::Failed,
+            Some(error.to_string()),
+        ));
+    }
+
+    /// The summary as JSON, with the recorded state of each skill taken from
+    /// `config`.
+    fn to_json(&self, config: &SkillsConfig, skills_dir: &Path) -> serde_json::Value {
+        let skills: Vec<_> = self
+            .skills
+            .iter()
+            .map(|(name, status, error)| {
+                let mut skill = serde_json::json!({
+                    "name": name,
+                    "status": status.as_str(),
+                });
+                if let Some(entry) = config.skills.get(name)
+                    && *status != InstallStatus::Failed
+                {
+                    skill["sha"] = entry.sha.clone().into();
+                    skill["path"] = entry.path.clone().into();
+                    skill["dir"] = skills_dir.join(name).display().to_string().into();
+                    skill["checksum"] = entry.checksum.clone().into();
+                }
+                if let Some(error) = error {
+                    skill["error"] = error.clone().into();
+                }
+                skill
+            })
+            .collect();
+        let totals: serde_json::Map<_, _> = InstallStatus::ALL
+            .iter()
+            .map(|status| {
+                let count = self.skills.iter().filter(|(_, s, _)| s == status).count();
+                (status.as_str().to_string(), count.into())
+            })
+            .collect();
+        serde_json::json!({ "skills": skills, "totals": totals })
+    }
+}
+
+impl InstallOptions {
+    /// Options that reproduce how the skill `name` was installed.
+    pub(super) fn for_entry(name: &str, entry: &SkillEntry) -> Self {
+        Self {
+            checksum_algorithm: ChecksumAlgorithm::of_checksum(&entry.checksum),
+            ref_name: entry.ref_name.clone(),
+            skip_checksum: entry.checksum == SKIPPED_CHECKSUM,
+            ..Self::for_manifest_entry(name, &entry.split().0)
+        }
+    }
+
+    /// Options that install the skill `name` as `skills.toml` records it,
+    /// for entries that have not been resolved yet.
+    pub(super) fn for_manifest_entry(name: &str, entry: &ManifestEntry) -> Self {
+        Self {
+            at: entry.until.clone(),
+            archive_format: entry.archive_format.unwrap_or_default(),
+            root: entry.root.clone(),
+            prefer_ref: entry.prefer_ref,
+            nested: is_nested(name),
+            prefix: entry.prefix.clone(),
+            // The collection was accepted when it was first installed; new
+            // non-skill folders upstream shouldn't block updating it.
+            continue_on_manifest_error: entry.collection_url.is_some(),
+            ref_kind: entry.ref_kind,
+            no_subdir: entry.no_subdir,
+            ..Default::default()
+        }
+    }
+
+    /// The archive format to record in `skills.toml`; the default is omitted.
+    fn stored_archive_format(&self) -> Option<ArchiveFormat> {
+        (self.archive_format != ArchiveFormat::default()).then_some(self.archive_format)
+    }
+}
 
 pub fn install_skill(
     url: &str,
-    base_dir: &Path,
-    yes: bool,
+    paths: &SkillsPaths,
+    options: &InstallOptions,
     registry: &ProviderRegistry,
 ) -> SkillsResult<()> {
+    let mut hook = PostInstallHook::default();
+    install_skill_counted(url, paths, options, registry, &mut hook)?;
+    hook.finish()
+}
+
+/// Like [`install_skill`], returning the number of bytes downloaded. Failed
+/// `post_install` hooks are left in `hook` for the caller to report.
+pub(super) fn install_skill_counted(
+    url: &str,
+    paths: &SkillsPaths,
+    options: &InstallOptions,
+    registry: &ProviderRegistry,
+    hook: &mut PostInstallHook,
+) -> SkillsResult<u64> {
+    let options = InstallOptions {
+        at: options.at.as_deref().map(parse_until_date).transpose()?,
+        ..options.clone()
+    };
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+    // A cached skill from a source no longer allowed is refused too.
+    config.settings.check_source(url)?;
+    if options.prefer_cache
+        && !options.refresh
+        && let Some(names) = cached_up_to_date(url, &config, paths, &options)
+    {
+        let mut summary = InstallSummary::default();
+        for name in &names {
+            println!("Skill '{}' is already up to date (cached).", name);
+            summary.record(name, InstallStatus::UpToDate);
+        }
+        if options.summary {
+            println!(
+                "{}",
+                summary.to_json(&config, &paths.root_dir(&config, options.root.as_deref())?)
+            );
+        }
+        return Ok(0);
+    }
     let provider = registry.get(url)?;
-    let plan = provider.resolve_install_plan(url)?;
-    install_plan(provider, plan, base_dir, yes)
+    let mut settings = config.settings;
+    settings.add_manifest_names(&options.manifest_names);
+    let resolve_options = ResolveOptions {
+        until: options.at.clone(),
+        archive_format: options.archive_format,
+        prefer_ref: options.prefer_ref,
+        manifest_names: settings.manifest_names,
+        ref_name: options.ref_name.clone(),
+        ref_kind: options.ref_kind,
+        path: options.path.clone(),
+        no_subdir: options.no_subdir,
+        check_token: options.token_check,
+    };
+    let plan = provider.resolve_install_plan(url, &resolve_options)?;
+    install_plan(provider, plan, paths, &options, hook)
+}
+
+/// Install every URL listed in `list_path`, one per line. Blank lines and
+/// `#` comments are ignored; lines that fail are reported with their line
+/// number and skipped.
+pub fn install_from_file(
+    list_path: &Path,
+    paths: &SkillsPaths,
+    options: &InstallOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let content = fs::read_to_string(list_path)?;
+    let urls = parse_url_list(&content);
+    if urls.is_empty() {
+        println!("No URLs found in {}", list_path.display());
+        return Ok(());
+    }
+
+    let mut successful = 0;
+    let mut failed = Vec::new();
+    for (idx, (line, url)) in urls.iter().enumerate() {
+        if stop_early(options, &failed) {
+            println!(
+                "Stopping after the first failure; {} URL(s) not attempted.",
+                urls.len() - idx
+            );
+            break;
+        }
+        println!("[line {}] Installing {}...", line, url);
+        match install_skill(url, paths, options, registry) {
+            Ok(_) => successful += 1,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!("[line {}] Failed to install {}: {}", line, url, e))
+                );
+                failed.push((format!("line {}: {}", line, url), e.to_string()));
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(SkillsError::BatchInstallationFailed { successful, failed });
+    }
+
+    Ok(())
+}
+
+/// Non-empty, non-comment lines of a URL list with their 1-based line numbers.
+fn parse_url_list(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let line = match line.find(" #") {
+                Some(pos) => &line[..pos],
+                None => line,
+            }
+            .trim();
+            (!line.is_empty() && !line.starts_with('#')).then_some((idx + 1, line))
+        })
+        .collect()
 }
 
 fn install_plan(
     provider: &dyn SkillProvider,
     plan: InstallPlan,
-    base_dir: &Path,
-    yes: bool,
-) -> SkillsResult<()> {
-    let skills_dir = base_dir.join("skills");
-    let config_path = base_dir.join("skills.toml");
+    paths: &SkillsPaths,
+    options: &InstallOptions,
+    hook: &mut PostInstallHook,
+) -> SkillsResult<u64> {
+    let config_path = &paths.manifest;
 
-    let mut config = SkillsConfig::from_file(&config_path)?;
+    let mut config = SkillsConfig::from_file(config_path)?;
+    config.settings.add_manifest_names(&options.manifest_names);
+    recover_before_run(paths, &config)?;
+    let skills_dir = &paths.root_dir(&config, options.root.as_deref())?;
     let InstallPlan {
         archive_url,
         is_batch,
-        skills,
+        non_skill_dirs,
+        mut skills,
     } = plan;
+    if !non_skill_dirs.is_empty() {
+        if !options.continue_on_manifest_error {
+            return Err(SkillsError::NonSkillDirectories(non_skill_dirs));
+        }
+        for dir in &non_skill_dirs {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "Skipping '{}': no SKILL.md (or a name listed under [config] manifest_names) found",
+                    dir
+                ))
+            );
+        }
+    }
+    if options.nested {
+        for skill in &mut skills {
+            skill.name = nested_name(&skill.path);
+        }
+    }
+    if let Some(prefix) = &options.prefix {
+        for skill in &mut skills {
+            skill.name = prefixed_name(&skill.name, prefix);
+            if !is_safe_skill_name(&skill.name) {
+                return Err(SkillsError::InvalidSkillName(skill.name.clone()));
+            }
+        }
+    }
+    if options.update_existing_only {
+        let found = skills.len();
+        skills.retain(|skill| config.skills.contains_key(&skill.name));
+        let new = found - skills.len();
+        if new > 0 {
+            println!(
+                "Skipping {} new skill(s) not in skills.toml; install them without --update-existing-only.",
+                new
+            );
+        }
+        if skills.is_empty() {
+            println!("No installed skills to update.");
+            return Ok(0);
+        }
+    }
 
-    if is_batch {
+    if is_batch && options.interactive && !options.yes && is_interactive() {
+        println!("Found {} skills in directory:", skills.len());
+        let names: Vec<String> = skills.iter().map(|skill| skill.name.clone()).collect();
+        let Some(picked) = select_items(&names) else {
+            println!("Installation cancelled.");
+            return Ok(0);
+        };
+        if picked.is_empty() {
+            println!("No skills selected.");
+            return Ok(0);
+        }
+        skills = skills
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| picked.contains(idx))
+            .map(|(_, skill)| skill)
+            .collect();
+        println!();
+    } else if is_batch && options.confirm_each && !options.yes && is_interactive() {
+        println!("Found {} skills in directory.", skills.len());
+        let approved = confirm_each_skill(skills);
+        if approved.is_empty() {
+            println!("No skills selected.");
+            return Ok(0);
+        }
+        skills = approved;
+        println!();
+    } else if is_batch {
         println!("Found {} skills in directory:", skills.len());
         for skill in &skills {
             println!("  - {}", skill.name);
         }
         println!();
 
-        if !confirm_action_or_yes("Install all these skills?", yes) {
+        if !confirm_action_or_yes("Install all these skills?", options.yes) {
             println!("Installation cancelled.");
-            return Ok(());
+            return Ok(0);
         }
         println!();
     }
 
-    let per_skill_yes = yes || is_batch;
+    let per_skill_options = InstallOptions {
+        yes: options.yes || is_batch,
+        ..options.clone()
+    };
     let mut pending = Vec::new();
+    let mut successful = 0;
+    let mut failed = Vec::new();
+    let mut summary = InstallSummary::default();
+    let mut taken_names: Vec<String> = config.skills.keys().cloned().collect();
+    taken_names.extend(list_skill_dirs(skills_dir));
 
     for skill in skills {
-        if should_install_skill(&skill, &mut config, &skills_dir, per_skill_yes) {
+        if stop_early(options, &failed) {
+            summary.record(&skill.name, InstallStatus::Skipped);
+            continue;
+        }
+        let conflict =
+            match find_name_collision(&skill.name, taken_names.iter().map(String::as_str)) {
+                Some(existing) => Some(SkillsError::NameCollision {
+                    name: skill.name.clone(),
+                    existing: existing.to_string(),
+                }),
+                None => refuse_overwrite(&skill, &config, skills_dir, options),
+            };
+        if let Some(err) = conflict {
+            if !is_batch {
+                return Err(err);
+            }
+            eprintln!(
+                "{}",
+                style::error(&format!("Failed to install '{}': {}", skill.name, err))
+            );
+            summary.record_failure(&skill.name, &err.to_string());
+            failed.push((skill.name, err.to_string()));
+            continue;
+        }
+        taken_names.push(skill.name.clone());
+
+        if should_install_skill(&skill, &mut config, skills_dir, &per_skill_options) {
             pending.push(skill);
+        } else if config
+            .skills
+            .get(&skill.name)
+            .is_some_and(|entry| same_commit(&entry.sha, &skill.sha))
+        {
+            summary.record(&skill.name, InstallStatus::UpToDate);
+        } else {
+            summary.record(&skill.name, InstallStatus::Skipped);
         }
     }
 
-    if pending.is_empty() {
-        config.save(&config_path)?;
-        return Ok(());
+    if stop_early(options, &failed) {
+        for skill in pending.drain(..) {
+            summary.record(&skill.name, InstallStatus::Skipped);
+        }
+    }
+
+    if options.manifest_only {
+        for skill in pending.drain(..) {
+            let status = if config.skills.contains_key(&skill.name) {
+                InstallStatus::Updated
+            } else {
+                InstallStatus::Installed
+            };
+            let entry = skill_entry(&skill, options, String::new(), None);
+            config.unresolved.remove(&skill.name);
+            config.skills.insert(skill.name.clone(), entry);
+            println!(
+                "{}",
+                style::success(&format!(
+                    "Recorded skill '{}'; run `skill sync` to download it.",
+                    skill.name
+                ))
+            );
+            successful += 1;
+            summary.record(&skill.name, status);
+        }
     }
 
-    let temp_root = skills_dir.join(".install.tmp");
-    if temp_root.exists() {
-        fs::remove_dir_all(&temp_root)?;
+    if pending.is_empty() {
+        config.save(config_path)?;
+        if options.summary {
+            println!("{}", summary.to_json(&config, skills_dir));
+        }
+        if !failed.is_empty() {
+            return Err(SkillsError::BatchInstallationFailed { successful, failed });
+        }
+        return Ok(0);
     }
+
+    let temp_root = skills_dir.join(unique_temp_name("install"));
     fs::create_dir_all(&temp_root)?;
 
     println!("Downloading {} skill(s)...", pending.len());
@@ -78,39 +570,215 @@ fn install_plan(
         })
         .collect();
 
-    if let Err(e) = provider.fetch_and_extract(&archive_url, &targets) {
-        fs::remove_dir_all(&temp_root).ok();
-        return Err(e);
-    }
+    let stats = match provider.fetch_and_extract(&archive_url, &targets) {
+        Ok(stats) => stats,
+        Err(e) => {
+            discard_temp_root(&temp_root, options);
+            return Err(e);
+        }
+    };
+    let expected_files = match provider.expected_file_counts(&pending) {
+        Ok(counts) => counts,
+        Err(e) => {
+            discard_temp_root(&temp_root, options);
+            return Err(e);
+        }
+    };
 
-    let mut successful = 0;
-    let mut failed = Vec::new();
+    let mut installed = Vec::new();
+    for (idx, skill) in pending.into_iter().enumerate() {
+        if stop_early(options, &failed) {
+            summary.record(&skill.name, InstallStatus::Skipped);
+            continue;
+        }
+        if let Some(expected) = expected_files.as_ref().map(|counts| counts[idx])
+            && expected != stats.files[idx]
+        {
+            let err = SkillsError::IncompleteExtraction {
+                expected,
+                actual: stats.files[idx],
+            };
+            if !is_batch {
+                discard_temp_root(&temp_root, options);
+                return Err(err);
+            }
+            eprintln!(
+                "{}",
+                style::error(&format!("Failed to install '{}': {}", skill.name, err))
+            );
+            summary.record_failure(&skill.name, &err.to_string());
+            failed.push((skill.name, err.to_string()));
+            continue;
+        }
 
-    for skill in pending {
-        match finalize_skill_install(&skill, &mut config, &skills_dir, &temp_root) {
-            Ok(_) => successful += 1,
+        let status = if config.skills.contains_key(&skill.name) {
+            InstallStatus::Updated
+        } else {
+            InstallStatus::Installed
+        };
+        let previous_dir = config
+            .skills
+            .contains_key(&skill.name)
+            .then(|| paths.skill_dir(&config, &skill.name).ok())
+            .flatten();
+        match finalize_skill_install(
+            &skill,
+            &mut config,
+            skills_dir,
+            &temp_root,
+            previous_dir.as_deref(),
+            options,
+        ) {
+            Ok(_) => {
+                successful += 1;
+                summary.record(&skill.name, status);
+                installed.push(skill.name);
+            }
             Err(e) => {
-                eprintln!("Failed to install '{}': {}", skill.name, e);
-                failed.push(skill.name);
+                eprintln!(
+                    "{}",
+                    style::error(&format!("Failed to install '{}': {}", skill.name, e))
+                );
+                summary.record_failure(&skill.name, &e.to_string());
+                failed.push((skill.name, e.to_string()));
             }
         }
     }
 
-    fs::remove_dir_all(&temp_root).ok();
-    config.save(&config_path)?;
+    if failed.is_empty() {
+        fs::remove_dir_all(&temp_root).ok();
+    } else {
+        discard_temp_root(&temp_root, options);
+    }
+    config.save(config_path)?;
+    if options.summary {
+        println!("{}", summary.to_json(&config, skills_dir));
+    }
+
+    for name in &installed {
+        hook.run(&config.hooks, name, &skills_dir.join(name));
+    }
 
     if !failed.is_empty() {
         return Err(SkillsError::BatchInstallationFailed { successful, failed });
     }
 
-    Ok(())
+    Ok(stats.bytes)
+}
+
+/// Names of the skills installed from `url`, when there are any and every
+/// one of them is on disk, matches its checksum and was installed with the
+/// same `--at` and root as `options` ask for.
+fn cached_up_to_date(
+    url: &str,
+    config: &SkillsConfig,
+    paths: &SkillsPaths,
+    options: &InstallOptions,
+) -> Option<Vec<String>> {
+    let url = url.trim_end_matches('/');
+    let mut names = Vec::new();
+    for (name, entry) in &config.skills {
+        if entry.source_url.trim_end_matches('/') != url
+            && entry
+                .collection_url
+                .as_deref()
+                .is_none_or(|collection| collection.trim_end_matches('/') != url)
+        {
+            continue;
+        }
+        if entry.until != options.at || entry.root != options.root {
+            return None;
+        }
+        let skill_dir = paths.skill_dir(config, name).ok()?;
+        if !checksum_matches(&skill_dir, &entry.checksum).unwrap_or(false) {
+            return None;
+        }
+        names.push(name.clone());
+    }
+    (!names.is_empty()).then_some(names)
+}
+
+/// Whether the recorded `checksum` of `name` can be compared with a fresh
+/// one. When it cannot, the skill is reinstalled as if it had no checksum.
+fn verifiable_checksum(name: &str, checksum: &str) -> bool {
+    if checksum == SKIPPED_CHECKSUM {
+        return true;
+    }
+    match parse_checksum(checksum) {
+        Ok(_) => true,
+        Err(reason) => {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "Cannot verify the checksum recorded for '{}' ({}); reinstalling to record a new one",
+                    name, reason
+                ))
+            );
+            false
+        }
+    }
+}
+
+/// Whether `--fail-fast` says to skip the remaining skills.
+fn stop_early(options: &InstallOptions, failed: &[(String, String)]) -> bool {
+    options.fail_fast && !failed.is_empty()
+}
+
+/// Config key for a skill in the nested layout: its directory together with
+/// the parent directory it lives in upstream, e.g. `category/skill`.
+fn nested_name(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    parts[parts.len().saturating_sub(2)..].join("/")
+}
+
+/// Whether the config key `name` was installed with the nested layout.
+fn is_nested(name: &str) -> bool {
+    name.contains('/')
+}
+
+/// Names of the skill directories currently on disk, skipping temp dirs.
+fn list_skill_dirs(skills_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(skills_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect()
+}
+
+/// The error to fail `skill` with under `--no-overwrite`, when installing it
+/// would replace a skill from another source or an unmanaged directory.
+fn refuse_overwrite(
+    skill: &ResolvedSkill,
+    config: &SkillsConfig,
+    skills_dir: &Path,
+    options: &InstallOptions,
+) -> Option<SkillsError> {
+    if !options.no_overwrite {
+        return None;
+    }
+    let source = match config.skills.get(&skill.name) {
+        Some(existing) if existing.source_url != skill.source_url => {
+            Some(existing.source_url.clone())
+        }
+        Some(_) => return None,
+        None if skills_dir.join(&skill.name).exists() => None,
+        None => return None,
+    };
+    Some(SkillsError::WouldOverwrite {
+        name: skill.name.clone(),
+        source,
+    })
 }
 
 fn should_install_skill(
     skill: &ResolvedSkill,
     config: &mut SkillsConfig,
     skills_dir: &Path,
-    yes: bool,
+    options: &InstallOptions,
 ) -> bool {
     let skill_dir = skills_dir.join(&skill.name);
 
@@ -124,25 +792,59 @@ fn should_install_skill(
         println!("  Current: {}", existing.source_url);
         println!("  New:     {}", skill.source_url);
 
-        if !confirm_action_or_yes("Continue to install with new source?", yes) {
+        if !confirm_action_or_yes("Continue to install with new source?", options.yes) {
             println!("Installation cancelled.");
             return false;
         }
     }
 
+    let unchanged = config
+        .skills
+        .get(&skill.name)
+        .filter(|existing| {
+            existing.root == options.root && skill_dir.exists() && !existing.is_pending()
+        })
+        .filter(|existing| verifiable_checksum(&skill.name, &existing.checksum))
+        .and_then(|existing| checksum_matches(&skill_dir, &existing.checksum).ok());
+    if unchanged == Some(false) && !overwrite_local_changes(&skill.name, options) {
+        return false;
+    }
+
     if let Some(existing) = config.skills.get(&skill.name)
-        && skill_dir.exists()
-        && let Ok(checksum) = calculate_checksum(&skill_dir)
-        && checksum == existing.checksum
+        && unchanged == Some(true)
     {
-        if skill.sha == existing.sha {
+        if same_commit(&skill.sha, &existing.sha) {
+            if options.refresh {
+                println!(
+                    "Skill '{}' is already up to date. Refreshing...",
+                    skill.name
+                );
+                return true;
+            }
             if let Some(entry) = config.skills.get_mut(&skill.name) {
+                // Upgrade abbreviated SHAs recorded by older versions.
+                if entry.sha != skill.sha {
+                    entry.sha = skill.sha.clone();
+                }
                 if entry.source_url != skill.source_url {
                     entry.source_url = skill.source_url.clone();
                 }
                 if entry.collection_url != skill.collection_url {
                     entry.collection_url = skill.collection_url.clone();
                 }
+                if entry.until != options.at {
+                    entry.until = options.at.clone();
+                }
+                if entry.default_branch != skill.default_branch {
+                    entry.default_branch = skill.default_branch.clone();
+                }
+                if entry.ref_name != skill.ref_name {
+                    entry.ref_name = skill.ref_name.clone();
+                }
+                entry.archive_format = options.stored_archive_format();
+                entry.prefer_ref = options.prefer_ref;
+                entry.ref_kind = options.ref_kind;
+                entry.no_subdir = options.no_subdir;
             }
             println!(
                 "Skill '{}' is already installed and up to date.",
@@ -160,39 +862,536 @@ fn should_install_skill(
     true
 }
 
+/// Ask whether to install each of `skills` and return the approved ones.
+/// Quitting skips the skills not asked about yet.
+fn confirm_each_skill(skills: Vec<ResolvedSkill>) -> Vec<ResolvedSkill> {
+    let mut approved = Vec::new();
+    for skill in skills {
+        println!();
+        println!("  {} ({})", skill.name, skill.path);
+        match confirm_or_quit(&format!("Install '{}'?", skill.name)) {
+            Some(true) => approved.push(skill),
+            Some(false) => {}
+            None => break,
+        }
+    }
+    approved
+}
+
+/// Whether to overwrite the edited files of `name`, as
+/// `options.local_changes` says.
+fn overwrite_local_changes(name: &str, options: &InstallOptions) -> bool {
+    match options.local_changes {
+        LocalChanges::Discard => {
+            println!("Skill '{}' has local changes; discarding them.", name);
+            true
+        }
+        LocalChanges::Keep => {
+            println!(
+                "{}",
+                style::warning(&format!(
+                    "Skill '{}' has local changes; leaving it untouched.",
+                    name
+                ))
+            );
+            false
+        }
+        LocalChanges::Prompt if !options.yes && is_interactive() => {
+            println!(
+                "{}",
+                style::warning(&format!("Skill '{}' has local changes.", name))
+            );
+            let overwrite = confirm_action("Overwrite local changes?");
+            if !overwrite {
+                println!("Leaving '{}' untouched.", name);
+            }
+            overwrite
+        }
+        LocalChanges::Prompt => {
+            println!(
+                "{}",
+                style::warning(&format!(
+                    "Skill '{}' has local changes; overwriting them.",
+                    name
+                ))
+            );
+            true
+        }
+    }
+}
+
+/// The entry recording `skill` as installed with `options`.
+fn skill_entry(
+    skill: &ResolvedSkill,
+    options: &InstallOptions,
+    checksum: String,
+    tree_mtime: Option<u64>,
+) -> SkillEntry {
+    SkillEntry {
+        source_url: skill.source_url.clone(),
+        collection_url: skill.collection_url.clone(),
+        slug: skill.slug.clone(),
+        sha: skill.sha.clone(),
+        path: skill.path.clone(),
+        checksum,
+        until: options.at.clone(),
+        default_branch: skill.default_branch.clone(),
+        archive_format: options.stored_archive_format(),
+        root: options.root.clone(),
+        prefer_ref: options.prefer_ref,
+        prefix: options.prefix.clone(),
+        ref_name: skill.ref_name.clone(),
+        ref_kind: options.ref_kind,
+        no_subdir: options.no_subdir,
+        updated_at: Some(current_timestamp()),
+        tree_mtime,
+    }
+}
+
+/// Remove the temp directory of a failed download, or with `--keep-temp`
+/// leave it for inspection and say where it is.
+fn discard_temp_root(temp_root: &Path, options: &InstallOptions) {
+    if options.keep_temp {
+        eprintln!(
+            "{}",
+            style::warning_err(&format!(
+                "Kept the download in {} (--keep-temp); remove it when done.",
+                temp_root.display()
+            ))
+        );
+    } else {
+        fs::remove_dir_all(temp_root).ok();
+    }
+}
+
 fn finalize_skill_install(
     skill: &ResolvedSkill,
     config: &mut SkillsConfig,
     skills_dir: &Path,
     temp_root: &Path,
+    previous_dir: Option<&Path>,
+    options: &InstallOptions,
 ) -> SkillsResult<()> {
     let temp_dir = temp_root.join(&skill.name);
     let skill_dir = skills_dir.join(&skill.name);
 
-    if let Err(e) = ensure_skill_manifest(&temp_dir) {
-        fs::remove_dir_all(&temp_dir).ok();
+    if !options.no_subdir
+        && let Err(e) = ensure_skill_manifest(&temp_dir, &config.settings.manifest_names)
+    {
+        if !options.keep_temp {
+            fs::remove_dir_all(&temp_dir).ok();
+        }
         return Err(e);
     }
+    let missing = missing_front_matter(
+        &temp_dir,
+        &config.settings.manifest_names,
+        &config
+            .settings
+            .required_front_matter(options.strict_manifest),
+    );
+    if !missing.is_empty() {
+        if !options.keep_temp {
+            fs::remove_dir_all(&temp_dir).ok();
+        }
+        return Err(SkillsError::MissingFrontMatter {
+            name: skill.name.clone(),
+            missing,
+        });
+    }
+    if options.verify_signatures
+        && let Err(reason) = verify_skill_signature(&temp_dir, &config.settings.trusted_keys)
+    {
+        if !options.keep_temp {
+            fs::remove_dir_all(&temp_dir).ok();
+        }
+        return Err(SkillsError::SignatureVerificationFailed {
+            name: skill.name.clone(),
+            reason,
+        });
+    }
 
     if skill_dir.exists() {
         fs::remove_dir_all(&skill_dir)?;
     }
+    if let Some(parent) = skill_dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
 
-    fs::rename(&temp_dir, &skill_dir)?;
+    move_dir(&temp_dir, &skill_dir)?;
 
-    let checksum = calculate_checksum(&skill_dir)?;
+    // Moving a skill to another root leaves its old directory behind.
+    if let Some(previous_dir) = previous_dir
+        && previous_dir != skill_dir
+    {
+        fs::remove_dir_all(previous_dir).ok();
+    }
 
-    let entry = SkillEntry {
-        source_url: skill.source_url.clone(),
-        collection_url: skill.collection_url.clone(),
-        slug: skill.slug.clone(),
-        sha: skill.sha.clone(),
-        path: skill.path.clone(),
-        checksum,
+    let algorithm = options
+        .checksum_algorithm
+        .or_else(|| {
+            config
+                .skills
+                .get(&skill.name)
+                .and_then(|existing| ChecksumAlgorithm::of_checksum(&existing.checksum))
+        })
+        .unwrap_or_default();
+    let (checksum, tree_mtime) = if options.skip_checksum {
+        (SKIPPED_CHECKSUM.to_string(), None)
+    } else {
+        (
+            calculate_checksum(&skill_dir, algorithm)?,
+            cacheable_tree_mtime(&skill_dir),
+        )
     };
 
+    let entry = skill_entry(skill, options, checksum, tree_mtime);
+    config.unresolved.remove(&skill.name);
     config.skills.insert(skill.name.clone(), entry);
-    println!("Successfully installed skill '{}'.", skill.name);
+    println!(
+        "{}",
+        style::success(&format!("Successfully installed skill '{}'.", skill.name))
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_list() {
+        let content = "# team skills\n\nhttps://github.com/a/b/tree/main/one\n  https://github.com/a/b/tree/main/two  # pinned later\n#https://github.com/a/b/tree/main/off\n";
+
+        assert_eq!(
+            parse_url_list(content),
+            vec![
+                (3, "https://github.com/a/b/tree/main/one"),
+                (4, "https://github.com/a/b/tree/main/two"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_from_file_fail_fast() {
+        let temp_dir = std::env::temp_dir().join("skills_test_install_fail_fast");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let list = temp_dir.join("skills.txt");
+        fs::write(&list, "https://example.com/one\nhttps://example.com/two\n").unwrap();
+        let paths = SkillsPaths::from_base_dir(&temp_dir);
+        let registry = ProviderRegistry::new(Vec::new());
+
+        let failures = |fail_fast| {
+            let options = InstallOptions {
+                fail_fast,
+                ..Default::default()
+            };
+            match install_from_file(&list, &paths, &options, &registry) {
+                Err(SkillsError::BatchInstallationFailed { failed, .. }) => failed.len(),
+                other => panic!("unexpected result: {other:?}"),
+            }
+        };
+        assert_eq!(failures(false), 2);
+        assert_eq!(failures(true), 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_cached_up_to_date() {
+        let temp_dir = std::env::temp_dir().join("skills_test_prefer_cache");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_base_dir(&temp_dir);
+        let skill_dir = paths.skills_dir.join("demo");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), b"# Demo").unwrap();
+
+        let url = "https://github.com/owner/repo/tree/main/skills/demo";
+        let mut entry = SkillEntry::local(
+            calculate_checksum(&skill_dir, ChecksumAlgorithm::default()).unwrap(),
+            None,
+        );
+        entry.source_url = url.to_string();
+        entry.collection_url = Some("https://github.com/owner/repo/tree/main/skills".to_string());
+        let mut config = SkillsConfig::default();
+        config.skills.insert("demo".to_string(), entry);
+        let options = InstallOptions::default();
+
+        assert_eq!(
+            cached_up_to_date(url, &config, &paths, &options),
+            Some(vec!["demo".to_string()])
+        );
+        assert!(
+            cached_up_to_date(
+                "https://github.com/owner/repo/tree/main/skills/",
+                &config,
+                &paths,
+                &options
+            )
+            .is_some()
+        );
+        assert_eq!(
+            cached_up_to_date(
+                "https://github.com/owner/repo/tree/main/other",
+                &config,
+                &paths,
+                &options
+            ),
+            None
+        );
+
+        // A source dropped from allowed_sources is refused even when cached.
+        config
+            .settings
+            .set("allowed_sources", "github.com/other/*")
+            .unwrap();
+        config.save(&paths.manifest).unwrap();
+        let prefer_cache = InstallOptions {
+            prefer_cache: true,
+            ..Default::default()
+        };
+        let registry = ProviderRegistry::new(Vec::new());
+        assert!(matches!(
+            install_skill_counted(
+                url,
+                &paths,
+                &prefer_cache,
+                &registry,
+                &mut Default::default()
+            ),
+            Err(SkillsError::SourceNotAllowed { .. })
+        ));
+
+        fs::write(skill_dir.join("SKILL.md"), b"# Changed").unwrap();
+        assert_eq!(cached_up_to_date(url, &config, &paths, &options), None);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_local_changes() {
+        let temp_dir = std::env::temp_dir().join("skills_test_local_changes");
+        fs::remove_dir_all(&temp_dir).ok();
+        let skills_dir = temp_dir.join("skills");
+        let skill_dir = skills_dir.join("demo");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), b"# Demo").unwrap();
+
+        let skill = ResolvedSkill {
+            name: "demo".to_string(),
+            source_url: "https://github.com/owner/repo/tree/main/demo".to_string(),
+            collection_url: None,
+            slug: "owner/repo".to_string(),
+            sha: "def456".to_string(),
+            path: "demo".to_string(),
+            default_branch: None,
+            ref_name: None,
+        };
+        let mut entry = SkillEntry::local(
+            calculate_checksum(&skill_dir, ChecksumAlgorithm::default()).unwrap(),
+            None,
+        );
+        entry.source_url = skill.source_url.clone();
+        entry.sha = "abc123".to_string();
+        let mut config = SkillsConfig::default();
+        config.skills.insert("demo".to_string(), entry);
+        fs::write(skill_dir.join("SKILL.md"), b"# Edited").unwrap();
+
+        let keep = InstallOptions {
+            local_changes: LocalChanges::Keep,
+            ..Default::default()
+        };
+        assert!(!should_install_skill(
+            &skill,
+            &mut config,
+            &skills_dir,
+            &keep
+        ));
+        let discard = InstallOptions {
+            local_changes: LocalChanges::Discard,
+            ..Default::default()
+        };
+        assert!(should_install_skill(
+            &skill,
+            &mut config,
+            &skills_dir,
+            &discard
+        ));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_refuse_overwrite() {
+        let temp_dir = std::env::temp_dir().join("skills_test_no_overwrite");
+        fs::remove_dir_all(&temp_dir).ok();
+        let skills_dir = temp_dir.join("skills");
+        fs::create_dir_all(skills_dir.join("manual")).unwrap();
+
+        let skill = |name: &str| ResolvedSkill {
+            name: name.to_string(),
+            source_url: format!("https://github.com/owner/repo/tree/main/{}", name),
+            collection_url: None,
+            slug: "owner/repo".to_string(),
+            sha: "abc123".to_string(),
+            path: name.to_string(),
+            default_branch: None,
+            ref_name: None,
+        };
+        let mut entry = SkillEntry::local("sha256:abc".to_string(), None);
+        entry.source_url = "https://github.com/other/repo/tree/main/demo".to_string();
+        let mut config = SkillsConfig::default();
+        config.skills.insert("demo".to_string(), entry);
+        let options = InstallOptions {
+            no_overwrite: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            refuse_overwrite(&skill("demo"), &config, &skills_dir, &options),
+            Some(SkillsError::WouldOverwrite {
+                source: Some(_),
+                ..
+            })
+        ));
+        assert!(matches!(
+            refuse_overwrite(&skill("manual"), &config, &skills_dir, &options),
+            Some(SkillsError::WouldOverwrite { source: None, .. })
+        ));
+        assert!(refuse_overwrite(&skill("fresh"), &config, &skills_dir, &options).is_none());
+        assert!(
+            refuse_overwrite(
+                &skill("demo"),
+                &config,
+                &skills_dir,
+                &InstallOptions::default()
+            )
+            .is_none()
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_summary_json() {
+        let mut config = SkillsConfig::default();
+        config.skills.insert(
+            "demo".to_string(),
+            SkillEntry::local("sha256:abc".to_string(), None),
+        );
+        let mut summary = InstallSummary::default();
+        summary.record("demo", InstallStatus::UpToDate);
+        summary.record_failure("broken", "Path not found");
+
+        let json = summary.to_json(&config, Path::new("skills"));
+        assert_eq!(json["skills"][0]["status"], "up_to_date");
+        assert_eq!(json["skills"][0]["checksum"], "sha256:abc");
+        assert_eq!(json["skills"][1]["status"], "failed");
+        assert_eq!(json["skills"][1]["error"], "Path not found");
+        assert!(json["skills"][1].get("sha").is_none());
+        assert_eq!(json["totals"]["up_to_date"], 1);
+        assert_eq!(json["totals"]["failed"], 1);
+        assert_eq!(json["totals"]["installed"], 0);
+    }
+
+    #[test]
+    fn test_nested_name() {
+        assert_eq!(nested_name("skills/writing/editor"), "writing/editor");
+        assert_eq!(nested_name("writing/editor/"), "writing/editor");
+        assert_eq!(nested_name("editor"), "editor");
+    }
+
+    #[test]
+    fn test_options_for_manifest_entry() {
+        let entry = ManifestEntry {
+            source_url: "https://github.com/owner/repo/tree/v1/skills/editor".to_string(),
+            collection_url: None,
+            until: None,
+            archive_format: Some(ArchiveFormat::Zip),
+            root: Some("team".to_string()),
+            prefer_ref: None,
+            ref_kind: Some(RefKind::Tag),
+            prefix: Some("acme-".to_string()),
+            no_subdir: true,
+        };
+        let options = InstallOptions::for_manifest_entry("writing/acme-editor", &entry);
+        assert_eq!(options.ref_kind, Some(RefKind::Tag));
+        assert_eq!(options.archive_format, ArchiveFormat::Zip);
+        assert_eq!(options.root.as_deref(), Some("team"));
+        assert_eq!(options.prefix.as_deref(), Some("acme-"));
+        assert!(options.nested && options.no_subdir);
+    }
+
+    #[test]
+    fn test_finalize_rejects_extraction_without_manifest() {
+        let temp_dir = std::env::temp_dir().join("skills_test_finalize_missing_manifest");
+        fs::remove_dir_all(&temp_dir).ok();
+        let skills_dir = temp_dir.join("skills");
+        let temp_root = skills_dir.join(".install.tmp");
+        fs::create_dir_all(temp_root.join("demo")).unwrap();
+        fs::write(temp_root.join("demo/README.md"), b"not a skill").unwrap();
+
+        let skill = ResolvedSkill {
+            name: "demo".to_string(),
+            source_url: "https://github.com/owner/repo/tree/main/demo".to_string(),
+            collection_url: None,
+            slug: "owner/repo".to_string(),
+            sha: "abc123".to_string(),
+            path: "demo".to_string(),
+            default_branch: None,
+            ref_name: None,
+        };
+        let mut config = SkillsConfig::default();
+
+        let keep_temp = InstallOptions {
+            keep_temp: true,
+            ..Default::default()
+        };
+        let result = finalize_skill_install(
+            &skill,
+            &mut config,
+            &skills_dir,
+            &temp_root,
+            None,
+            &keep_temp,
+        );
+        assert!(matches!(result, Err(SkillsError::MissingSkillManifest)));
+        assert!(temp_root.join("demo/README.md").exists());
+
+        let result = finalize_skill_install(
+            &skill,
+            &mut config,
+            &skills_dir,
+            &temp_root,
+            None,
+            &InstallOptions::default(),
+        );
+
+        assert!(matches!(result, Err(SkillsError::MissingSkillManifest)));
+        assert!(!temp_root.join("demo").exists());
+        assert!(!skills_dir.join("demo").exists());
+        assert!(config.skills.is_empty());
+
+        fs::create_dir_all(temp_root.join("demo")).unwrap();
+        fs::write(temp_root.join("demo/README.md"), b"not a skill").unwrap();
+        let no_subdir = InstallOptions {
+            no_subdir: true,
+            ..Default::default()
+        };
+        finalize_skill_install(
+            &skill,
+            &mut config,
+            &skills_dir,
+            &temp_root,
+            None,
+            &no_subdir,
+        )
+        .unwrap();
+        assert!(skills_dir.join("demo/README.md").exists());
+        assert!(config.skills["demo"].no_subdir);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -1,11 +1,54 @@
-use crate::{errors::SkillsResult, models::SkillsConfig};
-use std::path::Path;
+use crate::{
+    errors::SkillsResult,
+    models::{SkillEntry, SkillsConfig},
+    providers::ProviderRegistry,
+    utils::{dir_size, format_bytes, same_commit},
+};
+use clap::ValueEnum;
+use std::{cmp::Reverse, collections::BTreeSet};
 
-pub fn list_skills(base_dir: &Path) -> SkillsResult<()> {
-    let config_path = base_dir.join("skills.toml");
-    let config = SkillsConfig::from_file(&config_path)?;
+use super::{
+    SkillsPaths,
+    outdated::{short_sha, upstream_commit},
+};
 
-    if config.skills.is_empty() {
+/// Options controlling a single `list` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Show the file count and disk size of each skill.
+    pub sizes: bool,
+    /// Order of the skills within each root.
+    pub sort: ListSort,
+    /// Resolve each skill's upstream commit and show whether it is behind.
+    pub outdated: bool,
+    /// Only show skills whose name, repository or path contains this text,
+    /// ignoring case.
+    pub filter: Option<String>,
+}
+
+/// How `list` orders skills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ListSort {
+    /// Alphabetically by name.
+    #[default]
+    Name,
+    /// Largest first.
+    Size,
+    /// Most recently updated first.
+    Updated,
+    /// By repository, keeping skills from the same repository together.
+    Slug,
+}
+
+pub fn list_skills(
+    paths: &SkillsPaths,
+    options: &ListOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let config = SkillsConfig::from_file(config_path)?;
+
+    if config.skills.is_empty() && config.unresolved.is_empty() {
         println!("No skills installed.");
         return Ok(());
     }
@@ -13,16 +56,181 @@ pub fn list_skills(base_dir: &Path) -> SkillsResult<()> {
     println!("Installed skills:");
     println!();
 
-    for (name, entry) in &config.skills {
-        println!("  {}", name);
-        println!("    Source: {}", entry.source_url);
-        println!("    Repo:   {}", entry.slug);
-        println!("    SHA:    {}", &entry.sha[..7.min(entry.sha.len())]);
-        println!("    Path:   {}", entry.path);
-        println!();
+    let grouped = !config.roots.is_empty();
+    let mut roots: BTreeSet<Option<&str>> = config
+        .roots
+        .keys()
+        .map(|root| Some(root.as_str()))
+        .collect();
+    roots.insert(None);
+    roots.extend(config.skills.keys().map(|name| config.root(name)));
+    roots.extend(config.unresolved.keys().map(|name| config.root(name)));
+
+    let show_sizes = options.sizes || options.sort == ListSort::Size;
+    let filter = options.filter.as_deref().map(str::to_lowercase);
+    let mut total_bytes = 0;
+    let mut shown = 0;
+
+    for root in roots {
+        let mut skills: Vec<_> = config
+            .skills
+            .iter()
+            .filter(|(_, entry)| entry.root.as_deref() == root)
+            .filter(|(name, entry)| {
+                filter
+                    .as_deref()
+                    .is_none_or(|filter| matches_filter(name, entry, filter))
+            })
+            .map(|(name, entry)| {
+                let size = show_sizes
+                    .then(|| paths.skill_dir(&config, name).ok())
+                    .flatten()
+                    .and_then(|dir| dir_size(&dir).ok());
+                (name, entry, size)
+            })
+            .collect();
+        match options.sort {
+            ListSort::Name => {}
+            ListSort::Size => {
+                skills.sort_by_key(|(_, _, size)| Reverse(size.map(|(_, bytes)| bytes)))
+            }
+            ListSort::Updated => {
+                skills.sort_by_key(|(_, entry, _)| Reverse(entry.updated_at.clone()))
+            }
+            ListSort::Slug => skills.sort_by(|(_, a, _), (_, b, _)| a.slug.cmp(&b.slug)),
+        }
+        let unresolved: Vec<_> = config
+            .unresolved
+            .iter()
+            .filter(|(_, manifest)| manifest.root.as_deref() == root)
+            .filter(|(name, manifest)| {
+                filter.as_deref().is_none_or(|filter| {
+                    name.to_lowercase().contains(filter)
+                        || manifest.source_url.to_lowercase().contains(filter)
+                })
+            })
+            .collect();
+        shown += skills.len() + unresolved.len();
+
+        if grouped {
+            if skills.is_empty() && unresolved.is_empty() {
+                continue;
+            }
+            let dir = match paths.root_dir(&config, root) {
+                Ok(dir) => dir.display().to_string(),
+                Err(_) => "(not declared under [roots])".to_string(),
+            };
+            println!("[{}] {}", root.unwrap_or("default"), dir);
+            println!();
+        }
+
+        for (name, entry, size) in skills {
+            println!("  {}", name);
+            if let Some(prefix) = &entry.prefix {
+                println!(
+                    "    Upstream name: {} (prefix '{}')",
+                    entry.upstream_name(name),
+                    prefix
+                );
+            }
+            println!("    Source: {}", entry.source_url);
+            println!("    Repo:   {}", entry.slug);
+            let sha = short_sha(&entry.sha);
+            match &entry.ref_name {
+                Some(ref_name) => println!("    Tracking: {} @ {}", ref_name, sha),
+                None => println!("    SHA:    {}", sha),
+            }
+            println!("    Path:   {}", entry.path);
+            if let Some(updated_at) = &entry.updated_at {
+                println!("    Updated: {}", updated_at);
+            }
+            if options.outdated && !entry.is_local() {
+                let upstream = upstream_commit(name, entry, registry);
+                println!(
+                    "    Status: {}",
+                    drift_status(&entry.sha, upstream.map(|commit| commit.sha).as_deref())
+                );
+            }
+            if show_sizes {
+                match size {
+                    Some((files, bytes)) => {
+                        total_bytes += bytes;
+                        println!("    Size:   {} in {} file(s)", format_bytes(bytes), files);
+                    }
+                    None => println!("    Size:   - (not on disk)"),
+                }
+            }
+            println!();
+        }
+
+        for (name, manifest) in unresolved {
+            println!("  {}", name);
+            println!("    Source: {}", manifest.source_url);
+            println!("    Status: not resolved yet (run `skill sync`)");
+            println!();
+        }
     }
 
-    println!("Total: {} skill(s)", config.skills.len());
+    let total = config.skills.len() + config.unresolved.len();
+    let count = match &options.filter {
+        Some(filter) if shown == 0 => {
+            println!("No skills match '{}'.", filter);
+            return Ok(());
+        }
+        Some(_) => format!("{} of {}", shown, total),
+        None => total.to_string(),
+    };
+    if show_sizes {
+        println!("Total: {} skill(s), {}", count, format_bytes(total_bytes));
+    } else {
+        println!("Total: {} skill(s)", count);
+    }
 
     Ok(())
 }
+
+/// Whether the lowercase `filter` occurs in the name, repository or path of
+/// the skill, ignoring case.
+fn matches_filter(name: &str, entry: &SkillEntry, filter: &str) -> bool {
+    [name, &entry.slug, &entry.path]
+        .iter()
+        .any(|field| field.to_lowercase().contains(filter))
+}
+
+/// How the locked commit `sha` compares with the `upstream` commit, which is
+/// `None` when it could not be resolved.
+fn drift_status(sha: &str, upstream: Option<&str>) -> String {
+    match upstream {
+        Some(upstream) if same_commit(upstream, sha) => "up to date".to_string(),
+        Some(upstream) => format!("outdated ({} -> {})", short_sha(sha), short_sha(upstream)),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_status() {
+        let sha = "a".repeat(40);
+        let newer = "b".repeat(40);
+        assert_eq!(drift_status(&sha, Some(&sha)), "up to date");
+        assert_eq!(
+            drift_status(&sha, Some(&newer)),
+            "outdated (aaaaaaa -> bbbbbbb)"
+        );
+        assert_eq!(drift_status(&sha, None), "unknown");
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let mut entry = SkillEntry::local("sha256:abc".to_string(), None);
+        entry.slug = "Acme/Skills".to_string();
+        entry.path = "writing/editor".to_string();
+        assert!(matches_filter("editor", &entry, "edit"));
+        assert!(matches_filter("reviewer", &entry, "acme/skills"));
+        assert!(matches_filter("reviewer", &entry, "writing"));
+        assert!(!matches_filter("reviewer", &entry, "frontend"));
+    }
+}
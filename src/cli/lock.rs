@@ -0,0 +1,158 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    style,
+};
+use std::{
+    fs::{self, File, TryLockError},
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long to wait for another process to release the manifest.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Exclusive hold on a `skills.toml` for the duration of a mutating command,
+/// so concurrent runs cannot interleave their load/modify/save cycles.
+///
+/// The lock is an OS advisory lock on a `skills.toml.lock` file next to the
+/// manifest, which records the holder's PID. The file is removed when the
+/// guard is dropped (including on panic). If the process is killed instead,
+/// the OS releases the lock, and the file it leaves behind is taken over by
+/// the next run.
+#[derive(Debug)]
+pub struct ManifestLock {
+    path: PathBuf,
+    file: File,
+}
+
+impl ManifestLock {
+    /// Lock the manifest at `manifest`, waiting a while for another process
+    /// to finish before giving up with [`SkillsError::ManifestLocked`].
+    pub fn acquire(manifest: &Path) -> SkillsResult<Self> {
+        Self::acquire_within(manifest, LOCK_TIMEOUT)
+    }
+
+    fn acquire_within(manifest: &Path, timeout: Duration) -> SkillsResult<Self> {
+        let path = lock_file_path(manifest);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let started = Instant::now();
+        let mut announced = false;
+        loop {
+            let mut file = fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                // The previous holder may have removed the file between our
+                // open and lock; then the lock is on a stale file.
+                Ok(()) if !is_same_file(&file, &path) => continue,
+                Ok(()) => {
+                    file.set_len(0).ok();
+                    writeln!(file, "{}", std::process::id()).ok();
+                    return Ok(Self { path, file });
+                }
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(SkillsError::ManifestLocked {
+                    lock: path.display().to_string(),
+                });
+            }
+            if !announced {
+                eprintln!(
+                    "{}",
+                    style::warning_err("Waiting for another skills-man process to finish...")
+                );
+                announced = true;
+            }
+            thread::sleep(LOCK_POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for ManifestLock {
+    fn drop(&mut self) {
+        // Remove the file while still holding the lock, so no one can lock
+        // it in between and then lose it.
+        fs::remove_file(&self.path).ok();
+        self.file.unlock().ok();
+    }
+}
+
+/// Whether `path` still names the open `file`.
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(open), Ok(named)) => open.dev() == named.dev() && open.ino() == named.ino(),
+        _ => false,
+    }
+}
+
+/// Whether `path` still names the open `file`. Without inode numbers to
+/// compare, it only checks that the file still exists.
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+fn lock_file_path(manifest: &Path) -> PathBuf {
+    let mut name = manifest.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    manifest.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_lock_is_exclusive() {
+        let temp_dir = std::env::temp_dir().join("skills_test_manifest_lock");
+        fs::remove_dir_all(&temp_dir).ok();
+        let manifest = temp_dir.join("skills.toml");
+
+        let lock = ManifestLock::acquire_within(&manifest, Duration::ZERO).unwrap();
+        assert!(temp_dir.join("skills.toml.lock").exists());
+        assert!(matches!(
+            ManifestLock::acquire_within(&manifest, Duration::ZERO),
+            Err(SkillsError::ManifestLocked { .. })
+        ));
+
+        drop(lock);
+        assert!(!temp_dir.join("skills.toml.lock").exists());
+        ManifestLock::acquire_within(&manifest, Duration::ZERO).unwrap();
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_lock_takes_over_file_left_by_dead_process() {
+        let temp_dir = std::env::temp_dir().join("skills_test_manifest_lock_stale");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let manifest = temp_dir.join("skills.toml");
+        // What a killed process leaves behind: the file, but no OS lock.
+        fs::write(temp_dir.join("skills.toml.lock"), "4194304\n").unwrap();
+
+        let lock = ManifestLock::acquire_within(&manifest, Duration::ZERO).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("skills.toml.lock")).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+        drop(lock);
+        assert!(!temp_dir.join("skills.toml.lock").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
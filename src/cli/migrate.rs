@@ -0,0 +1,178 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::{SCHEMA_VERSION, SkillEntry, SkillsConfig, lock_path},
+    providers::{ProviderRegistry, ResolveOptions},
+    style,
+    utils::same_commit,
+};
+use std::fs;
+use toml_edit::DocumentMut;
+
+use super::SkillsPaths;
+
+/// Resolved fields that versions before `skills.lock` wrote into `skills.toml`.
+const LEGACY_LOCK_FIELDS: [&str; 4] = ["slug", "path", "sha", "checksum"];
+
+/// Rewrite `skills.toml` and `skills.lock` in the current format and report
+/// each change. Abbreviated commit SHAs recorded by older versions are
+/// expanded by resolving them upstream. Running it again changes nothing.
+pub fn migrate(paths: &SkillsPaths, registry: &ProviderRegistry) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    if !config_path.exists() {
+        println!("No skills.toml to migrate");
+        return Ok(());
+    }
+    let mut config = SkillsConfig::from_file(config_path)?;
+
+    let content = fs::read_to_string(config_path)?;
+    let mut changes = format_changes(&content, lock_path(config_path).exists());
+
+    let mut failed = 0;
+    for (name, entry) in config.skills.iter_mut() {
+        if entry.is_local() || !is_abbreviated(&entry.sha) {
+            continue;
+        }
+        match resolve_full_sha(entry, &config.settings.manifest_names, registry) {
+            Ok(sha) => {
+                changes.push(format!(
+                    "{}: expanded commit {} to {}",
+                    name, entry.sha, sha
+                ));
+                entry.sha = sha;
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style::warning_err(&format!(
+                        "[{}] Could not expand commit {}: {}",
+                        name, entry.sha, e
+                    ))
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    if changes.is_empty() {
+        if failed == 0 {
+            println!("skills.toml is already in the current format");
+        }
+        return Ok(());
+    }
+
+    config.save(config_path)?;
+    println!("Migrated {}:", config_path.display());
+    for change in &changes {
+        println!("  - {}", change);
+    }
+    if failed > 0 {
+        println!(
+            "Run `skill migrate` again to retry the {} failed skill(s).",
+            failed
+        );
+    }
+    Ok(())
+}
+
+/// Changes needed to bring the manifest `content` to the current format,
+/// where `has_lock` says whether `skills.lock` already exists.
+fn format_changes(content: &str, has_lock: bool) -> Vec<String> {
+    let Ok(doc) = content.parse::<DocumentMut>() else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+
+    if !doc.contains_key("version") {
+        changes.push(format!("recorded schema version {}", SCHEMA_VERSION));
+    }
+
+    let legacy = doc
+        .get("skills")
+        .and_then(|skills| skills.as_table_like())
+        .map(|skills| {
+            skills
+                .iter()
+                .filter(|(_, entry)| {
+                    entry.as_table_like().is_some_and(|entry| {
+                        LEGACY_LOCK_FIELDS
+                            .iter()
+                            .any(|field| entry.contains_key(field))
+                    })
+                })
+                .count()
+        })
+        .unwrap_or_default();
+    if legacy > 0 {
+        changes.push(if has_lock {
+            format!("dropped stale resolved fields of {} skill(s)", legacy)
+        } else {
+            format!(
+                "moved resolved fields of {} skill(s) to skills.lock",
+                legacy
+            )
+        });
+    }
+
+    changes
+}
+
+/// Whether `sha` is a shortened commit SHA rather than a full one.
+fn is_abbreviated(sha: &str) -> bool {
+    sha.len() < 40 && sha.bytes().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Resolve the commit `entry.sha` abbreviates to its full SHA.
+fn resolve_full_sha(
+    entry: &SkillEntry,
+    manifest_names: &[String],
+    registry: &ProviderRegistry,
+) -> SkillsResult<String> {
+    let provider = registry.get(&entry.source_url)?;
+    let url = provider.source_url_with_ref(entry, &entry.sha)?;
+    let options = ResolveOptions {
+        archive_format: entry.archive_format.unwrap_or_default(),
+        manifest_names: manifest_names.to_vec(),
+        ref_name: Some(entry.sha.clone()),
+        ..Default::default()
+    };
+    let plan = provider.resolve_install_plan(&url, &options)?;
+    plan.skills
+        .into_iter()
+        .map(|skill| skill.sha)
+        .find(|sha| same_commit(sha, &entry.sha))
+        .ok_or(SkillsError::NotFound { url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_changes() {
+        let legacy = r#"
+[skills.demo]
+source_url = "https://github.com/owner/repo/tree/main/demo"
+slug = "owner/repo"
+path = "demo"
+sha = "abc1234"
+checksum = "sha256:abc"
+"#;
+        assert_eq!(
+            format_changes(legacy, false),
+            vec![
+                format!("recorded schema version {}", SCHEMA_VERSION),
+                "moved resolved fields of 1 skill(s) to skills.lock".to_string(),
+            ]
+        );
+
+        let current = format!(
+            "version = {}\n\n[skills.demo]\nsource_url = \"https://github.com/owner/repo/tree/main/demo\"\n",
+            SCHEMA_VERSION
+        );
+        assert!(format_changes(&current, true).is_empty());
+
+        assert!(is_abbreviated("abc1234"));
+        assert!(!is_abbreviated(&"a".repeat(40)));
+        assert!(!is_abbreviated("main"));
+    }
+}
@@ -1,12 +1,146 @@
+mod adopt;
+mod browse;
+mod config;
+mod doctor;
+mod fetch;
+mod hooks;
 mod install;
 mod list;
+mod lock;
+mod migrate;
+mod outdated;
 mod prompt;
+mod recover;
+mod reinstall;
+mod report;
+mod resolve;
+mod search;
 mod sync;
 mod uninstall;
 mod update;
+mod update_check;
 
-pub use install::install_skill;
-pub use list::list_skills;
-pub use sync::sync_skills;
-pub use uninstall::uninstall_skill;
-pub use update::{update_collection_for_skill, update_skill};
+pub use adopt::{AdoptOptions, adopt_skills};
+pub use browse::browse_skills;
+pub use config::{config_get, config_list, config_set};
+pub use doctor::{DoctorOptions, doctor};
+pub use fetch::{FetchOptions, fetch_skill};
+pub use install::{InstallOptions, LocalChanges, install_from_file, install_skill};
+pub use list::{ListOptions, ListSort, list_skills};
+pub use lock::ManifestLock;
+pub use migrate::migrate;
+pub use outdated::{OutdatedOptions, outdated_skills};
+pub use reinstall::{ReinstallOptions, reinstall_skill};
+pub use report::RunReport;
+pub use resolve::{ResolveUrlOptions, resolve_url};
+pub use search::search_skills;
+pub use sync::{SyncOptions, check_sync, sync_skills};
+pub use uninstall::{UninstallOptions, uninstall_skills};
+pub use update::{UpdateOptions, check_updates, update_collection_for_skill, update_skill};
+pub use update_check::UpdateCheck;
+
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::SkillsConfig,
+    utils,
+};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+/// How read-only commands (`resolve`, `outdated`, `browse`) print their
+/// results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Text for people to read.
+    #[default]
+    Human,
+    /// A single JSON document on stdout.
+    Json,
+}
+
+/// Locations of the manifest and the skills directory a command operates on.
+#[derive(Debug, Clone)]
+pub struct SkillsPaths {
+    /// Path to `skills.toml`; `skills.lock` lives next to it.
+    pub manifest: PathBuf,
+    /// Directory that holds one sub-directory per installed skill.
+    pub skills_dir: PathBuf,
+}
+
+impl SkillsPaths {
+    /// `skills.toml` and `skills/` inside `base_dir`.
+    pub fn from_base_dir(base_dir: &Path) -> Self {
+        Self {
+            manifest: base_dir.join("skills.toml"),
+            skills_dir: base_dir.join("skills"),
+        }
+    }
+
+    /// An explicit manifest path, with the skills directory defaulting to a
+    /// `skills/` sibling of the manifest.
+    pub fn from_manifest(manifest: PathBuf) -> Self {
+        let skills_dir = manifest
+            .parent()
+            .map(|parent| parent.join("skills"))
+            .unwrap_or_else(|| PathBuf::from("skills"));
+        Self {
+            manifest,
+            skills_dir,
+        }
+    }
+
+    /// Directory holding the skills of `root`. `None` is the default skills
+    /// directory; named roots resolve relative to the manifest after `~` and
+    /// environment variables are expanded.
+    pub fn root_dir(&self, config: &SkillsConfig, root: Option<&str>) -> SkillsResult<PathBuf> {
+        let Some(root) = root else {
+            return Ok(self.skills_dir.clone());
+        };
+        let Some(relative) = config.roots.get(root) else {
+            return Err(SkillsError::UnknownRoot(root.to_string()));
+        };
+        let base = self.manifest.parent().unwrap_or(Path::new(""));
+        Ok(base.join(utils::expand_path(relative)))
+    }
+
+    /// Directory of the skill `name`, placed in the root its entry records.
+    pub fn skill_dir(&self, config: &SkillsConfig, name: &str) -> SkillsResult<PathBuf> {
+        Ok(self.root_dir(config, config.root(name))?.join(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skills_dir_defaults_next_to_manifest() {
+        let paths = SkillsPaths::from_manifest(PathBuf::from("config/skills.toml"));
+        assert_eq!(paths.skills_dir, PathBuf::from("config/skills"));
+
+        let paths = SkillsPaths::from_manifest(PathBuf::from("skills.toml"));
+        assert_eq!(paths.skills_dir, PathBuf::from("skills"));
+    }
+
+    #[test]
+    fn test_root_dir_resolves_relative_to_manifest() {
+        let paths = SkillsPaths::from_manifest(PathBuf::from("repo/skills.toml"));
+        let mut config = SkillsConfig::default();
+        config
+            .roots
+            .insert("shared".to_string(), "shared/skills".to_string());
+
+        assert_eq!(
+            paths.root_dir(&config, None).unwrap(),
+            PathBuf::from("repo/skills")
+        );
+        assert_eq!(
+            paths.root_dir(&config, Some("shared")).unwrap(),
+            PathBuf::from("repo/shared/skills")
+        );
+        assert!(matches!(
+            paths.root_dir(&config, Some("missing")),
+            Err(SkillsError::UnknownRoot(_))
+        ));
+    }
+}
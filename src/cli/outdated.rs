@@ -0,0 +1,212 @@
+use crate::{
+    errors::SkillsResult,
+    models::{SkillEntry, SkillsConfig},
+    providers::{ProviderRegistry, UpstreamCommit},
+    style,
+    utils::{parse_timestamp, same_commit},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{OutputFormat, SkillsPaths};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Options controlling a single `outdated` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct OutdatedOptions {
+    /// Only report skills whose upstream commit is at least this many days old.
+    pub older_than: Option<u64>,
+    /// Only report skills whose upstream commit is at most this many days old.
+    pub newer_than: Option<u64>,
+    /// Print text or one JSON document.
+    pub output: OutputFormat,
+}
+
+impl OutdatedOptions {
+    /// Whether a commit of the given age passes the filters. Commits without a
+    /// known age only pass when no filter is set.
+    fn matches(&self, age_days: Option<u64>) -> bool {
+        if self.older_than.is_none() && self.newer_than.is_none() {
+            return true;
+        }
+        let Some(age) = age_days else {
+            return false;
+        };
+        self.older_than.is_none_or(|days| age >= days)
+            && self.newer_than.is_none_or(|days| age <= days)
+    }
+}
+
+/// List installed skills whose upstream ref has moved past the locked commit.
+pub fn outdated_skills(
+    paths: &SkillsPaths,
+    options: &OutdatedOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+    let json = options.output == OutputFormat::Json;
+
+    if config.skills.is_empty() {
+        if json {
+            println!("{}", serde_json::json!({ "outdated": [] }));
+        } else {
+            println!("No skills installed.");
+        }
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut outdated = 0;
+    let mut rows = Vec::new();
+
+    for (name, entry) in config.skills.iter().filter(|(_, entry)| !entry.is_local()) {
+        let Some(commit) = upstream_commit(name, entry, registry) else {
+            continue;
+        };
+
+        if same_commit(&commit.sha, &entry.sha) {
+            continue;
+        }
+
+        let age_days = commit
+            .date
+            .as_deref()
+            .and_then(parse_timestamp)
+            .map(|timestamp| now.saturating_sub(timestamp) / SECONDS_PER_DAY);
+        if !options.matches(age_days) {
+            continue;
+        }
+
+        outdated += 1;
+        if json {
+            rows.push(serde_json::json!({
+                "name": name,
+                "locked": entry.sha,
+                "upstream": commit.sha,
+                "committed_at": commit.date,
+                "age_days": age_days,
+            }));
+            continue;
+        }
+        let age = match (commit.date.as_deref(), age_days) {
+            (Some(date), Some(days)) => format!(" (committed {}, {} days ago)", date, days),
+            _ => String::new(),
+        };
+        println!(
+            "{}  {} -> {}{}",
+            name,
+            short_sha(&entry.sha),
+            short_sha(&commit.sha),
+            age
+        );
+    }
+
+    if json {
+        println!("{}", serde_json::json!({ "outdated": rows }));
+    } else if outdated == 0 {
+        println!("{}", style::success("All skills are up to date."));
+    }
+
+    Ok(())
+}
+
+/// Print the skills of `config` picked by `selects` whose upstream ref has
+/// moved past the locked commit, and return how many there are.
+pub(super) fn report_drift(
+    config: &SkillsConfig,
+    registry: &ProviderRegistry,
+    selects: impl Fn(&str) -> bool,
+) -> usize {
+    let mut drifted = 0;
+    for (name, entry) in config
+        .skills
+        .iter()
+        .filter(|(name, entry)| selects(name) && !entry.is_local())
+    {
+        let Some(commit) = upstream_commit(name, entry, registry) else {
+            continue;
+        };
+        if same_commit(&commit.sha, &entry.sha) {
+            continue;
+        }
+        if drifted == 0 {
+            println!("Skills behind upstream:");
+        }
+        drifted += 1;
+        println!(
+            "  {}  {} -> {}",
+            name,
+            short_sha(&entry.sha),
+            short_sha(&commit.sha)
+        );
+    }
+    if drifted == 0 {
+        println!("{}", style::success("All skills match upstream."));
+    }
+    drifted
+}
+
+/// The newest upstream commit of the ref `entry` tracks. Failures are
+/// reported on stderr and yield `None`.
+pub(super) fn upstream_commit(
+    name: &str,
+    entry: &SkillEntry,
+    registry: &ProviderRegistry,
+) -> Option<UpstreamCommit> {
+    let latest = registry
+        .get(&entry.source_url)
+        .and_then(|provider| provider.latest_commit(entry));
+    match latest {
+        Ok(Some(commit)) => Some(commit),
+        Ok(None) => {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!("[{}] Upstream no longer resolves", name))
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!(
+                "{}",
+                style::error(&format!("[{}] Failed to check upstream: {}", name, e))
+            );
+            None
+        }
+    }
+}
+
+pub(super) fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outdated_options_matches() {
+        let all = OutdatedOptions::default();
+        assert!(all.matches(None));
+        assert!(all.matches(Some(3)));
+
+        let old = OutdatedOptions {
+            older_than: Some(30),
+            ..Default::default()
+        };
+        assert!(old.matches(Some(30)));
+        assert!(!old.matches(Some(29)));
+        assert!(!old.matches(None));
+
+        let window = OutdatedOptions {
+            older_than: Some(7),
+            newer_than: Some(14),
+            ..Default::default()
+        };
+        assert!(window.matches(Some(10)));
+        assert!(!window.matches(Some(3)));
+        assert!(!window.matches(Some(20)));
+    }
+}
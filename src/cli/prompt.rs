@@ -33,3 +33,100 @@ pub fn confirm_action_or_yes(prompt: &str, yes: bool) -> bool {
         "Non-interactive mode detected. Use --yes flag to auto-confirm.",
     )
 }
+
+/// Ask a yes/no question that can also be answered with "quit". Returns
+/// `None` on quit or when stdin is closed.
+pub fn confirm_or_quit(prompt: &str) -> Option<bool> {
+    print!("{} (y/N/q): ", prompt);
+    io::stdout().flush().ok();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+        return None;
+    }
+    match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "q" | "quit" => None,
+        _ => Some(false),
+    }
+}
+
+/// Whether prompts can be answered, i.e. stdin is a terminal.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Let the user toggle which of `items` to keep, starting with all of them
+/// selected. Returns the selected indices, or `None` when cancelled.
+pub fn select_items(items: &[String]) -> Option<Vec<usize>> {
+    let mut selected = vec![true; items.len()];
+    loop {
+        for (idx, item) in items.iter().enumerate() {
+            let mark = if selected[idx] { "x" } else { " " };
+            println!("  [{}] {:>2}. {}", mark, idx + 1, item);
+        }
+        println!();
+        print!("Toggle numbers (e.g. 1,3-5), 'a' all, 'n' none, Enter to confirm, 'q' to cancel: ");
+        io::stdout().flush().ok();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return None;
+        }
+        match input.trim() {
+            "" => {
+                return Some((0..items.len()).filter(|idx| selected[*idx]).collect());
+            }
+            "q" => return None,
+            "a" => selected.fill(true),
+            "n" => selected.fill(false),
+            other => match parse_selection(other, items.len()) {
+                Some(indices) => {
+                    for idx in indices {
+                        selected[idx] = !selected[idx];
+                    }
+                }
+                None => println!("Invalid selection: {}", other),
+            },
+        }
+        println!();
+    }
+}
+
+/// Parse 1-based numbers and ranges such as `1,3-5` (commas or spaces) into
+/// 0-based indices below `count`.
+fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+    let mut indices = Vec::new();
+    for part in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+    {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+            None => {
+                let number: usize = part.parse().ok()?;
+                (number, number)
+            }
+        };
+        if start == 0 || start > end || end > count {
+            return None;
+        }
+        indices.extend(start - 1..end);
+    }
+    Some(indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1,3-5", 5), Some(vec![0, 2, 3, 4]));
+        assert_eq!(parse_selection(" 2 4 ", 5), Some(vec![1, 3]));
+        assert_eq!(parse_selection("0", 5), None);
+        assert_eq!(parse_selection("6", 5), None);
+        assert_eq!(parse_selection("4-2", 5), None);
+        assert_eq!(parse_selection("x", 5), None);
+    }
+}
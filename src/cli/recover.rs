@@ -0,0 +1,177 @@
+use crate::{
+    errors::SkillsResult,
+    models::SkillsConfig,
+    style,
+    utils::{
+        ChecksumAlgorithm, calculate_checksum, ensure_skill_manifest, is_temp_name_for, move_dir,
+    },
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{SkillsPaths, sync::leftover_temp_dirs};
+
+/// A skill whose directory is missing while a download of it is still
+/// sitting in a temp directory, left behind by an interrupted install or sync.
+#[derive(Debug)]
+pub(super) struct InterruptedInstall {
+    pub name: String,
+    /// Where the skill should be.
+    pub skill_dir: PathBuf,
+    /// Every temp directory holding a download of the skill.
+    pub temp_dirs: Vec<PathBuf>,
+    /// The temp directory whose contents match the locked checksum, if any.
+    pub complete: Option<PathBuf>,
+}
+
+/// Find skills in `config` whose directory is missing but whose download
+/// was left in a temp directory.
+pub(super) fn find_interrupted(
+    paths: &SkillsPaths,
+    config: &SkillsConfig,
+) -> SkillsResult<Vec<InterruptedInstall>> {
+    let mut interrupted = Vec::new();
+    for (name, entry) in &config.skills {
+        let skill_dir = paths.skill_dir(config, name)?;
+        if skill_dir.exists() {
+            continue;
+        }
+        let root_dir = paths.root_dir(config, config.root(name))?;
+        let mut temp_dirs = leftover_temp_dirs(&root_dir, name);
+        temp_dirs.extend(
+            install_temp_roots(&root_dir)
+                .into_iter()
+                .map(|temp_root| temp_root.join(name))
+                .filter(|dir| dir.is_dir()),
+        );
+        if temp_dirs.is_empty() {
+            continue;
+        }
+        let algorithm = ChecksumAlgorithm::of_checksum(&entry.checksum).unwrap_or_default();
+        let complete = temp_dirs
+            .iter()
+            .find(|dir| {
+                ensure_skill_manifest(dir, &config.settings.manifest_names).is_ok()
+                    && calculate_checksum(dir, algorithm).is_ok_and(|sum| sum == entry.checksum)
+            })
+            .cloned();
+        interrupted.push(InterruptedInstall {
+            name: name.clone(),
+            skill_dir,
+            temp_dirs,
+            complete,
+        });
+    }
+    Ok(interrupted)
+}
+
+/// Move each complete download into place and remove the rest, so that the
+/// next sync downloads those skills again.
+pub(super) fn recover_interrupted(interrupted: &[InterruptedInstall]) -> SkillsResult<()> {
+    for install in interrupted {
+        if let Some(complete) = &install.complete {
+            if let Some(parent) = install.skill_dir.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            move_dir(complete, &install.skill_dir)?;
+            println!(
+                "{}",
+                style::success(&format!("[{}] Finished interrupted install", install.name))
+            );
+        } else {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "[{}] Removed incomplete download; it will be downloaded again on sync",
+                    install.name
+                ))
+            );
+        }
+        for dir in &install.temp_dirs {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+            if let Some(parent) = dir.parent()
+                && is_install_temp_root(parent)
+            {
+                // Other skills of the same batch may still be in there.
+                fs::remove_dir(parent).ok();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Recover interrupted installs before a command touches the skills.
+pub(super) fn recover_before_run(paths: &SkillsPaths, config: &SkillsConfig) -> SkillsResult<()> {
+    let interrupted = find_interrupted(paths, config)?;
+    recover_interrupted(&interrupted)
+}
+
+/// Scratch directories of `install` runs in `root_dir`.
+fn install_temp_roots(root_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_install_temp_root(path))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn is_install_temp_root(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| is_temp_name_for(&name.to_string_lossy(), "install"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SkillEntry;
+
+    #[test]
+    fn test_recover_interrupted_installs() {
+        let temp_dir = std::env::temp_dir().join("skills_test_recover_interrupted");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+
+        // `done` was fully extracted by an install, `partial` only halfway
+        // by a sync.
+        let install_root = paths.skills_dir.join(".install.42.abc.tmp");
+        let done = install_root.join("done");
+        fs::create_dir_all(&done).unwrap();
+        fs::write(done.join("SKILL.md"), b"# Done").unwrap();
+        let partial = paths.skills_dir.join(".partial.42.abc.tmp");
+        fs::create_dir_all(&partial).unwrap();
+        fs::write(partial.join("notes.md"), b"half").unwrap();
+
+        let mut config = SkillsConfig::default();
+        let checksum = calculate_checksum(&done, ChecksumAlgorithm::Sha256).unwrap();
+        config
+            .skills
+            .insert("done".to_string(), SkillEntry::local(checksum, None));
+        config.skills.insert(
+            "partial".to_string(),
+            SkillEntry::local("sha256:0".to_string(), None),
+        );
+
+        let interrupted = find_interrupted(&paths, &config).unwrap();
+        assert_eq!(interrupted.len(), 2);
+        assert_eq!(interrupted[0].complete.as_deref(), Some(done.as_path()));
+        assert!(interrupted[1].complete.is_none());
+
+        recover_interrupted(&interrupted).unwrap();
+        assert!(paths.skills_dir.join("done/SKILL.md").exists());
+        assert!(!paths.skills_dir.join("partial").exists());
+        assert!(!partial.exists());
+        assert!(!install_root.exists());
+        assert!(find_interrupted(&paths, &config).unwrap().is_empty());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
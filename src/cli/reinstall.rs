@@ -0,0 +1,85 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::SkillsConfig,
+    providers::ProviderRegistry,
+    style,
+};
+
+use super::{
+    SkillsPaths,
+    install::{InstallOptions, install_skill},
+    prompt::confirm_action_or_yes,
+    uninstall::remove_skill,
+};
+
+/// Options controlling a single `reinstall` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct ReinstallOptions {
+    /// Automatically answer yes to prompts.
+    pub yes: bool,
+    /// Install from this ref instead of the one the skill was installed from.
+    pub r#ref: Option<String>,
+}
+
+/// Remove a skill and install it again from its recorded source, keeping its
+/// name, root and pins. If the install fails, the skill's entry is restored
+/// so a later `sync` can recover it.
+pub fn reinstall_skill(
+    name: &str,
+    paths: &SkillsPaths,
+    options: &ReinstallOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let mut config = SkillsConfig::from_file(config_path)?;
+
+    let Some(entry) = config.skills.get(name).cloned() else {
+        println!("Skill '{}' is not installed.", name);
+        return Ok(());
+    };
+    if entry.is_local() {
+        return Err(SkillsError::LocalSkill(name.to_string()));
+    }
+
+    let url = match &options.r#ref {
+        Some(new_ref) => registry
+            .get(&entry.source_url)?
+            .source_url_with_ref(&entry, new_ref)?,
+        None => entry.source_url.clone(),
+    };
+
+    println!("Reinstalling '{}' from {}", name, url);
+    if !confirm_action_or_yes("Remove the skill and install it again?", options.yes) {
+        println!("Reinstall cancelled.");
+        return Ok(());
+    }
+
+    remove_skill(name, &mut config, paths)?;
+    config.save(config_path)?;
+
+    let mut install_options = InstallOptions {
+        yes: true,
+        ..InstallOptions::for_entry(name, &entry)
+    };
+    if options.r#ref.is_some() {
+        // Whether the old ref was a branch or a tag says nothing of the new one.
+        install_options.ref_name = options.r#ref.clone();
+        install_options.ref_kind = None;
+    }
+    install_skill(&url, paths, &install_options, registry).inspect_err(|_| {
+        if let Ok(mut config) = SkillsConfig::from_file(config_path)
+            && !config.skills.contains_key(name)
+        {
+            config.skills.insert(name.to_string(), entry.clone());
+            if config.save(config_path).is_ok() {
+                eprintln!(
+                    "{}",
+                    style::warning_err(&format!(
+                        "Restored the entry for '{}'; run `skill sync` to download it again.",
+                        name
+                    ))
+                );
+            }
+        }
+    })
+}
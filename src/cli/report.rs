@@ -0,0 +1,189 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::SkillsConfig,
+    utils::{current_timestamp, unique_temp_name},
+};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use super::SkillsPaths;
+
+/// The locked commit and checksum of one skill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SkillState {
+    sha: String,
+    checksum: String,
+}
+
+/// State of the skills before an `install`, `sync` or `update`, to be
+/// compared with the state after it for `--report-file`.
+#[derive(Debug)]
+pub struct RunReport {
+    command: &'static str,
+    started_at: String,
+    before: BTreeMap<String, SkillState>,
+}
+
+impl RunReport {
+    /// Record the state of the skills before `command` runs.
+    pub fn start(command: &'static str, paths: &SkillsPaths) -> Self {
+        Self {
+            command,
+            started_at: current_timestamp(),
+            before: skill_states(paths),
+        }
+    }
+
+    /// Write the report as JSON to `path`, comparing the recorded state with
+    /// the current one. `error` is how the command failed, if it did. The
+    /// file is replaced in one step, so readers never see half a report.
+    pub fn write(
+        &self,
+        paths: &SkillsPaths,
+        error: Option<&SkillsError>,
+        path: &Path,
+    ) -> SkillsResult<()> {
+        let failures: BTreeMap<String, String> = match error {
+            Some(SkillsError::BatchInstallationFailed { failed, .. }) => {
+                failed.iter().cloned().collect()
+            }
+            Some(SkillsError::SyncFailed { failed, .. }) => failed
+                .iter()
+                .map(|name| (name.clone(), "failed to sync".to_string()))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        let mut report = serde_json::json!({
+            "command": self.command,
+            "started_at": self.started_at,
+            "finished_at": current_timestamp(),
+            "status": if error.is_some() { "failed" } else { "ok" },
+            "skills": skill_outcomes(&self.before, &skill_states(paths), &failures),
+        });
+        if let Some(error) = error {
+            report["error"] = serde_json::json!({
+                "kind": error.kind(),
+                "message": error.to_string(),
+            });
+        }
+
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let temp = dir.join(unique_temp_name("report"));
+        fs::write(&temp, format!("{:#}\n", report))?;
+        fs::rename(&temp, path).inspect_err(|_| {
+            fs::remove_file(&temp).ok();
+        })?;
+        Ok(())
+    }
+}
+
+/// The locked state of every skill in the manifest; empty when it cannot be
+/// read.
+fn skill_states(paths: &SkillsPaths) -> BTreeMap<String, SkillState> {
+    let Ok(config) = SkillsConfig::from_file(&paths.manifest) else {
+        return BTreeMap::new();
+    };
+    config
+        .skills
+        .into_iter()
+        .map(|(name, entry)| {
+            let state = SkillState {
+                sha: entry.sha,
+                checksum: entry.checksum,
+            };
+            (name, state)
+        })
+        .collect()
+}
+
+/// One JSON object per skill known before or after the run, with its state on
+/// both sides and what happened to it.
+fn skill_outcomes(
+    before: &BTreeMap<String, SkillState>,
+    after: &BTreeMap<String, SkillState>,
+    failures: &BTreeMap<String, String>,
+) -> Vec<serde_json::Value> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.extend(failures.keys());
+    names.sort();
+    names.dedup();
+
+    let state_json = |state: Option<&SkillState>| match state {
+        Some(state) => serde_json::json!({ "sha": state.sha, "checksum": state.checksum }),
+        None => serde_json::Value::Null,
+    };
+    names
+        .into_iter()
+        .map(|name| {
+            let (old, new) = (before.get(name), after.get(name));
+            let outcome = match (old, new) {
+                _ if failures.contains_key(name) => "failed",
+                (None, Some(_)) => "installed",
+                (Some(_), None) => "removed",
+                (Some(old), Some(new)) if old != new => "updated",
+                _ => "unchanged",
+            };
+            let mut skill = serde_json::json!({
+                "name": name,
+                "outcome": outcome,
+                "before": state_json(old),
+                "after": state_json(new),
+            });
+            if let Some(reason) = failures.get(name) {
+                skill["reason"] = reason.clone().into();
+            }
+            skill
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_outcomes() {
+        let state = |sha: &str| SkillState {
+            sha: sha.to_string(),
+            checksum: format!("sha256:{}", sha),
+        };
+        let before = BTreeMap::from([
+            ("kept".to_string(), state("a")),
+            ("moved".to_string(), state("b")),
+            ("gone".to_string(), state("c")),
+        ]);
+        let after = BTreeMap::from([
+            ("kept".to_string(), state("a")),
+            ("moved".to_string(), state("d")),
+            ("new".to_string(), state("e")),
+        ]);
+        let failures = BTreeMap::from([("broken".to_string(), "404".to_string())]);
+
+        let outcomes: Vec<(String, String)> = skill_outcomes(&before, &after, &failures)
+            .iter()
+            .map(|skill| {
+                (
+                    skill["name"].as_str().unwrap().to_string(),
+                    skill["outcome"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("broken", "failed"),
+                ("gone", "removed"),
+                ("kept", "unchanged"),
+                ("moved", "updated"),
+                ("new", "installed"),
+            ]
+            .map(|(name, outcome)| (name.to_string(), outcome.to_string()))
+        );
+
+        let moved = &skill_outcomes(&before, &after, &failures)[3];
+        assert_eq!(moved["before"]["sha"], "b");
+        assert_eq!(moved["after"]["checksum"], "sha256:d");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::RefKind,
+    providers::{CandidateOutcome, ProviderRegistry, ResolveOptions, ResolveReport},
+    style,
+    utils::parse_until_date,
+};
+
+use super::OutputFormat;
+
+/// Options controlling a single `resolve` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveUrlOptions {
+    /// Resolve refs to the last commit at or before this date.
+    pub at: Option<String>,
+    /// Try tags or branches first when the URL could be split several ways.
+    pub prefer_ref: Option<RefKind>,
+    /// File names that mark a directory as a skill; empty means `SKILL.md`.
+    pub manifest_names: Vec<String>,
+    /// Print text or one JSON document.
+    pub output: OutputFormat,
+}
+
+/// Print how `url` resolves: each candidate `(ref, path)` split with its
+/// outcome, then the winning commit and whether it is a single skill or a
+/// collection. Nothing is downloaded or written.
+pub fn resolve_url(
+    url: &str,
+    options: &ResolveUrlOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let provider = registry.get(url)?;
+    let resolve_options = ResolveOptions {
+        until: options.at.as_deref().map(parse_until_date).transpose()?,
+        prefer_ref: options.prefer_ref,
+        manifest_names: options.manifest_names.clone(),
+        ..Default::default()
+    };
+    let report = provider.explain_resolution(url, &resolve_options)?;
+
+    if options.output == OutputFormat::Json {
+        println!("{}", report_json(&report));
+        if report.resolved.is_none() {
+            return Err(SkillsError::PathNotFound(vec![url.to_string()]));
+        }
+        return Ok(());
+    }
+
+    println!("Candidates:");
+    for (idx, candidate) in report.candidates.iter().enumerate() {
+        let outcome = match &candidate.outcome {
+            CandidateOutcome::Resolved(sha) => {
+                style::success(&format!("resolved to {}", sha)).into_owned()
+            }
+            CandidateOutcome::NotFound => "not found".to_string(),
+            CandidateOutcome::Failed(reason) => style::error(reason).into_owned(),
+            CandidateOutcome::Skipped => "not tried".to_string(),
+        };
+        println!(
+            "  {}. ref '{}', path '{}': {}",
+            idx + 1,
+            candidate.r#ref,
+            candidate.path,
+            outcome
+        );
+    }
+    println!();
+
+    let Some(resolved) = report.resolved else {
+        return Err(SkillsError::PathNotFound(vec![url.to_string()]));
+    };
+    println!("Resolved:");
+    println!("  Repo:   {}", resolved.slug);
+    println!("  Ref:    {}", resolved.r#ref);
+    println!("  Commit: {}", resolved.sha);
+    println!("  Path:   {}", resolved.path);
+    if resolved.collection.is_empty() {
+        println!("  Layout: single skill");
+    } else {
+        println!(
+            "  Layout: collection of {} skill(s): {}",
+            resolved.collection.len(),
+            resolved.collection.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// `report` as the JSON document `resolve --output json` prints.
+fn report_json(report: &ResolveReport) -> serde_json::Value {
+    let candidates: Vec<_> = report
+        .candidates
+        .iter()
+        .map(|candidate| {
+            let mut value = serde_json::json!({
+                "ref": candidate.r#ref,
+                "path": candidate.path,
+            });
+            let outcome = match &candidate.outcome {
+                CandidateOutcome::Resolved(sha) => {
+                    value["sha"] = sha.clone().into();
+                    "resolved"
+                }
+                CandidateOutcome::NotFound => "not_found",
+                CandidateOutcome::Failed(reason) => {
+                    value["reason"] = reason.clone().into();
+                    "failed"
+                }
+                CandidateOutcome::Skipped => "skipped",
+            };
+            value["outcome"] = outcome.into();
+            value
+        })
+        .collect();
+    let resolved = report.resolved.as_ref().map(|resolved| {
+        serde_json::json!({
+            "slug": resolved.slug,
+            "ref": resolved.r#ref,
+            "sha": resolved.sha,
+            "path": resolved.path,
+            "collection": resolved.collection,
+        })
+    });
+    serde_json::json!({ "candidates": candidates, "resolved": resolved })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CandidateAttempt, ResolvedSource};
+
+    #[test]
+    fn test_report_json() {
+        let attempt = |r#ref: &str, outcome| CandidateAttempt {
+            r#ref: r#ref.to_string(),
+            path: "skills/demo".to_string(),
+            outcome,
+        };
+        let report = ResolveReport {
+            candidates: vec![
+                attempt("feature", CandidateOutcome::NotFound),
+                attempt("main", CandidateOutcome::Resolved("abc123".to_string())),
+            ],
+            resolved: Some(ResolvedSource {
+                slug: "owner/repo".to_string(),
+                r#ref: "main".to_string(),
+                sha: "abc123".to_string(),
+                path: "skills/demo".to_string(),
+                collection: Vec::new(),
+            }),
+        };
+
+        let json = report_json(&report);
+        assert_eq!(json["candidates"][0]["outcome"], "not_found");
+        assert_eq!(json["candidates"][1]["outcome"], "resolved");
+        assert_eq!(json["candidates"][1]["sha"], "abc123");
+        assert_eq!(json["resolved"]["slug"], "owner/repo");
+
+        let unresolved = ResolveReport::default();
+        assert!(report_json(&unresolved)["resolved"].is_null());
+    }
+}
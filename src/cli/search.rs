@@ -0,0 +1,30 @@
+use crate::{
+    errors::SkillsResult,
+    http::create_agent,
+    index::{fetch_index, index_url_from_env, search},
+};
+
+pub fn search_skills(query: &str) -> SkillsResult<()> {
+    let index_url = index_url_from_env()?;
+    let agent = create_agent()?;
+    let entries = fetch_index(&agent, &index_url)?;
+    let matches = search(&entries, query);
+
+    if matches.is_empty() {
+        println!("No skills found matching '{}'.", query);
+        return Ok(());
+    }
+
+    for entry in &matches {
+        println!("  {}", entry.name);
+        if !entry.description.is_empty() {
+            println!("    {}", entry.description);
+        }
+        println!("    Install: skill install {}", entry.url);
+        println!();
+    }
+
+    println!("Found: {} skill(s)", matches.len());
+
+    Ok(())
+}
@@ -1,116 +1,998 @@
 use crate::{
-    errors::SkillsResult,
-    models::SkillsConfig,
+    errors::{SkillsError, SkillsResult},
+    models::{Settings, SkillEntry, SkillsConfig},
     providers::{ExtractTarget, ProviderRegistry},
-    utils::{calculate_checksum, ensure_skill_manifest},
+    style,
+    utils::{
+        ChecksumAlgorithm, SKIPPED_CHECKSUM, cacheable_tree_mtime, calculate_checksum,
+        checksum_matches, current_timestamp, default_jobs, ensure_skill_manifest, format_bytes,
+        is_symlink, is_temp_name_for, missing_front_matter, move_dir, parallel_map, parse_checksum,
+        remove_skill_dir, tree_mtime, unique_temp_name, verify_skill_signature,
+    },
 };
-use std::{fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use super::{
+    SkillsPaths,
+    doctor::report_directory_conflicts,
+    hooks::PostInstallHook,
+    install::{InstallOptions, LocalChanges, install_skill_counted},
+    outdated::report_drift,
+    prompt::confirm_action,
+    recover::recover_before_run,
+};
+
+/// Options controlling a single `sync` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Continue with the remaining skills after a failure.
+    pub keep_going: bool,
+    /// Re-download every skill, even when its checksum matches.
+    pub refresh: bool,
+    /// Install exactly what skills.lock records; fail if it is out of date
+    /// or a download does not match its locked checksum.
+    pub frozen: bool,
+    /// Skip the transfer summary printed at the end.
+    pub quiet: bool,
+    /// Hash every skill directory even when its recorded mtime is unchanged.
+    pub no_cache_checksum: bool,
+    /// Only sync these skills; empty syncs every skill.
+    pub only: Vec<String>,
+    /// Only download skills whose directory is missing, leaving every
+    /// skill already on disk unchecked.
+    pub only_missing: bool,
+    /// Replace skill directories that are symlinks (e.g. to a dev checkout)
+    /// instead of leaving them alone.
+    pub force: bool,
+    /// Threads used to hash skill directories; 0 uses one per CPU.
+    pub jobs: usize,
+    /// What to do with skills whose files differ from skills.lock. Without a
+    /// prompt, [`LocalChanges::Prompt`] leaves them untouched.
+    pub local_changes: LocalChanges,
+    /// After syncing, list skills whose upstream ref has moved past the
+    /// locked commit. Costs one API request per skill.
+    pub report_drift: bool,
+    /// Require `name`, `description` and `required_fields` from `[config]`
+    /// in each downloaded skill's front matter.
+    pub strict_manifest: bool,
+    /// Refuse downloads whose files are not covered by a `SHA256SUMS`
+    /// signed with one of the `trusted_keys` in `[config]`.
+    pub verify_signatures: bool,
+}
+
+/// What sync makes of a skill that has a source to download from, before
+/// asking or downloading anything.
+#[derive(Debug)]
+enum SkillState {
+    /// The directory is missing, or the skill was recorded without being
+    /// downloaded.
+    Missing,
+    /// `--refresh` downloads it whatever its state.
+    Refresh,
+    /// The checksum in skills.lock cannot be verified, for this reason.
+    Unverifiable(String),
+    /// The files match skills.lock; carries the tree mtime to record.
+    UpToDate(Option<u64>),
+    /// The files differ from skills.lock.
+    Modified,
+    /// Hashing the directory failed.
+    Unreadable(io::Error),
+}
 
-use super::prompt::confirm_action;
+impl SyncOptions {
+    fn selects(&self, name: &str) -> bool {
+        self.only.is_empty() || self.only.iter().any(|only| only == name)
+    }
+}
 
-pub fn sync_skills(base_dir: &Path, registry: &ProviderRegistry) -> SkillsResult<()> {
-    let config_path = base_dir.join("skills.toml");
-    let mut config = SkillsConfig::from_file(&config_path)?;
+/// Totals reported at the end of a sync.
+#[derive(Debug, Default)]
+struct SyncSummary {
+    checked: usize,
+    downloaded: usize,
+    bytes: u64,
+}
 
-    let skills_dir = base_dir.join("skills");
+pub fn sync_skills(
+    paths: &SkillsPaths,
+    options: &SyncOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let config = SkillsConfig::from_file(config_path)?;
 
-    if config.skills.is_empty() {
+    if config.skills.is_empty() && config.unresolved.is_empty() {
         println!("No skills configured in skills.toml");
         return Ok(());
     }
 
-    let skill_names: Vec<String> = config.skills.keys().cloned().collect();
+    if options.frozen && !config.lock_issues.is_empty() {
+        return Err(SkillsError::LockOutOfDate(config.lock_issues));
+    }
 
-    for name in skill_names {
-        let entry = config.skills.get(&name).unwrap();
-        let skill_dir = skills_dir.join(&name);
+    check_only_configured(&config, options)?;
 
-        let needs_download = if !skill_dir.exists() {
-            println!("[{}] Downloading...", name);
-            true
-        } else {
-            match calculate_checksum(&skill_dir) {
-                Ok(checksum) if checksum == entry.checksum => {
-                    println!("[{}] Up to date", name);
-                    false
-                }
-                Ok(_) => {
-                    println!(
-                        "[{}] Checksum mismatch - local modifications detected",
-                        name
-                    );
+    let started = Instant::now();
+    let mut summary = SyncSummary::default();
+    let result = sync_configured_skills(paths, config, options, registry, &mut summary);
 
-                    confirm_action("Overwrite local changes?")
-                }
-                Err(e) => {
-                    eprintln!("[{}] Error calculating checksum: {}", name, e);
-                    true
+    if !options.quiet {
+        println!();
+        println!(
+            "Checked {} skill(s), downloaded {}, transferred {} in {:.1}s",
+            summary.checked,
+            summary.downloaded,
+            format_bytes(summary.bytes),
+            started.elapsed().as_secs_f64()
+        );
+    }
+
+    if options.report_drift && result.is_ok() {
+        println!();
+        let config = SkillsConfig::from_file(config_path)?;
+        report_drift(&config, registry, |name| options.selects(name));
+    }
+
+    result
+}
+
+/// Fail when `--only` names a skill that is not in skills.toml.
+fn check_only_configured(config: &SkillsConfig, options: &SyncOptions) -> SkillsResult<()> {
+    let unknown: Vec<String> = options
+        .only
+        .iter()
+        .filter(|name| !config.skills.contains_key(*name) && !config.unresolved.contains_key(*name))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Err(SkillsError::SkillsNotConfigured(unknown));
+    }
+    Ok(())
+}
+
+/// Report what `sync` would do to each skill without downloading, asking
+/// or writing anything: `sync --check`. Returns how many skills are not in
+/// the state skills.lock records.
+pub fn check_sync(paths: &SkillsPaths, options: &SyncOptions) -> SkillsResult<usize> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+    if config.skills.is_empty() && config.unresolved.is_empty() {
+        println!("No skills configured in skills.toml");
+        return Ok(0);
+    }
+    check_only_configured(&config, options)?;
+
+    let conflicting = report_directory_conflicts(paths, &config);
+    let mut changes = 0;
+    for (name, manifest) in &config.unresolved {
+        if conflicting.contains(name) || !options.selects(name) {
+            continue;
+        }
+        println!(
+            "{}: would resolve and download {}",
+            name, manifest.source_url
+        );
+        changes += 1;
+    }
+
+    let names: Vec<&String> = config
+        .skills
+        .keys()
+        .filter(|name| options.selects(name) && !conflicting.contains(*name))
+        .collect();
+    let jobs = match options.jobs {
+        0 => default_jobs(),
+        jobs => jobs,
+    };
+    let mut checks = checksum_pass(&config, paths, &names, options, jobs);
+
+    for name in names {
+        let entry = &config.skills[name];
+        let skill_dir = paths.skill_dir(&config, name)?;
+        let checked = checks.remove(name);
+        if entry.is_local() {
+            let status = if !skill_dir.exists() {
+                Some("missing, and has no source to download from")
+            } else if matches!(checked, Some(Ok((true, _)))) {
+                None
+            } else {
+                Some("changed since it was adopted")
+            };
+            match status {
+                Some(status) => {
+                    println!("{}: {} (local)", name, status);
+                    changes += 1;
                 }
+                None => println!("{}: up to date (local)", name),
             }
+            continue;
+        }
+        if is_symlink(&skill_dir) && !options.force {
+            println!("{}: skipped (symlink)", name);
+            continue;
+        }
+
+        let reason = match skill_state(&skill_dir, entry, options, checked) {
+            SkillState::UpToDate(_) => {
+                println!("{}: up to date", name);
+                continue;
+            }
+            SkillState::Missing => "missing".to_string(),
+            SkillState::Refresh => "--refresh".to_string(),
+            SkillState::Unverifiable(reason) => format!("unverifiable checksum: {}", reason),
+            SkillState::Modified => "local modifications".to_string(),
+            SkillState::Unreadable(e) => format!("checksum failed: {}", e),
         };
+        println!("{}: would download ({})", name, reason);
+        changes += 1;
+    }
+    Ok(changes)
+}
 
-        if needs_download {
-            let Ok(provider) = registry.get(&entry.source_url) else {
-                eprintln!("[{}] No provider available for: {}", name, entry.source_url);
+/// Classify the remote skill `entry` installed at `skill_dir`, using the
+/// result of the checksum pass when there is one. Nothing is printed or
+/// written, so `sync --check` shares the decisions of a real sync.
+fn skill_state(
+    skill_dir: &Path,
+    entry: &SkillEntry,
+    options: &SyncOptions,
+    checked: Option<ChecksumCheck>,
+) -> SkillState {
+    if !skill_dir.exists() || entry.is_pending() {
+        return SkillState::Missing;
+    }
+    if options.refresh {
+        return SkillState::Refresh;
+    }
+    if entry.checksum != SKIPPED_CHECKSUM
+        && let Err(reason) = parse_checksum(&entry.checksum)
+    {
+        return SkillState::Unverifiable(reason);
+    }
+    let checked =
+        checked.unwrap_or_else(|| check_checksum(skill_dir, entry, !options.no_cache_checksum));
+    match checked {
+        Ok((true, mtime)) => SkillState::UpToDate(mtime),
+        Ok((false, _)) => SkillState::Modified,
+        Err(e) => SkillState::Unreadable(e),
+    }
+}
+
+fn sync_configured_skills(
+    paths: &SkillsPaths,
+    mut config: SkillsConfig,
+    options: &SyncOptions,
+    registry: &ProviderRegistry,
+    summary: &mut SyncSummary,
+) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let conflicting = report_directory_conflicts(paths, &config);
+    recover_before_run(paths, &config)?;
+    let mut successful = 0;
+    let mut failed = Vec::new();
+    let mut hook = PostInstallHook::default();
+
+    if !config.unresolved.is_empty() {
+        for (name, manifest) in &config.unresolved {
+            if conflicting.contains(name) || !options.selects(name) {
                 continue;
+            }
+            summary.checked += 1;
+            println!("[{}] Resolving {}...", name, manifest.source_url);
+            let install_options = InstallOptions {
+                yes: true,
+                strict_manifest: options.strict_manifest,
+                verify_signatures: options.verify_signatures,
+                ..InstallOptions::for_manifest_entry(name, manifest)
             };
-
-            let temp_dir = skills_dir.join(format!(".{}.tmp", name));
-            if temp_dir.exists() {
-                fs::remove_dir_all(&temp_dir).ok();
+            // A failed hook leaves the skill installed; it is reported at the end.
+            match install_skill_counted(
+                &manifest.source_url,
+                paths,
+                &install_options,
+                registry,
+                &mut hook,
+            ) {
+                Ok(bytes) => {
+                    successful += 1;
+                    summary.downloaded += 1;
+                    summary.bytes += bytes;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{}",
+                        style::error(&format!("[{}] Failed to resolve: {}", name, e))
+                    );
+                    failed.push(name.clone());
+                    if !options.keep_going {
+                        return Err(SkillsError::SyncFailed { successful, failed });
+                    }
+                }
             }
-            if let Err(e) = fs::create_dir_all(&temp_dir) {
-                eprintln!("[{}] Failed to create temp directory: {}", name, e);
+        }
+        config = SkillsConfig::from_file(config_path)?;
+    }
+
+    let mut skill_names: Vec<String> = config
+        .skills
+        .keys()
+        .filter(|name| options.selects(name))
+        .cloned()
+        .collect();
+    if options.only_missing {
+        let selected = skill_names.len();
+        skill_names.retain(|name| {
+            !paths
+                .skill_dir(&config, name)
+                .is_ok_and(|dir| dir.exists() || is_symlink(&dir))
+        });
+        let present = selected - skill_names.len();
+        if present > 0 {
+            println!(
+                "Skipped {} skill(s) already on disk (--only-missing)",
+                present
+            );
+        }
+    }
+    let mut downloaded = Vec::new();
+
+    // Hash every skill that will be compared up front, in parallel; prompts
+    // about mismatches then come one at a time in the loop below.
+    let compared: Vec<&String> = skill_names
+        .iter()
+        .filter(|name| !conflicting.contains(*name))
+        .collect();
+    let jobs = match options.jobs {
+        0 => default_jobs(),
+        jobs => jobs,
+    };
+    let mut checks = checksum_pass(&config, paths, &compared, options, jobs);
+
+    for name in skill_names {
+        if conflicting.contains(&name) {
+            println!("[{}] Skipped - directory conflict", name);
+            continue;
+        }
+        summary.checked += 1;
+        let entry = config.skills[&name].clone();
+        let use_cache = !options.no_cache_checksum;
+        let skills_dir = match paths.root_dir(&config, entry.root.as_deref()) {
+            Ok(dir) => dir,
+            Err(e) => {
+                eprintln!("{}", style::error(&format!("[{}] {}", name, e)));
+                failed.push(name);
+                if !options.keep_going {
+                    break;
+                }
                 continue;
             }
+        };
+        let skill_dir = skills_dir.join(&name);
 
-            let archive_url = provider.archive_url_for_entry(entry);
-            let target = ExtractTarget {
-                path: entry.path.clone(),
-                dest_dir: temp_dir.clone(),
-            };
+        if entry.is_local() {
+            // Adopted skills have nothing to download; only report drift.
+            if !skill_dir.exists() {
+                eprintln!(
+                    "{}",
+                    style::error(&format!(
+                        "[{}] Local skill is missing and has no source to download from",
+                        name
+                    ))
+                );
+                failed.push(name);
+                if !options.keep_going {
+                    break;
+                }
+            } else if let Ok(true) = verify_checksum(
+                &skill_dir,
+                &name,
+                &mut config,
+                use_cache,
+                checks.remove(&name),
+            ) {
+                println!("[{}] Up to date (local)", name);
+            } else {
+                println!(
+                    "{}",
+                    style::warning(&format!(
+                        "[{}] Local skill changed since it was adopted",
+                        name
+                    ))
+                );
+            }
+            continue;
+        }
 
-            match provider.fetch_and_extract(&archive_url, &[target]) {
-                Ok(_) => {
-                    if let Err(e) = ensure_skill_manifest(&temp_dir) {
-                        eprintln!("[{}] Downloaded but invalid skill: {}", name, e);
-                        fs::remove_dir_all(&temp_dir).ok();
-                        continue;
-                    }
+        if is_symlink(&skill_dir) && !options.force {
+            // Likely a dev checkout linked in by hand; never write through it.
+            println!(
+                "{}",
+                style::warning(&format!(
+                    "[{}] Skipped - {} is a symlink; pass --force to replace it",
+                    name,
+                    skill_dir.display()
+                ))
+            );
+            continue;
+        }
+
+        let state = skill_state(&skill_dir, &entry, options, checks.remove(&name));
+        if let SkillState::UpToDate(_) | SkillState::Modified = &state
+            && let Some(entry) = config.skills.get_mut(&name)
+        {
+            // Only a verified match may skip hashing next time.
+            entry.tree_mtime = match state {
+                SkillState::UpToDate(mtime) => mtime,
+                _ => None,
+            };
+        }
+        let needs_download = match state {
+            SkillState::Missing => {
+                println!("[{}] Downloading...", name);
+                true
+            }
+            SkillState::Refresh => {
+                println!("[{}] Refreshing...", name);
+                true
+            }
+            SkillState::Unverifiable(reason) => {
+                println!(
+                    "{}",
+                    style::warning(&format!(
+                        "[{}] Cannot verify the checksum in skills.lock ({}); downloading again to record a new one",
+                        name, reason
+                    ))
+                );
+                true
+            }
+            SkillState::UpToDate(_) => {
+                println!("[{}] Up to date", name);
+                false
+            }
+            SkillState::Modified => {
+                println!(
+                    "{}",
+                    style::warning(&format!(
+                        "[{}] Checksum mismatch - local modifications detected",
+                        name
+                    ))
+                );
 
-                    if skill_dir.exists() {
-                        fs::remove_dir_all(&skill_dir).ok();
+                match options.local_changes {
+                    LocalChanges::Prompt => confirm_action("Overwrite local changes?"),
+                    LocalChanges::Discard => {
+                        println!("[{}] Overwriting local changes (--overwrite-dirty)", name);
+                        true
                     }
-                    match fs::rename(&temp_dir, &skill_dir) {
-                        Ok(_) => match calculate_checksum(&skill_dir) {
-                            Ok(checksum) => {
-                                if let Some(entry) = config.skills.get_mut(&name) {
-                                    entry.checksum = checksum;
-                                }
-                                println!("[{}] Downloaded successfully", name);
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "[{}] Downloaded but failed to calculate checksum: {}",
-                                    name, e
-                                );
-                            }
-                        },
-                        Err(e) => {
-                            eprintln!("[{}] Failed to move to final location: {}", name, e);
-                            fs::remove_dir_all(&temp_dir).ok();
-                        }
+                    LocalChanges::Keep => {
+                        println!("[{}] Keeping local changes (--allow-dirty)", name);
+                        false
                     }
                 }
-                Err(e) => {
-                    eprintln!("[{}] Download failed: {}", name, e);
-                    fs::remove_dir_all(&temp_dir).ok();
+            }
+            SkillState::Unreadable(e) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!("[{}] Error calculating checksum: {}", name, e))
+                );
+                true
+            }
+        };
+
+        if !needs_download {
+            continue;
+        }
+        if let Err(e) = config.settings.check_source(&entry.source_url) {
+            eprintln!("{}", style::error(&format!("[{}] {}", name, e)));
+            failed.push(name);
+            if !options.keep_going {
+                break;
+            }
+            continue;
+        }
+
+        match download_skill(
+            &name,
+            &entry,
+            &skills_dir,
+            &config.settings,
+            options,
+            registry,
+        ) {
+            Ok((checksum, bytes)) => {
+                summary.downloaded += 1;
+                summary.bytes += bytes;
+                if let Some(entry) = config.skills.get_mut(&name) {
+                    entry.checksum = checksum;
+                    entry.updated_at = Some(current_timestamp());
+                    entry.tree_mtime = cacheable_tree_mtime(&skills_dir.join(&name));
+                }
+                println!(
+                    "{}",
+                    style::success(&format!("[{}] Downloaded successfully", name))
+                );
+                successful += 1;
+                downloaded.push((name, skills_dir));
+            }
+            Err(_) => {
+                failed.push(name);
+                if !options.keep_going {
+                    break;
                 }
             }
         }
     }
 
-    config.save(&config_path)?;
+    config.save(config_path)?;
 
-    Ok(())
+    for (name, skills_dir) in &downloaded {
+        hook.run(&config.hooks, name, &skills_dir.join(name));
+    }
+
+    if !failed.is_empty() {
+        return Err(SkillsError::SyncFailed { successful, failed });
+    }
+
+    hook.finish()
+}
+
+/// Whether a skill directory matches its recorded checksum, and the tree
+/// mtime to record for it.
+type ChecksumCheck = io::Result<(bool, Option<u64>)>;
+
+/// Compare `skill_dir` with the checksum recorded in `entry`. An unchanged
+/// tree mtime is trusted without hashing when `use_cache` is set; after a
+/// full hash that matches, the new mtime is returned for next time.
+fn check_checksum(skill_dir: &Path, entry: &SkillEntry, use_cache: bool) -> ChecksumCheck {
+    if use_cache && entry.tree_mtime.is_some() && tree_mtime(skill_dir).ok() == entry.tree_mtime {
+        return Ok((true, entry.tree_mtime));
+    }
+
+    let matches = checksum_matches(skill_dir, &entry.checksum)?;
+    let mtime = if matches {
+        cacheable_tree_mtime(skill_dir)
+    } else {
+        None
+    };
+    Ok((matches, mtime))
+}
+
+/// Check, on up to `jobs` threads, every skill of `names` whose directory
+/// sync will compare with its checksum.
+fn checksum_pass(
+    config: &SkillsConfig,
+    paths: &SkillsPaths,
+    names: &[&String],
+    options: &SyncOptions,
+    jobs: usize,
+) -> HashMap<String, ChecksumCheck> {
+    let use_cache = !options.no_cache_checksum;
+    let pending: Vec<(&String, PathBuf, &SkillEntry)> = names
+        .iter()
+        .filter_map(|name| {
+            let entry = config.skills.get(*name)?;
+            let skill_dir = paths
+                .root_dir(config, entry.root.as_deref())
+                .ok()?
+                .join(name);
+            let compared = entry.is_local()
+                || (!options.refresh
+                    && !entry.is_pending()
+                    && (options.force || !is_symlink(&skill_dir)));
+            (compared && skill_dir.exists()).then_some((*name, skill_dir, entry))
+        })
+        .collect();
+
+    let results = parallel_map(&pending, jobs, |(_, skill_dir, entry)| {
+        check_checksum(skill_dir, entry, use_cache)
+    });
+    pending
+        .into_iter()
+        .map(|(name, _, _)| name.clone())
+        .zip(results)
+        .collect()
+}
+
+/// Whether `skill_dir` still matches the recorded checksum of `name`, taking
+/// the result of the checksum pass when there is one, and record the tree
+/// mtime that goes with it.
+fn verify_checksum(
+    skill_dir: &Path,
+    name: &str,
+    config: &mut SkillsConfig,
+    use_cache: bool,
+    checked: Option<ChecksumCheck>,
+) -> io::Result<bool> {
+    let Some(entry) = config.skills.get_mut(name) else {
+        return Ok(false);
+    };
+    let (matches, mtime) = match checked {
+        Some(check) => check?,
+        None => check_checksum(skill_dir, entry, use_cache)?,
+    };
+    entry.tree_mtime = mtime;
+    Ok(matches)
+}
+
+/// A fresh scratch directory for a sync download of `name`, unique to this
+/// process so concurrent runs don't extract into each other's.
+pub(super) fn temp_dir_for(skills_dir: &Path, name: &str) -> PathBuf {
+    skills_dir.join(unique_temp_name(&temp_stem(name)))
+}
+
+/// Scratch directories of `name` left behind by interrupted syncs.
+pub(super) fn leftover_temp_dirs(skills_dir: &Path, name: &str) -> Vec<PathBuf> {
+    let stem = temp_stem(name);
+    let Ok(entries) = fs::read_dir(skills_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_temp_name_for(&entry.file_name().to_string_lossy(), &stem))
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn temp_stem(name: &str) -> String {
+    name.replace('/', "__")
+}
+
+/// Download a single skill into place and return its new checksum along with
+/// the bytes transferred. Failures are reported on stderr with the skill name
+/// before being returned. A download that does not match the locked checksum
+/// is an error with `--frozen` and leaves the installed copy alone.
+fn download_skill(
+    name: &str,
+    entry: &SkillEntry,
+    skills_dir: &Path,
+    settings: &Settings,
+    options: &SyncOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<(String, u64)> {
+    let provider = registry.get(&entry.source_url).inspect_err(|_| {
+        eprintln!(
+            "{}",
+            style::error(&format!(
+                "[{}] No provider available for: {}",
+                name, entry.source_url
+            ))
+        );
+    })?;
+
+    let temp_dir = temp_dir_for(skills_dir, name);
+    fs::create_dir_all(&temp_dir).inspect_err(|e| {
+        eprintln!(
+            "{}",
+            style::error(&format!(
+                "[{}] Failed to create temp directory: {}",
+                name, e
+            ))
+        );
+    })?;
+
+    let archive_url = provider.archive_url_for_entry(entry);
+    let target = ExtractTarget {
+        path: entry.path.clone(),
+        dest_dir: temp_dir.clone(),
+    };
+
+    let stats = provider
+        .fetch_and_extract(&archive_url, &[target])
+        .inspect_err(|e| {
+            eprintln!(
+                "{}",
+                style::error(&format!("[{}] Download failed: {}", name, e))
+            );
+            fs::remove_dir_all(&temp_dir).ok();
+        })?;
+
+    let manifest_names = &settings.manifest_names;
+    let has_manifest = if entry.no_subdir {
+        Ok(())
+    } else {
+        ensure_skill_manifest(&temp_dir, manifest_names)
+    };
+    let valid = has_manifest.and_then(|_| {
+        let required_fields = settings.required_front_matter(options.strict_manifest);
+        let missing = missing_front_matter(&temp_dir, manifest_names, &required_fields);
+        if !missing.is_empty() {
+            return Err(SkillsError::MissingFrontMatter {
+                name: name.to_string(),
+                missing,
+            });
+        }
+        if options.verify_signatures {
+            verify_skill_signature(&temp_dir, &settings.trusted_keys).map_err(|reason| {
+                SkillsError::SignatureVerificationFailed {
+                    name: name.to_string(),
+                    reason,
+                }
+            })?;
+        }
+        Ok(())
+    });
+    if let Err(e) = valid {
+        eprintln!(
+            "{}",
+            style::error(&format!("[{}] Downloaded but invalid skill: {}", name, e))
+        );
+        fs::remove_dir_all(&temp_dir).ok();
+        return Err(e);
+    }
+
+    let checksum = match locked_checksum(name, entry, &temp_dir, options.frozen) {
+        Ok(checksum) => checksum,
+        Err(e) => {
+            fs::remove_dir_all(&temp_dir).ok();
+            return Err(e);
+        }
+    };
+
+    let skill_dir = skills_dir.join(name);
+    if is_symlink(&skill_dir) || skill_dir.exists() {
+        remove_skill_dir(&skill_dir).ok();
+    }
+    if let Some(parent) = skill_dir.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if let Err(e) = move_dir(&temp_dir, &skill_dir) {
+        eprintln!(
+            "{}",
+            style::error(&format!(
+                "[{}] Failed to move to final location: {}",
+                name, e
+            ))
+        );
+        fs::remove_dir_all(&temp_dir).ok();
+        return Err(e.into());
+    }
+
+    Ok((checksum, stats.bytes))
+}
+
+/// Checksum of a fresh download of `entry` in `dir`, compared with the one in
+/// the lock. The locked commit should always produce the same files; when it
+/// does not, `frozen` makes that an error and otherwise it is a warning.
+fn locked_checksum(
+    name: &str,
+    entry: &SkillEntry,
+    dir: &Path,
+    frozen: bool,
+) -> SkillsResult<String> {
+    if entry.checksum == SKIPPED_CHECKSUM {
+        return Ok(entry.checksum.clone());
+    }
+    let algorithm = ChecksumAlgorithm::of_checksum(&entry.checksum).unwrap_or_default();
+    let checksum = calculate_checksum(dir, algorithm).map_err(|e| {
+        eprintln!(
+            "{}",
+            style::error(&format!(
+                "[{}] Downloaded but failed to calculate checksum: {}",
+                name, e
+            ))
+        );
+        SkillsError::from(e)
+    })?;
+    // A pending skill has nothing locked to compare with yet.
+    if checksum == entry.checksum || entry.is_pending() {
+        return Ok(checksum);
+    }
+    if frozen {
+        let err = SkillsError::LockedChecksumMismatch {
+            name: name.to_string(),
+            expected: entry.checksum.clone(),
+            actual: checksum,
+        };
+        eprintln!("{}", style::error(&format!("[{}] {}", name, err)));
+        return Err(err);
+    }
+    eprintln!(
+        "{}",
+        style::warning_err(&format!(
+            "[{}] Downloaded files differ from skills.lock; recording the new checksum",
+            name
+        ))
+    );
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_checksum_in_frozen_mode() {
+        let temp_dir = std::env::temp_dir().join("skills_test_sync_locked_checksum");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+        let checksum = calculate_checksum(&temp_dir, ChecksumAlgorithm::default()).unwrap();
+
+        let entry = SkillEntry::local(checksum.clone(), None);
+        assert_eq!(
+            locked_checksum("demo", &entry, &temp_dir, true).unwrap(),
+            checksum
+        );
+
+        let entry = SkillEntry::local("sha256:0".to_string(), None);
+        assert_eq!(
+            locked_checksum("demo", &entry, &temp_dir, false).unwrap(),
+            checksum
+        );
+        assert!(matches!(
+            locked_checksum("demo", &entry, &temp_dir, true),
+            Err(SkillsError::LockedChecksumMismatch { .. })
+        ));
+
+        // Recorded by `install --manifest-only`: nothing to compare with yet.
+        let entry = SkillEntry::local(String::new(), None);
+        assert_eq!(
+            locked_checksum("demo", &entry, &temp_dir, true).unwrap(),
+            checksum
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_checksum_pass_matches_serial() {
+        let temp_dir = std::env::temp_dir().join("skills_test_sync_checksum_pass");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+
+        let mut config = SkillsConfig::default();
+        for idx in 0..64 {
+            let name = format!("skill-{idx:02}");
+            let skill_dir = paths.skills_dir.join(&name);
+            fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+            fs::write(skill_dir.join("SKILL.md"), format!("# Skill {idx}")).unwrap();
+            fs::write(skill_dir.join("scripts/run.sh"), b"echo hi").unwrap();
+            let checksum = calculate_checksum(&skill_dir, ChecksumAlgorithm::default()).unwrap();
+            let mut entry = SkillEntry::local(checksum, None);
+            entry.source_url = format!("https://github.com/owner/repo/tree/main/{name}");
+            config.skills.insert(name, entry);
+        }
+        // Local edits to every seventh skill.
+        for idx in (0..64).step_by(7) {
+            let skill_dir = paths.skills_dir.join(format!("skill-{idx:02}"));
+            fs::write(skill_dir.join("SKILL.md"), b"# Edited").unwrap();
+        }
+
+        let names: Vec<&String> = config.skills.keys().collect();
+        let options = SyncOptions {
+            no_cache_checksum: true,
+            ..Default::default()
+        };
+        let outcome = |checks: HashMap<String, ChecksumCheck>| {
+            let mut outcome: Vec<(String, bool)> = checks
+                .into_iter()
+                .map(|(name, check)| (name, check.unwrap().0))
+                .collect();
+            outcome.sort();
+            outcome
+        };
+        let serial = outcome(checksum_pass(&config, &paths, &names, &options, 1));
+        let parallel = outcome(checksum_pass(&config, &paths, &names, &options, 8));
+
+        assert_eq!(serial.len(), 64);
+        assert_eq!(serial, parallel);
+        let mismatched: Vec<&str> = parallel
+            .iter()
+            .filter(|(_, matches)| !matches)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(mismatched.len(), 10);
+        assert!(mismatched.contains(&"skill-07"));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sync_skips_symlinked_skill() {
+        let temp_dir = std::env::temp_dir().join("skills_test_sync_symlink");
+        fs::remove_dir_all(&temp_dir).ok();
+        let checkout = temp_dir.join("dev/demo");
+        fs::create_dir_all(&checkout).unwrap();
+        fs::write(checkout.join("SKILL.md"), b"# Local edits").unwrap();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+        fs::create_dir_all(&paths.skills_dir).unwrap();
+        std::os::unix::fs::symlink(&checkout, paths.skills_dir.join("demo")).unwrap();
+
+        let mut entry = SkillEntry::local("sha256:stale".to_string(), None);
+        entry.source_url = "https://github.com/owner/repo/tree/main/demo".to_string();
+        let mut config = SkillsConfig::default();
+        config.skills.insert("demo".to_string(), entry);
+        config.save(&paths.manifest).unwrap();
+
+        // No providers: reaching the download step would fail the sync.
+        let registry = ProviderRegistry::new(Vec::new());
+        let options = SyncOptions {
+            refresh: true,
+            quiet: true,
+            ..Default::default()
+        };
+        sync_skills(&paths, &options, &registry).unwrap();
+
+        assert!(is_symlink(&paths.skills_dir.join("demo")));
+        assert_eq!(
+            fs::read(checkout.join("SKILL.md")).unwrap(),
+            b"# Local edits"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_sync_reports_without_writing() {
+        let temp_dir = std::env::temp_dir().join("skills_test_check_sync");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+        let present = paths.skills_dir.join("present");
+        fs::create_dir_all(&present).unwrap();
+        fs::write(present.join("SKILL.md"), b"# Present").unwrap();
+        let checksum = calculate_checksum(&present, ChecksumAlgorithm::default()).unwrap();
+
+        let mut config = SkillsConfig::default();
+        for (name, checksum) in [("present", checksum), ("missing", "sha256:abc".to_string())] {
+            let mut entry = SkillEntry::local(checksum, None);
+            entry.source_url = format!("https://github.com/owner/repo/tree/main/{}", name);
+            config.skills.insert(name.to_string(), entry);
+        }
+        config.save(&paths.manifest).unwrap();
+        let manifest = fs::read(&paths.manifest).unwrap();
+        let lock = fs::read(temp_dir.join("skills.lock")).unwrap();
+
+        let changes = check_sync(&paths, &SyncOptions::default()).unwrap();
+
+        assert_eq!(changes, 1);
+        assert!(!paths.skills_dir.join("missing").exists());
+        assert_eq!(fs::read(&paths.manifest).unwrap(), manifest);
+        assert_eq!(fs::read(temp_dir.join("skills.lock")).unwrap(), lock);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_sync_only_missing_leaves_present_skills() {
+        let temp_dir = std::env::temp_dir().join("skills_test_sync_only_missing");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+        fs::create_dir_all(paths.skills_dir.join("present")).unwrap();
+        fs::write(paths.skills_dir.join("present/SKILL.md"), b"# Edited").unwrap();
+
+        let mut config = SkillsConfig::default();
+        for name in ["present", "missing"] {
+            let mut entry = SkillEntry::local("sha256:stale".to_string(), None);
+            entry.source_url = format!("https://github.com/owner/repo/tree/main/{}", name);
+            config.skills.insert(name.to_string(), entry);
+        }
+        config.save(&paths.manifest).unwrap();
+
+        // No providers: only the skill that reaches the download step fails.
+        let registry = ProviderRegistry::new(Vec::new());
+        let options = SyncOptions {
+            only_missing: true,
+            keep_going: true,
+            quiet: true,
+            ..Default::default()
+        };
+        let result = sync_skills(&paths, &options, &registry);
+
+        assert!(
+            matches!(result, Err(SkillsError::SyncFailed { failed, .. }) if failed == ["missing"])
+        );
+        assert_eq!(
+            fs::read(paths.skills_dir.join("present/SKILL.md")).unwrap(),
+            b"# Edited"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
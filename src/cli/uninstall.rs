@@ -1,29 +1,244 @@
-use crate::{errors::SkillsResult, models::SkillsConfig};
-use std::{fs, path::Path};
+use crate::{
+    errors::SkillsResult,
+    models::SkillsConfig,
+    style,
+    utils::{glob_match, is_glob, is_symlink, prune_empty_parents, remove_skill_dir},
+};
+use std::fs;
 
-pub fn uninstall_skill(name: &str, base_dir: &Path) -> SkillsResult<()> {
-    let config_path = base_dir.join("skills.toml");
-    let mut config = SkillsConfig::from_file(&config_path)?;
+use super::{SkillsPaths, prompt::confirm_action_or_yes, sync::leftover_temp_dirs};
 
-    let skills_dir = base_dir.join("skills");
-    let skill_dir = skills_dir.join(name);
+/// Options controlling a single `uninstall` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct UninstallOptions {
+    /// Remove every skill in the manifest.
+    pub all: bool,
+    /// Automatically answer yes to prompts.
+    pub yes: bool,
+    /// Also remove skill directories that `skills.toml` doesn't know about,
+    /// and any leftover temp directories.
+    pub force: bool,
+}
 
-    let mut removed_any = false;
-    if skill_dir.exists() {
-        fs::remove_dir_all(&skill_dir)?;
-        removed_any = true;
-    }
+/// Uninstall the skills matching `patterns`, which may be exact names or
+/// globs such as `legacy-*`. Globs and `--all` list the matches and ask for
+/// confirmation first.
+pub fn uninstall_skills(
+    patterns: &[String],
+    paths: &SkillsPaths,
+    options: &UninstallOptions,
+) -> SkillsResult<()> {
+    let config_path = &paths.manifest;
+    let mut config = SkillsConfig::from_file(config_path)?;
 
-    if config.skills.remove(name).is_some() {
-        removed_any = true;
-        config.save(&config_path)?;
+    let mut declared: Vec<String> = config
+        .skills
+        .keys()
+        .chain(config.unresolved.keys())
+        .cloned()
+        .collect();
+    if options.force && !options.all {
+        for name in untracked_dirs(paths, &declared) {
+            declared.push(name);
+        }
     }
 
-    if removed_any {
-        println!("Successfully uninstalled skill '{}'.", name);
+    let mut names = Vec::new();
+    let mut unmatched = Vec::new();
+    if options.all {
+        names = declared.clone();
     } else {
-        println!("Skill '{}' is not installed.", name);
+        for pattern in patterns {
+            let matches: Vec<&String> = if is_glob(pattern) {
+                declared
+                    .iter()
+                    .filter(|name| glob_match(pattern, name))
+                    .collect()
+            } else if declared.contains(pattern)
+                || paths.skills_dir.join(pattern).exists()
+                || (options.force && !leftover_temp_dirs(&paths.skills_dir, pattern).is_empty())
+            {
+                vec![pattern]
+            } else {
+                Vec::new()
+            };
+            if matches.is_empty() {
+                unmatched.push(pattern.as_str());
+            }
+            for name in matches {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+    }
+
+    if (options.all || patterns.iter().any(|p| is_glob(p))) && !names.is_empty() {
+        println!("The following skill(s) will be uninstalled:");
+        for name in &names {
+            println!("  - {}", name);
+        }
+        println!();
+
+        if !confirm_action_or_yes("Uninstall these skills?", options.yes) {
+            println!("Uninstall cancelled.");
+            return Ok(());
+        }
+    }
+
+    for name in &names {
+        if !options.force {
+            remove_skill(name, &mut config, paths)?;
+            println!(
+                "{}",
+                style::success(&format!("Successfully uninstalled skill '{}'.", name))
+            );
+            continue;
+        }
+
+        let mut removed = Vec::new();
+        if config.skills.contains_key(name) || config.unresolved.contains_key(name) {
+            removed.push("entry in skills.toml".to_string());
+        }
+        let skills_dir = paths.root_dir(&config, config.root(name))?;
+        let skill_dir = skills_dir.join(name);
+        if is_symlink(&skill_dir) {
+            removed.push(format!("symlink {}", skill_dir.display()));
+        } else if skill_dir.exists() {
+            removed.push(format!("directory {}", skill_dir.display()));
+        }
+        remove_skill(name, &mut config, paths)?;
+        for temp_dir in leftover_temp_dirs(&skills_dir, name) {
+            fs::remove_dir_all(&temp_dir)?;
+            removed.push(format!("temp directory {}", temp_dir.display()));
+        }
+        println!(
+            "{}",
+            style::success(&format!("Uninstalled skill '{}'. Removed:", name))
+        );
+        for item in removed {
+            println!("  - {}", item);
+        }
+    }
+
+    if !names.is_empty() {
+        config.save(config_path)?;
+    }
+
+    if options.all && names.is_empty() {
+        println!("No skills installed.");
+    }
+    for pattern in unmatched {
+        if is_glob(pattern) {
+            println!("No installed skill matches '{}'.", pattern);
+        } else {
+            println!("Skill '{}' is not installed.", pattern);
+        }
     }
 
     Ok(())
 }
+
+/// Skill directories in the default skills directory that are not in
+/// `declared`. Hidden and temp directories are skipped.
+fn untracked_dirs(paths: &SkillsPaths, declared: &[String]) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(&paths.skills_dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_dir()))
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| !name.starts_with('.') && !declared.contains(name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Remove the directory and manifest entry of `name`.
+pub(super) fn remove_skill(
+    name: &str,
+    config: &mut SkillsConfig,
+    paths: &SkillsPaths,
+) -> SkillsResult<()> {
+    let root_dir = paths.root_dir(config, config.root(name))?;
+    let skill_dir = root_dir.join(name);
+    if is_symlink(&skill_dir) {
+        let target = fs::read_link(&skill_dir)?;
+        remove_skill_dir(&skill_dir)?;
+        println!(
+            "{}",
+            style::warning(&format!(
+                "'{}' was a symlink; removed the link and kept its target {}",
+                name,
+                target.display()
+            ))
+        );
+    } else if skill_dir.exists() {
+        remove_skill_dir(&skill_dir)?;
+    }
+
+    // Nested skills leave their category directory behind once it is empty.
+    prune_empty_parents(&skill_dir, &root_dir);
+
+    config.skills.remove(name);
+    config.unresolved.remove(name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SkillEntry;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_remove_skill_removes_symlink_only() {
+        let temp_dir = std::env::temp_dir().join("skills_test_uninstall_symlink");
+        fs::remove_dir_all(&temp_dir).ok();
+        let checkout = temp_dir.join("dev/demo");
+        fs::create_dir_all(&checkout).unwrap();
+        fs::write(checkout.join("SKILL.md"), b"# Demo").unwrap();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+        fs::create_dir_all(&paths.skills_dir).unwrap();
+        std::os::unix::fs::symlink(&checkout, paths.skills_dir.join("demo")).unwrap();
+
+        let mut config = SkillsConfig::default();
+        config.skills.insert(
+            "demo".to_string(),
+            SkillEntry::local("sha256:abc".to_string(), None),
+        );
+        remove_skill("demo", &mut config, &paths).unwrap();
+
+        assert!(fs::symlink_metadata(paths.skills_dir.join("demo")).is_err());
+        assert!(checkout.join("SKILL.md").exists());
+        assert!(config.skills.is_empty());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_skill_prunes_empty_parents() {
+        let temp_dir = std::env::temp_dir().join("skills_test_uninstall_prune");
+        fs::remove_dir_all(&temp_dir).ok();
+        let paths = SkillsPaths::from_manifest(temp_dir.join("skills.toml"));
+        fs::create_dir_all(paths.skills_dir.join("a/b/skill")).unwrap();
+        fs::create_dir_all(paths.skills_dir.join("c/skill")).unwrap();
+        fs::write(paths.skills_dir.join("c/.keep"), b"").unwrap();
+
+        let mut config = SkillsConfig::default();
+        for name in ["a/b/skill", "c/skill"] {
+            config.skills.insert(
+                name.to_string(),
+                SkillEntry::local("sha256:abc".to_string(), None),
+            );
+            remove_skill(name, &mut config, &paths).unwrap();
+        }
+
+        assert!(!paths.skills_dir.join("a").exists());
+        assert!(paths.skills_dir.join("c/.keep").exists());
+        assert!(paths.skills_dir.is_dir());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -1,33 +1,144 @@
-use crate::{errors::SkillsResult, models::SkillsConfig, providers::ProviderRegistry};
-use std::path::Path;
+use super::{
+    SkillsPaths,
+    install::{InstallOptions, LocalChanges, install_skill},
+    outdated::short_sha,
+    prompt::confirm_action_or_yes,
+    uninstall::remove_skill,
+};
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    models::{SkillEntry, SkillsConfig},
+    providers::ProviderRegistry,
+    style,
+    utils::{prefixed_name, same_commit},
+};
 
-use super::install::install_skill;
+/// Options controlling a single `update` invocation.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    /// Automatically answer yes to prompts.
+    pub yes: bool,
+    /// Re-download skills even when they are already up to date.
+    pub refresh: bool,
+    /// What to do with skills whose files were edited locally.
+    pub local_changes: LocalChanges,
+}
+
+impl UpdateOptions {
+    fn install_options(&self, name: &str, entry: &SkillEntry) -> InstallOptions {
+        InstallOptions {
+            yes: self.yes,
+            refresh: self.refresh,
+            local_changes: self.local_changes,
+            ..InstallOptions::for_entry(name, entry)
+        }
+    }
+}
 
 pub fn update_skill(
     name: &str,
-    base_dir: &Path,
-    yes: bool,
+    paths: &SkillsPaths,
+    options: &UpdateOptions,
     registry: &ProviderRegistry,
 ) -> SkillsResult<()> {
-    let config_path = base_dir.join("skills.toml");
-    let config = SkillsConfig::from_file(&config_path)?;
+    let config_path = &paths.manifest;
+    let config = SkillsConfig::from_file(config_path)?;
 
     let Some(entry) = config.skills.get(name) else {
         println!("Skill '{}' is not installed.", name);
         return Ok(());
     };
+    if entry.is_local() {
+        return Err(SkillsError::LocalSkill(name.to_string()));
+    }
+
+    install_with_branch_fallback(&entry.source_url, name, entry, paths, options, registry)
+}
+
+/// Compare the recorded commit of `name` (or of every skill when `None`)
+/// with upstream, without downloading anything or touching `skills.toml`.
+/// Returns how many skills have an update available.
+pub fn check_updates(
+    name: Option<&str>,
+    paths: &SkillsPaths,
+    registry: &ProviderRegistry,
+) -> SkillsResult<usize> {
+    let config = SkillsConfig::from_file(&paths.manifest)?;
+
+    let single = name.is_some();
+    let entries: Vec<(&String, &SkillEntry)> = match name {
+        Some(name) => {
+            let Some((name, entry)) = config.skills.get_key_value(name) else {
+                println!("Skill '{}' is not installed.", name);
+                return Ok(0);
+            };
+            if entry.is_local() {
+                return Err(SkillsError::LocalSkill(name.clone()));
+            }
+            vec![(name, entry)]
+        }
+        None => config
+            .skills
+            .iter()
+            .filter(|(_, entry)| !entry.is_local())
+            .collect(),
+    };
+    if entries.is_empty() {
+        println!("No skills installed.");
+        return Ok(0);
+    }
 
-    install_skill(&entry.source_url, base_dir, yes, registry)
+    let mut available = 0;
+    let mut failed = Vec::new();
+    for (name, entry) in entries {
+        let latest = registry
+            .get(&entry.source_url)
+            .and_then(|provider| provider.latest_commit(entry));
+        match latest {
+            Ok(Some(commit)) if same_commit(&commit.sha, &entry.sha) => {
+                println!("{}: up to date", name);
+            }
+            Ok(Some(commit)) => {
+                available += 1;
+                println!(
+                    "{}: update available: {} -> {}",
+                    name,
+                    short_sha(&entry.sha),
+                    short_sha(&commit.sha)
+                );
+            }
+            Ok(None) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!("[{}] Upstream no longer resolves", name))
+                );
+                failed.push(name.clone());
+            }
+            Err(e) if single => return Err(e),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style::error(&format!("[{}] Failed to check upstream: {}", name, e))
+                );
+                failed.push(name.clone());
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(SkillsError::UpdateCheckFailed(failed));
+    }
+    Ok(available)
 }
 
 pub fn update_collection_for_skill(
     name: &str,
-    base_dir: &Path,
-    yes: bool,
+    paths: &SkillsPaths,
+    options: &UpdateOptions,
     registry: &ProviderRegistry,
 ) -> SkillsResult<()> {
-    let config_path = base_dir.join("skills.toml");
-    let config = SkillsConfig::from_file(&config_path)?;
+    let config_path = &paths.manifest;
+    let config = SkillsConfig::from_file(config_path)?;
 
     let Some(entry) = config.skills.get(name) else {
         println!("Skill '{}' is not installed.", name);
@@ -42,5 +153,134 @@ pub fn update_collection_for_skill(
         return Ok(());
     };
 
-    install_skill(collection_url, base_dir, yes, registry)
+    install_with_branch_fallback(collection_url, name, entry, paths, options, registry)
+}
+
+/// Install from `url`, retrying on the repository's current default branch
+/// when the recorded default branch no longer resolves (e.g. `master` was
+/// renamed to `main`).
+fn install_with_branch_fallback(
+    url: &str,
+    name: &str,
+    entry: &SkillEntry,
+    paths: &SkillsPaths,
+    options: &UpdateOptions,
+    registry: &ProviderRegistry,
+) -> SkillsResult<()> {
+    let install_options = options.install_options(name, entry);
+    let err = match install_skill(url, paths, &install_options, registry) {
+        Err(err @ SkillsError::PathNotFound(_)) => err,
+        result => return result,
+    };
+
+    let relocated = match &entry.default_branch {
+        Some(old_branch) => registry
+            .get(url)?
+            .relocate_default_branch(url, old_branch)?
+            .map(|new_url| (old_branch, new_url)),
+        None => None,
+    };
+    let Some((old_branch, new_url)) = relocated else {
+        if url == entry.source_url {
+            return install_moved_skill(url, name, paths, options, registry, err);
+        }
+        return Err(err);
+    };
+
+    eprintln!(
+        "{}",
+        style::warning_err(&format!(
+            "Warning: branch '{}' no longer resolves; falling back to the repository's current default branch.",
+            old_branch
+        ))
+    );
+    eprintln!("  Using: {}", new_url);
+    install_skill(&new_url, paths, &install_options, registry)
+}
+
+/// A skill directory next to the one `url` points at whose manifest `name`
+/// still matches the skill, as left behind by an upstream rename or move.
+/// Returns its URL and directory name.
+fn find_moved_skill(
+    url: &str,
+    skill_name: &str,
+    registry: &ProviderRegistry,
+    manifest_names: &[String],
+) -> Option<(String, String)> {
+    let (parent, old_dir) = url.trim_end_matches('/').rsplit_once('/')?;
+    let listings = registry
+        .get(parent)
+        .ok()?
+        .browse(parent, manifest_names)
+        .ok()?;
+    listings
+        .into_iter()
+        .find(|listing| {
+            listing.has_manifest
+                && listing.name != old_dir
+                && listing.manifest_name.as_deref() == Some(skill_name)
+        })
+        .map(|listing| (format!("{}/{}", parent, listing.name), listing.name))
+}
+
+/// Offer to re-point `name` at the directory it was moved to upstream;
+/// returns `err` when no such directory is found or the user declines.
+fn install_moved_skill(
+    url: &str,
+    name: &str,
+    paths: &SkillsPaths,
+    options: &UpdateOptions,
+    registry: &ProviderRegistry,
+    err: SkillsError,
+) -> SkillsResult<()> {
+    let mut config = SkillsConfig::from_file(&paths.manifest)?;
+    let entry = &config.skills[name];
+    let upstream_name = entry.upstream_name(name);
+    let skill_name = upstream_name.rsplit('/').next().unwrap_or(&upstream_name);
+    let Some((new_url, new_dir)) =
+        find_moved_skill(url, skill_name, registry, &config.settings.manifest_names)
+    else {
+        eprintln!(
+            "{}",
+            style::warning_err(&format!(
+                "[{}] The skill may have been moved or renamed upstream.",
+                name
+            ))
+        );
+        return Err(err);
+    };
+
+    let new_upstream_name = match upstream_name.rsplit_once('/') {
+        Some((parent, _)) => format!("{}/{}", parent, new_dir),
+        None => new_dir,
+    };
+    let new_name = match &entry.prefix {
+        Some(prefix) => prefixed_name(&new_upstream_name, prefix),
+        None => new_upstream_name,
+    };
+    eprintln!(
+        "{}",
+        style::warning_err(&format!(
+            "[{}] Not found upstream; a skill with the same name now lives at {}",
+            name, new_url
+        ))
+    );
+    if !confirm_action_or_yes(&format!("Re-point '{}' to {}?", name, new_url), options.yes) {
+        return Err(err);
+    }
+
+    let install_options = InstallOptions {
+        yes: options.yes,
+        refresh: options.refresh,
+        local_changes: options.local_changes,
+        ..InstallOptions::for_entry(&new_name, entry)
+    };
+    install_skill(&new_url, paths, &install_options, registry)?;
+    if new_name != name {
+        config = SkillsConfig::from_file(&paths.manifest)?;
+        remove_skill(name, &mut config, paths)?;
+        config.save(&paths.manifest)?;
+        println!("Renamed '{}' to '{}'", name, new_name);
+    }
+    Ok(())
 }
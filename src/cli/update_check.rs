@@ -0,0 +1,207 @@
+use crate::{models::SkillsConfig, providers::ProviderRegistry, style, utils::same_commit};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::SkillsPaths;
+
+/// Seconds between two background checks for updates.
+const CHECK_INTERVAL_SECS: u64 = 86_400;
+
+/// How long a command waits at its end for a check still running.
+const CHECK_GRACE: Duration = Duration::from_secs(1);
+
+/// A background check for installed skills whose upstream ref has moved past
+/// the locked commit, run at most once a day per manifest.
+///
+/// A check that has not finished when the command ends is cut short and
+/// tried again by the next command. The result of a completed check is kept
+/// until a command has shown it.
+pub struct UpdateCheck {
+    state_path: PathBuf,
+    state: Option<CheckState>,
+    outdated: Option<mpsc::Receiver<usize>>,
+}
+
+/// What the state file of a manifest records about its last completed check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CheckState {
+    /// When the check completed, in seconds since the epoch.
+    checked_at: u64,
+    /// Skills with updates found by the check, until a hint has shown them.
+    outdated: Option<usize>,
+}
+
+impl CheckState {
+    fn parse(content: &str) -> Option<Self> {
+        let mut fields = content.split_whitespace();
+        let checked_at = fields.next()?.parse().ok()?;
+        let outdated = fields.next().and_then(|count| count.parse().ok());
+        Some(Self {
+            checked_at,
+            outdated,
+        })
+    }
+
+    fn render(&self) -> String {
+        match self.outdated {
+            Some(count) => format!("{} {}\n", self.checked_at, count),
+            None => format!("{}\n", self.checked_at),
+        }
+    }
+
+    /// Whether a new check is due at `now`.
+    fn is_due(&self, now: u64) -> bool {
+        now.saturating_sub(self.checked_at) >= CHECK_INTERVAL_SECS
+    }
+}
+
+impl UpdateCheck {
+    /// Start checking the skills of `paths` for updates on another thread,
+    /// unless `update_check = false` is set under `[config]`, there is nothing
+    /// to check, or the manifest was checked within the last day. Its state
+    /// is kept in `state_dir`, one file per manifest. The check is
+    /// best-effort: failures are ignored and nothing is printed, so
+    /// `registry` should fail rate-limited requests rather than wait them
+    /// out with a warning.
+    pub fn start(
+        paths: &SkillsPaths,
+        state_dir: &Path,
+        registry: ProviderRegistry,
+    ) -> Option<Self> {
+        let config = SkillsConfig::from_file(&paths.manifest).ok()?;
+        if config.settings.update_check == Some(false)
+            || config.skills.values().all(|entry| entry.is_local())
+        {
+            return None;
+        }
+        let state_path = state_dir.join(manifest_key(&paths.manifest));
+        let state = fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|content| CheckState::parse(&content));
+        if state.is_some_and(|state| !state.is_due(unix_now())) {
+            return Some(Self {
+                state_path,
+                state,
+                outdated: None,
+            });
+        }
+
+        let (sender, outdated) = mpsc::channel();
+        let worker_state_path = state_path.clone();
+        thread::spawn(move || {
+            let count = config
+                .skills
+                .iter()
+                .filter(|(_, entry)| !entry.is_local() && !entry.is_pending())
+                .filter(|(_, entry)| {
+                    let latest = registry
+                        .get(&entry.source_url)
+                        .and_then(|provider| provider.latest_commit(entry));
+                    matches!(latest, Ok(Some(commit)) if !same_commit(&commit.sha, &entry.sha))
+                })
+                .count();
+            // Only a completed check counts; one cut short is tried again.
+            let state = CheckState {
+                checked_at: unix_now(),
+                outdated: Some(count),
+            };
+            save_state(&worker_state_path, &state);
+            sender.send(count).ok();
+        });
+        Some(Self {
+            state_path,
+            state,
+            outdated: Some(outdated),
+        })
+    }
+
+    /// Print a one-line hint when the check, or an earlier one not shown
+    /// yet, found skills with updates. Waits at most [`CHECK_GRACE`] for a
+    /// check still running.
+    pub fn finish(self) {
+        let finished = self
+            .outdated
+            .and_then(|outdated| outdated.recv_timeout(CHECK_GRACE).ok());
+        let (count, checked_at) = match (finished, self.state) {
+            (Some(count), _) => (count, unix_now()),
+            (None, Some(state)) => match state.outdated {
+                Some(count) => (count, state.checked_at),
+                None => return,
+            },
+            (None, None) => return,
+        };
+        // Each completed check is hinted at once.
+        let shown = CheckState {
+            checked_at,
+            outdated: None,
+        };
+        save_state(&self.state_path, &shown);
+        if count > 0 {
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "{} skill(s) have updates; run `skill outdated` to see them.",
+                    count
+                ))
+            );
+        }
+    }
+}
+
+/// Name of the state file of `manifest`: a hash of its absolute path.
+fn manifest_key(manifest: &Path) -> String {
+    let path = fs::canonicalize(manifest).unwrap_or_else(|_| manifest.to_path_buf());
+    let digest = format!("{:x}", Sha256::digest(path.as_os_str().as_encoded_bytes()));
+    digest[..16].to_string()
+}
+
+fn save_state(path: &Path, state: &CheckState) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    fs::write(path, state.render()).ok();
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_state() {
+        let now = 1_700_000_000;
+        let state = CheckState::parse(&format!("{}\n", now - 60)).unwrap();
+        assert_eq!(state.outdated, None);
+        assert!(!state.is_due(now));
+        assert!(CheckState::parse("garbage").is_none());
+
+        let state = CheckState::parse(&format!("{} 3\n", now - CHECK_INTERVAL_SECS)).unwrap();
+        assert_eq!(state.outdated, Some(3));
+        assert!(state.is_due(now));
+        assert_eq!(CheckState::parse(&state.render()), Some(state));
+
+        // A state from the future (e.g. a clock change) is not due yet.
+        let state = CheckState {
+            checked_at: now + 60,
+            outdated: None,
+        };
+        assert!(!state.is_due(now));
+
+        assert_ne!(
+            manifest_key(Path::new("/one/skills.toml")),
+            manifest_key(Path::new("/two/skills.toml"))
+        );
+    }
+}
@@ -19,24 +19,67 @@ pub enum SkillsError {
         url: String,
     },
 
+    // GitHub does not show the repository without a token: it does not
+    // exist, or it is private.
+    RepositoryNotFound {
+        slug: String,
+    },
+
+    // The configured token cannot read the repository.
+    TokenAccessDenied {
+        slug: String,
+        reason: String,
+    },
+
     // GitHub API rate limit was exceeded (429).
     RateLimited,
 
+    // GitHub's secondary (abuse detection) rate limit kept rejecting
+    // requests (403 with Retry-After or a "secondary rate limit" message).
+    SecondaryRateLimited {
+        retry_after: u64,
+    },
+
     // Non-OK HTTP status from the API with a message body.
     HttpError {
         status: u16,
         message: String,
     },
 
-    // The downloaded archive could not be parsed as gzip.
+    // The downloaded archive could not be parsed.
     InvalidArchive(String),
 
+    // GitHub has not generated the archive for a commit that does exist
+    // (archive generation lags behind very fresh commits).
+    ArchiveNotReady {
+        url: String,
+    },
+
+    // An archive download returned something else, such as an HTML page
+    // from a proxy.
+    NotAnArchive {
+        url: String,
+        content_type: Option<String>,
+        snippet: String,
+    },
+
     // The requested paths do not exist at the resolved ref.
     PathNotFound(Vec<String>),
 
+    // A ref given as a branch or a tag does not exist as that kind.
+    RefNotFound {
+        slug: String,
+        kind: String,
+        name: String,
+    },
+
     // Skill directory missing the manifest file.
     MissingSkillManifest,
 
+    // The skill directory exists but has no real content (only hidden files,
+    // or an empty manifest).
+    EmptySkill(String),
+
     // Local filesystem error surfaced during IO.
     IoError(io::Error),
 
@@ -46,18 +89,249 @@ pub enum SkillsError {
     // Directory contains neither a SKILL.md nor sub-skills
     NoSkillsFound(String),
 
-    // One or more skills failed during batch installation
+    // Sub-directories of a collection have no SKILL.md or other manifest
+    NonSkillDirectories(Vec<String>),
+
+    // One or more skills failed during batch installation; `failed` pairs each
+    // skill with the reason it failed
     BatchInstallationFailed {
         successful: usize,
-        failed: Vec<String>,
+        failed: Vec<(String, String)>,
     },
 
     // No registered provider can handle the given URL
     UnsupportedProvider(String),
+
+    // The --at value is not a valid date or timestamp
+    InvalidDate(String),
+
+    // Skill name clashes with an existing one on case-insensitive filesystems
+    NameCollision {
+        name: String,
+        existing: String,
+    },
+
+    // SKILLS_MAN_INDEX is not set, so there is no index to search
+    IndexNotConfigured,
+
+    // The skills index could not be parsed
+    InvalidIndex(String),
+
+    // `doctor` found problems in the configuration
+    ProblemsFound(usize),
+
+    // Fewer or more files were extracted than the upstream tree contains
+    IncompleteExtraction {
+        expected: usize,
+        actual: usize,
+    },
+
+    // A skill refers to a root that is not declared under [roots]
+    UnknownRoot(String),
+
+    // skills.lock does not match skills.toml in --frozen mode
+    LockOutOfDate(Vec<String>),
+
+    // skills.toml was written by a newer skills-man
+    UnsupportedSchemaVersion {
+        found: u32,
+        supported: u32,
+    },
+
+    // A post_install hook exited unsuccessfully; the skills stay installed
+    HookFailed(Vec<(String, Option<i32>)>),
+
+    // `fetch` would extract into a directory that already has files
+    OutputExists(String),
+
+    // Skills named on the command line are not in skills.toml
+    SkillsNotConfigured(Vec<String>),
+
+    // `config get`/`config set` was given a key that `[config]` doesn't know
+    UnknownSetting(String),
+
+    // The value given to `config set` doesn't fit the key
+    InvalidSetting {
+        key: String,
+        reason: String,
+    },
+
+    // The skill was adopted from local files and has no upstream source
+    LocalSkill(String),
+
+    // One or more skills failed to download during sync
+    SyncFailed {
+        successful: usize,
+        failed: Vec<String>,
+    },
+
+    // `update --check` could not resolve the upstream of these skills
+    UpdateCheckFailed(Vec<String>),
+
+    // `git` is needed for git+ssh:// sources but is not installed
+    GitNotFound,
+
+    // A `git` command run for a git+ssh:// source failed
+    GitFailed {
+        command: String,
+        stderr: String,
+    },
+
+    // A release has no asset that can be installed (or none with the
+    // requested name)
+    NoReleaseAsset {
+        tag: String,
+        asset: Option<String>,
+    },
+
+    // Another process holds the manifest lock
+    ManifestLocked {
+        lock: String,
+    },
+
+    // The source repository is not matched by `allowed_sources` in [config]
+    SourceNotAllowed {
+        url: String,
+        allowed: Vec<String>,
+    },
+
+    // A download of the locked commit does not match the locked checksum
+    // (`sync --frozen`)
+    LockedChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+
+    // Installing would replace a skill from another source or an unmanaged
+    // directory (`install --no-overwrite`)
+    WouldOverwrite {
+        name: String,
+        source: Option<String>,
+    },
+
+    // A skill name (e.g. after `install --prefix`) uses characters that are
+    // unsafe in a directory name
+    InvalidSkillName(String),
+
+    // A skill's manifest front matter lacks fields that `--strict-manifest`
+    // or `required_fields` in [config] require
+    MissingFrontMatter {
+        name: String,
+        missing: Vec<String>,
+    },
+
+    // `--verify-signatures` could not verify a skill's manifest against the
+    // trusted_keys in [config]
+    SignatureVerificationFailed {
+        name: String,
+        reason: String,
+    },
+
+    // A git+ssh:// URL did not match `git+ssh://host/owner/repo#ref:path`
+    InvalidGitUrl(String),
+
+    // A command-line option that the provider of a source cannot honor
+    UnsupportedOption {
+        source: String,
+        option: String,
+    },
 }
 
 pub type SkillsResult<T> = Result<T, SkillsError>;
 
+impl SkillsError {
+    /// Name of the variant, for machine-readable error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SkillsError::InvalidUrl(..) => "InvalidUrl",
+            SkillsError::NetworkError(..) => "NetworkError",
+            SkillsError::NotFound { .. } => "NotFound",
+            SkillsError::Forbidden { .. } => "Forbidden",
+            SkillsError::RepositoryNotFound { .. } => "RepositoryNotFound",
+            SkillsError::TokenAccessDenied { .. } => "TokenAccessDenied",
+            SkillsError::RateLimited => "RateLimited",
+            SkillsError::SecondaryRateLimited { .. } => "SecondaryRateLimited",
+            SkillsError::HttpError { .. } => "HttpError",
+            SkillsError::InvalidArchive(..) => "InvalidArchive",
+            SkillsError::ArchiveNotReady { .. } => "ArchiveNotReady",
+            SkillsError::NotAnArchive { .. } => "NotAnArchive",
+            SkillsError::PathNotFound(..) => "PathNotFound",
+            SkillsError::RefNotFound { .. } => "RefNotFound",
+            SkillsError::MissingSkillManifest => "MissingSkillManifest",
+            SkillsError::EmptySkill(..) => "EmptySkill",
+            SkillsError::IoError(..) => "IoError",
+            SkillsError::ConfigParseError(..) => "ConfigParseError",
+            SkillsError::NoSkillsFound(..) => "NoSkillsFound",
+            SkillsError::NonSkillDirectories(..) => "NonSkillDirectories",
+            SkillsError::BatchInstallationFailed { .. } => "BatchInstallationFailed",
+            SkillsError::UnsupportedProvider(..) => "UnsupportedProvider",
+            SkillsError::InvalidDate(..) => "InvalidDate",
+            SkillsError::NameCollision { .. } => "NameCollision",
+            SkillsError::IndexNotConfigured => "IndexNotConfigured",
+            SkillsError::InvalidIndex(..) => "InvalidIndex",
+            SkillsError::ProblemsFound(..) => "ProblemsFound",
+            SkillsError::IncompleteExtraction { .. } => "IncompleteExtraction",
+            SkillsError::UnknownRoot(..) => "UnknownRoot",
+            SkillsError::LockOutOfDate(..) => "LockOutOfDate",
+            SkillsError::UnsupportedSchemaVersion { .. } => "UnsupportedSchemaVersion",
+            SkillsError::HookFailed(..) => "HookFailed",
+            SkillsError::OutputExists(..) => "OutputExists",
+            SkillsError::SkillsNotConfigured(..) => "SkillsNotConfigured",
+            SkillsError::UnknownSetting(..) => "UnknownSetting",
+            SkillsError::InvalidSetting { .. } => "InvalidSetting",
+            SkillsError::LocalSkill(..) => "LocalSkill",
+            SkillsError::SyncFailed { .. } => "SyncFailed",
+            SkillsError::UpdateCheckFailed(..) => "UpdateCheckFailed",
+            SkillsError::GitNotFound => "GitNotFound",
+            SkillsError::GitFailed { .. } => "GitFailed",
+            SkillsError::NoReleaseAsset { .. } => "NoReleaseAsset",
+            SkillsError::ManifestLocked { .. } => "ManifestLocked",
+            SkillsError::SourceNotAllowed { .. } => "SourceNotAllowed",
+            SkillsError::LockedChecksumMismatch { .. } => "LockedChecksumMismatch",
+            SkillsError::WouldOverwrite { .. } => "WouldOverwrite",
+            SkillsError::InvalidSkillName(..) => "InvalidSkillName",
+            SkillsError::MissingFrontMatter { .. } => "MissingFrontMatter",
+            SkillsError::SignatureVerificationFailed { .. } => "SignatureVerificationFailed",
+            SkillsError::InvalidGitUrl(..) => "InvalidGitUrl",
+            SkillsError::UnsupportedOption { .. } => "UnsupportedOption",
+        }
+    }
+
+    /// Process exit status for the error, so wrappers can tell kinds of
+    /// failure apart. Errors without a category of their own exit with 1;
+    /// 2 is left to usage errors and 3 to `--check` runs that found changes.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SkillsError::NotFound { .. }
+            | SkillsError::RepositoryNotFound { .. }
+            | SkillsError::PathNotFound(..)
+            | SkillsError::RefNotFound { .. }
+            | SkillsError::NoSkillsFound(..)
+            | SkillsError::NoReleaseAsset { .. } => 4,
+            SkillsError::RateLimited | SkillsError::SecondaryRateLimited { .. } => 5,
+            SkillsError::NetworkError(..)
+            | SkillsError::HttpError { .. }
+            | SkillsError::ArchiveNotReady { .. } => 6,
+            SkillsError::Forbidden { .. } | SkillsError::TokenAccessDenied { .. } => 7,
+            SkillsError::ConfigParseError(..)
+            | SkillsError::UnsupportedSchemaVersion { .. }
+            | SkillsError::UnknownSetting(..)
+            | SkillsError::InvalidSetting { .. }
+            | SkillsError::UnknownRoot(..)
+            | SkillsError::LockOutOfDate(..) => 8,
+            SkillsError::LockedChecksumMismatch { .. }
+            | SkillsError::SignatureVerificationFailed { .. }
+            | SkillsError::SourceNotAllowed { .. }
+            | SkillsError::MissingFrontMatter { .. } => 9,
+            SkillsError::BatchInstallationFailed { .. }
+            | SkillsError::SyncFailed { .. }
+            | SkillsError::UpdateCheckFailed(..) => 10,
+            _ => 1,
+        }
+    }
+}
+
 impl fmt::Display for SkillsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -82,12 +356,20 @@ impl fmt::Display for SkillsError {
                 f,
                 "GitHub API rate limit exceeded (HTTP 429)\n\nPlease try again later or wait about 1 hour for the limit to reset."
             ),
+            SkillsError::SecondaryRateLimited { retry_after } => write!(
+                f,
+                "GitHub secondary rate limit exceeded (HTTP 403)\n\nGitHub throttles bursts of requests. Slow down and try again in {retry_after} seconds."
+            ),
             SkillsError::HttpError { status, message } => {
                 write!(f, "HTTP error {status}: {message}")
             }
             SkillsError::InvalidArchive(reason) => {
-                write!(f, "Downloaded file is not a valid gzip archive\n\n{reason}")
+                write!(f, "Downloaded file is not a valid archive\n\n{reason}")
             }
+            SkillsError::RefNotFound { slug, kind, name } => write!(
+                f,
+                "No {kind} named '{name}' in {slug}\n\nCheck the name, or use --ref to let skill decide whether it is a branch or a tag."
+            ),
             SkillsError::PathNotFound(paths) => {
                 let label = if paths.len() == 1 { "Path" } else { "Paths" };
                 write!(
@@ -100,9 +382,34 @@ impl fmt::Display for SkillsError {
                         .join("\n")
                 )
             }
+            SkillsError::ArchiveNotReady { url } => write!(
+                f,
+                "Archive not yet available\n\nThe commit exists, but GitHub has not finished generating its archive. Try again in a minute.\n\nURL: {url}"
+            ),
+            SkillsError::NotAnArchive {
+                url,
+                content_type,
+                snippet,
+            } => write!(
+                f,
+                "Download is not an archive\n\nURL: {url}\nContent-Type: {}\nStarts with: {snippet}\n\nA proxy or captive portal may have answered instead of GitHub; check HTTPS_PROXY and your network.",
+                content_type.as_deref().unwrap_or("(none)")
+            ),
             SkillsError::MissingSkillManifest => write!(
                 f,
-                "Invalid skill\n\nExpect 'SKILL.md' or 'skill.md' in the directory."
+                "Invalid skill\n\nExpect 'SKILL.md' (or a name listed under [config] manifest_names in skills.toml) in the directory."
+            ),
+            SkillsError::EmptySkill(detail) => write!(
+                f,
+                "Skill has no content\n\n{detail}. The upstream skill may have been emptied without being deleted."
+            ),
+            SkillsError::RepositoryNotFound { slug } => write!(
+                f,
+                "Repository {slug} not found on GitHub\n\nIt does not exist, or it is private. To install from a private repository, set GITHUB_TOKEN or GH_TOKEN to a token that can read it."
+            ),
+            SkillsError::TokenAccessDenied { slug, reason } => write!(
+                f,
+                "Token lacks access to repository {slug}\n\n{reason}\nCheck GITHUB_TOKEN/GH_TOKEN and the repositories it is allowed to read."
             ),
             SkillsError::IoError(err) => write!(f, "Filesystem error\n\n{err}"),
             SkillsError::ConfigParseError(reason) => write!(
@@ -113,19 +420,193 @@ impl fmt::Display for SkillsError {
                 f,
                 "No skills found at path '{path}'\n\nThe directory contains neither a SKILL.md file nor any subdirectories with SKILL.md files."
             ),
+            SkillsError::NonSkillDirectories(dirs) => {
+                write!(
+                    f,
+                    "Some directories in the collection are not skills\n\nThese directories contain no SKILL.md (or a name listed under [config] manifest_names in skills.toml):"
+                )?;
+                for dir in dirs {
+                    write!(f, "\n  - {dir}")?;
+                }
+                write!(
+                    f,
+                    "\n\nPass --continue-on-manifest-error to skip them and install the rest."
+                )
+            }
             SkillsError::BatchInstallationFailed { successful, failed } => {
                 write!(
                     f,
-                    "Batch installation completed with failures\n\nSuccessfully installed: {successful}\nFailed skills:\n"
+                    "Batch installation completed with failures\n\nSuccessfully installed: {successful}\nFailed skills ({}):",
+                    failed.len()
                 )?;
-                for skill in failed {
-                    writeln!(f, "  - {}", skill)?;
+                for (skill, reason) in failed {
+                    // Only the summary line; the full error was printed when it happened.
+                    let summary = reason.lines().next().unwrap_or_default();
+                    write!(f, "\n  - {skill}: {summary}")?;
                 }
                 Ok(())
             }
             SkillsError::UnsupportedProvider(url) => {
                 write!(f, "No provider available that supports the URL: {url}")
             }
+            SkillsError::InvalidDate(value) => write!(
+                f,
+                "Invalid date\n\nExpected format: YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ\nGot: {value}"
+            ),
+            SkillsError::NameCollision { name, existing } => write!(
+                f,
+                "Skill name '{name}' collides with existing skill '{existing}'\n\nThe names differ only by case or Unicode normalization, so they would share a directory on case-insensitive filesystems (macOS, Windows).\nUninstall '{existing}' first if you want to replace it."
+            ),
+            SkillsError::IndexNotConfigured => write!(
+                f,
+                "No skills index configured\n\nSet SKILLS_MAN_INDEX to the URL of an index.json, either in your shell or in the [env] section of ~/.skills-man/config.toml."
+            ),
+            SkillsError::InvalidIndex(reason) => write!(
+                f,
+                "Failed to parse skills index\n\nReason: {reason}\nExpected a JSON array of {{\"name\", \"description\", \"url\"}} objects."
+            ),
+            SkillsError::ProblemsFound(count) => {
+                write!(f, "Found {count} problem(s) in skills.toml")
+            }
+            SkillsError::IncompleteExtraction { expected, actual } => write!(
+                f,
+                "Extracted {actual} file(s) but the upstream tree has {expected}\n\nThe download may have been truncated. Please try again."
+            ),
+            SkillsError::UnknownRoot(root) => write!(
+                f,
+                "Unknown skill root: {root}\n\nDeclare it under [roots] in skills.toml, e.g.:\n  [roots]\n  {root} = \"path/to/skills\""
+            ),
+            SkillsError::OutputExists(path) => write!(
+                f,
+                "Output directory is not empty: {path}\n\nChoose another --output-dir or remove the directory first."
+            ),
+            SkillsError::SkillsNotConfigured(names) => write!(
+                f,
+                "Not configured in skills.toml: {}\n\nRun `skill list` to see the configured skills.",
+                names.join(", ")
+            ),
+            SkillsError::UnknownSetting(key) => write!(
+                f,
+                "Unknown setting: {key}\n\nKnown settings: {}",
+                crate::models::Settings::KEYS.join(", ")
+            ),
+            SkillsError::InvalidSetting { key, reason } => {
+                write!(f, "Invalid value for setting '{key}'\n\n{reason}")
+            }
+            SkillsError::LocalSkill(name) => write!(
+                f,
+                "Skill '{name}' was adopted from local files and has no upstream source\n\nInstall it from a URL to track upstream changes."
+            ),
+            SkillsError::LockOutOfDate(issues) => {
+                writeln!(f, "skills.lock is out of date with skills.toml\n")?;
+                for issue in issues {
+                    writeln!(f, "  - {issue}")?;
+                }
+                write!(
+                    f,
+                    "\nRun `skill sync` without --frozen to update the lock file."
+                )
+            }
+            SkillsError::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "skills.toml uses schema version {found}, but this skills-man only understands up to version {supported}\n\nPlease upgrade skills-man to use this configuration."
+            ),
+            SkillsError::HookFailed(failures) => {
+                writeln!(f, "post_install hook failed\n")?;
+                for (skill, code) in failures {
+                    match code {
+                        Some(code) => writeln!(f, "  - {skill}: exit code {code}")?,
+                        None => writeln!(f, "  - {skill}: did not exit normally")?,
+                    }
+                }
+                write!(
+                    f,
+                    "\nThe skills were installed; fix the hook and run `skill sync --refresh` to retry."
+                )
+            }
+            SkillsError::SyncFailed { successful, failed } => {
+                write!(
+                    f,
+                    "Sync completed with failures\n\nSuccessfully downloaded: {successful}\nFailed skills:\n"
+                )?;
+                for skill in failed {
+                    writeln!(f, "  - {}", skill)?;
+                }
+                Ok(())
+            }
+            SkillsError::UpdateCheckFailed(names) => write!(
+                f,
+                "Could not check {} skill(s) for updates: {}",
+                names.len(),
+                names.join(", ")
+            ),
+            SkillsError::GitNotFound => write!(
+                f,
+                "git is required for git+ssh:// sources but was not found on PATH\n\nInstall git, or install the skill from its https://github.com/ URL instead."
+            ),
+            SkillsError::GitFailed { command, stderr } => {
+                write!(f, "`{command}` failed")?;
+                if !stderr.is_empty() {
+                    write!(f, "\n\n{stderr}")?;
+                }
+                Ok(())
+            }
+            SkillsError::NoReleaseAsset { tag, asset } => match asset {
+                Some(asset) => write!(f, "Release {tag} has no asset named {asset}"),
+                None => write!(
+                    f,
+                    "Release {tag} has no installable asset\n\nAttach the skill to the release as a .tar.gz, .tgz or .zip file."
+                ),
+            },
+            SkillsError::ManifestLocked { lock } => write!(
+                f,
+                "Another skills-man process is running on this skills.toml\n\nWait for it to finish and try again; its process ID is in {lock}."
+            ),
+            SkillsError::SourceNotAllowed { url, allowed } => write!(
+                f,
+                "Source not allowed: {url}\n\nskills.toml only allows sources matching: {}",
+                allowed.join(", ")
+            ),
+            SkillsError::LockedChecksumMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Downloaded files of '{name}' do not match skills.lock\n\nExpected: {expected}\nActual:   {actual}\n\nThe locked commit produced different content than when it was installed. The installed copy was left untouched."
+            ),
+            SkillsError::WouldOverwrite {
+                name,
+                source: Some(source),
+            } => write!(
+                f,
+                "Skill '{name}' is already installed from another source: {source}\n\nRefusing to replace it because of --no-overwrite."
+            ),
+            SkillsError::WouldOverwrite { name, source: None } => write!(
+                f,
+                "Directory for skill '{name}' already exists and is not in skills.toml\n\nRefusing to replace it because of --no-overwrite."
+            ),
+            SkillsError::InvalidSkillName(name) => write!(
+                f,
+                "Invalid skill name '{name}'\n\nNames may only contain ASCII letters, digits, '-', '_' and '.', and may not start with '.'."
+            ),
+            SkillsError::MissingFrontMatter { name, missing } => write!(
+                f,
+                "Skill '{name}' is missing required front matter: {}\n\nAdd these fields to the front matter of its SKILL.md, or change required_fields under [config] in skills.toml.",
+                missing.join(", ")
+            ),
+            SkillsError::SignatureVerificationFailed { name, reason } => write!(
+                f,
+                "Cannot verify the signature of skill '{name}': {reason}\n\nThe skill was not installed. List its files in SHA256SUMS, sign that with minisign and add the public key to trusted_keys under [config] in skills.toml, or run without --verify-signatures."
+            ),
+            SkillsError::InvalidGitUrl(url) => write!(
+                f,
+                "Invalid git+ssh URL\n\nExpected format: git+ssh://{{host}}/{{owner}}/{{repo}}#{{ref}}:{{path}} (ref and path are optional)\nGot: {url}"
+            ),
+            SkillsError::UnsupportedOption { source, option } => write!(
+                f,
+                "{option} is not supported for {source} sources\n\nRun the command without {option}, or pin a commit in the source URL instead."
+            ),
         }
     }
 }
@@ -150,3 +631,67 @@ impl<T> From<SkillsError> for SkillsResult<T> {
         Err(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_installation_failed_lists_reasons() {
+        let err = SkillsError::BatchInstallationFailed {
+            successful: 2,
+            failed: vec![(
+                "demo".to_string(),
+                "Resource not found\n\nURL: https://example.com".to_string(),
+            )],
+        };
+        let message = err.to_string();
+        assert!(message.contains("Successfully installed: 2"));
+        assert!(message.ends_with("  - demo: Resource not found"));
+    }
+
+    #[test]
+    fn test_kind_names_variant() {
+        assert_eq!(SkillsError::RateLimited.kind(), "RateLimited");
+        assert_eq!(
+            SkillsError::NotFound { url: String::new() }.kind(),
+            "NotFound"
+        );
+        assert_eq!(
+            SkillsError::from(io::Error::other("disk")).kind(),
+            "IoError"
+        );
+    }
+
+    #[test]
+    fn test_repository_not_found_suggests_token() {
+        let err = SkillsError::RepositoryNotFound {
+            slug: "owner/repo".to_string(),
+        };
+        assert_eq!(err.kind(), "RepositoryNotFound");
+        let message = err.to_string();
+        assert!(message.starts_with("Repository owner/repo not found"));
+        assert!(message.contains("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_exit_code_follows_variant() {
+        assert_eq!(SkillsError::RateLimited.exit_code(), 5);
+        assert_eq!(
+            SkillsError::RepositoryNotFound {
+                slug: "owner/repo".to_string()
+            }
+            .exit_code(),
+            4
+        );
+        assert_eq!(
+            SkillsError::SyncFailed {
+                successful: 1,
+                failed: vec!["demo".to_string()],
+            }
+            .exit_code(),
+            10
+        );
+        assert_eq!(SkillsError::MissingSkillManifest.exit_code(), 1);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::{
+    debug,
+    errors::{SkillsError, SkillsResult},
+    style,
+    utils::unique_temp_name,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs,
+    io::Read,
+    path::PathBuf,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use ureq::{config::Config, http::HeaderMap};
+
+/// Longest time a request waits for the rate limit to reset before giving up.
+pub const MAX_RATE_LIMIT_WAIT_SECS: u64 = 300;
+
+/// Wait GitHub asks for after a secondary rate limit without `Retry-After`.
+const SECONDARY_RATE_LIMIT_WAIT_SECS: u64 = 60;
+
+fn proxy_from_env() -> Option<String> {
+    for key in [
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+    ] {
+        if let Ok(value) = env::var(key) {
+            let trimmed = value.trim();
+            if !trimmed.is_empty() {
+                return Some(trimmed.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Build an HTTP agent, honoring the usual proxy environment variables.
+pub fn create_agent() -> SkillsResult<ureq::Agent> {
+    let agent = if let Some(proxy_url) = proxy_from_env() {
+        let proxy =
+            ureq::Proxy::new(&proxy_url).map_err(|e| SkillsError::NetworkError(e.to_string()))?;
+        let config = Config::builder().proxy(Some(proxy)).build();
+        ureq::Agent::new_with_config(config)
+    } else {
+        ureq::Agent::new_with_defaults()
+    };
+    Ok(agent)
+}
+
+/// A reader that counts the bytes passing through it.
+pub struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Bytes read so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// A rate-limit budget shared by every request made through one provider.
+///
+/// Responses refill the bucket from their `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` headers and each request takes one token. Once only
+/// `reserve` requests are left, new requests wait for the window to reset,
+/// so concurrent workers stay under the cap together and other tools keep
+/// their share. Until a response reports a budget, requests are not held.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    reserve: u64,
+    state: Mutex<RateLimitState>,
+}
+
+#[derive(Debug, Default)]
+struct RateLimitState {
+    remaining: Option<u64>,
+    reset_at: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Slot {
+    Ready,
+    Wait(u64),
+}
+
+impl RateLimiter {
+    /// A limiter that leaves `reserve` requests of each window unused.
+    pub fn new(reserve: u64) -> Self {
+        Self {
+            reserve,
+            ..Default::default()
+        }
+    }
+
+    /// Take a token for a new request, waiting for the window to reset when
+    /// the budget is spent. Returns `false` if that would take too long.
+    pub fn acquire(&self) -> bool {
+        loop {
+            match self.take_slot(unix_now()) {
+                Slot::Ready => return true,
+                Slot::Wait(secs) if secs > MAX_RATE_LIMIT_WAIT_SECS => return false,
+                Slot::Wait(secs) => {
+                    eprintln!(
+                        "{}",
+                        style::warning_err(&format!(
+                            "Rate limit budget reached; waiting {}s for it to reset",
+                            secs
+                        ))
+                    );
+                    thread::sleep(Duration::from_secs(secs));
+                }
+            }
+        }
+    }
+
+    /// Take a token for a new request if one is available now, without
+    /// waiting or printing anything.
+    pub fn try_acquire(&self) -> bool {
+        self.take_slot(unix_now()) == Slot::Ready
+    }
+
+    /// Record the budget reported by a response.
+    pub fn observe(&self, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let Some(remaining) = header("x-ratelimit-remaining") else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.remaining = Some(remaining);
+        state.reset_at = header("x-ratelimit-reset");
+    }
+
+    fn take_slot(&self, now: u64) -> Slot {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match (state.remaining, state.reset_at) {
+            (Some(remaining), _) if remaining > self.reserve => {
+                state.remaining = Some(remaining - 1);
+                Slot::Ready
+            }
+            (Some(_), Some(reset_at)) if now < reset_at => Slot::Wait(reset_at - now),
+            // The window has reset (or never said when); start over.
+            (Some(_), _) => {
+                state.remaining = None;
+                Slot::Ready
+            }
+            (None, _) => Slot::Ready,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Seconds to wait when a 403 response is GitHub's secondary rate limit
+/// rather than a permission problem: it carries `Retry-After`, or its body
+/// mentions the secondary rate limit.
+pub fn secondary_rate_limit_wait(headers: &HeaderMap, body: &str) -> Option<u64> {
+    let retry_after = headers
+        .get("retry-after")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    retry_after.or_else(|| {
+        body.to_ascii_lowercase()
+            .contains("secondary rate limit")
+            .then_some(SECONDARY_RATE_LIMIT_WAIT_SECS)
+    })
+}
+
+/// Response bodies kept on disk with their `ETag`, so that repeating a
+/// request only asks whether it changed and reuses the body on a
+/// `304 Not Modified`, which GitHub does not count against the rate limit.
+#[derive(Debug)]
+pub struct EtagCache {
+    dir: PathBuf,
+    force_revalidate: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    etag: String,
+    body: String,
+    /// Unix time the entry was written; 0 for entries of older versions.
+    #[serde(default)]
+    stored_at: u64,
+}
+
+impl EtagCache {
+    /// A cache storing its entries in `dir`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            force_revalidate: false,
+        }
+    }
+
+    /// Ignore cached entries and fetch fresh copies, which still replace
+    /// what is cached.
+    pub fn force_revalidate(mut self, force: bool) -> Self {
+        self.force_revalidate = force;
+        self
+    }
+
+    /// The `ETag` and body cached for `url`.
+    pub fn get(&self, url: &str) -> Option<(String, String)> {
+        if self.force_revalidate {
+            return None;
+        }
+        let content = fs::read_to_string(self.entry_path(url)).ok()?;
+        let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+        (cached.url == url).then_some((cached.etag, cached.body))
+    }
+
+    /// The body cached for `url` if it was stored less than `max_age` ago,
+    /// to be used without asking the server at all.
+    pub fn fresh(&self, url: &str, max_age: Duration) -> Option<String> {
+        if self.force_revalidate {
+            return None;
+        }
+        let content = fs::read_to_string(self.entry_path(url)).ok()?;
+        let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+        let age = unix_now().saturating_sub(cached.stored_at);
+        (cached.url == url && age < max_age.as_secs()).then_some(cached.body)
+    }
+
+    /// Cache `body` for `url`. Failing to write only costs a later request.
+    pub fn put(&self, url: &str, etag: &str, body: &str) {
+        let cached = CachedResponse {
+            url: url.to_string(),
+            etag: etag.to_string(),
+            body: body.to_string(),
+            stored_at: unix_now(),
+        };
+        let result = serde_json::to_string(&cached)
+            .map_err(std::io::Error::other)
+            .and_then(|content| {
+                fs::create_dir_all(&self.dir)?;
+                // Write aside and rename so concurrent runs never read half an entry.
+                let temp = self.dir.join(unique_temp_name("entry"));
+                fs::write(&temp, content)?;
+                fs::rename(&temp, self.entry_path(url)).inspect_err(|_| {
+                    fs::remove_file(&temp).ok();
+                })
+            });
+        if let Err(e) = result {
+            debug!("Failed to cache response for {}: {}", url, e);
+        }
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:x}.json", Sha256::digest(url.as_bytes())))
+    }
+}
+
+/// Convert a failed request into a [`SkillsError`], keeping errors that were
+/// raised as one (see [`ureq::Error::Other`]).
+pub fn request_error(error: ureq::Error) -> SkillsError {
+    match error {
+        ureq::Error::Other(inner) => match inner.downcast::<SkillsError>() {
+            Ok(error) => *error,
+            Err(inner) => SkillsError::NetworkError(inner.to_string()),
+        },
+        error => SkillsError::NetworkError(error.to_string()),
+    }
+}
+
+/// Map a non-OK HTTP status for `url` to the matching [`SkillsError`].
+pub fn status_error(status: u16, url: &str) -> SkillsError {
+    match status {
+        404 => SkillsError::NotFound {
+            url: url.to_string(),
+        },
+        403 => SkillsError::Forbidden {
+            url: url.to_string(),
+        },
+        429 => SkillsError::RateLimited,
+        _ => SkillsError::HttpError {
+            status,
+            message: url.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ureq::http::HeaderValue;
+
+    #[test]
+    fn test_etag_cache_round_trip() {
+        let temp_dir = std::env::temp_dir().join("skills_test_etag_cache");
+        fs::remove_dir_all(&temp_dir).ok();
+        let url = "https://api.github.com/repos/owner/repo/contents/skills?ref=main";
+
+        let cache = EtagCache::new(temp_dir.clone());
+        assert_eq!(cache.get(url), None);
+        cache.put(url, "\"abc\"", "[]");
+        assert_eq!(
+            cache.get(url),
+            Some(("\"abc\"".to_string(), "[]".to_string()))
+        );
+        assert_eq!(cache.get("https://api.github.com/other"), None);
+
+        let cache = EtagCache::new(temp_dir.clone()).force_revalidate(true);
+        assert_eq!(cache.get(url), None);
+        cache.put(url, "\"def\"", "[{}]");
+        let cache = EtagCache::new(temp_dir.clone());
+        assert_eq!(
+            cache.get(url),
+            Some(("\"def\"".to_string(), "[{}]".to_string()))
+        );
+        assert_eq!(
+            cache.fresh(url, Duration::from_secs(60)),
+            Some("[{}]".to_string())
+        );
+        assert_eq!(cache.fresh(url, Duration::ZERO), None);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_limiter_keeps_reserve() {
+        let limiter = RateLimiter::new(2);
+        assert_eq!(limiter.take_slot(0), Slot::Ready);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("3"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("100"));
+        limiter.observe(&headers);
+
+        assert_eq!(limiter.take_slot(50), Slot::Ready);
+        assert_eq!(limiter.take_slot(50), Slot::Wait(50));
+        assert_eq!(limiter.take_slot(100), Slot::Ready);
+        assert_eq!(limiter.take_slot(100), Slot::Ready);
+
+        // A spent budget that resets later is refused without waiting.
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("2"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("99999999999"));
+        limiter.observe(&headers);
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn test_secondary_rate_limit_wait() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            secondary_rate_limit_wait(&headers, "Resource not accessible"),
+            None
+        );
+        assert_eq!(
+            secondary_rate_limit_wait(
+                &headers,
+                r#"{"message":"You have exceeded a secondary rate limit."}"#
+            ),
+            Some(SECONDARY_RATE_LIMIT_WAIT_SECS)
+        );
+
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        assert_eq!(secondary_rate_limit_wait(&headers, ""), Some(30));
+    }
+
+    #[test]
+    fn test_request_error_keeps_skills_error() {
+        let error = ureq::Error::Other(Box::new(SkillsError::SecondaryRateLimited {
+            retry_after: 30,
+        }));
+        assert!(matches!(
+            request_error(error),
+            SkillsError::SecondaryRateLimited { retry_after: 30 }
+        ));
+        assert!(matches!(
+            request_error(ureq::Error::BadUri("x".to_string())),
+            SkillsError::NetworkError(_)
+        ));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    errors::{SkillsError, SkillsResult},
+    http::status_error,
+};
+use serde::Deserialize;
+use std::env;
+
+/// Environment variable holding the URL of the skills index.
+pub const INDEX_URL_ENV: &str = "SKILLS_MAN_INDEX";
+
+/// A single skill listed in a skills index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// GitHub tree URL that can be passed to `install`.
+    pub url: String,
+}
+
+/// Return the configured index URL from [`INDEX_URL_ENV`].
+pub fn index_url_from_env() -> SkillsResult<String> {
+    env::var(INDEX_URL_ENV)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or(SkillsError::IndexNotConfigured)
+}
+
+/// Download and parse the JSON index at `url`, a list of [`IndexEntry`].
+pub fn fetch_index(agent: &ureq::Agent, url: &str) -> SkillsResult<Vec<IndexEntry>> {
+    match agent.get(url).header("User-Agent", "skills-man").call() {
+        Ok(response) => response
+            .into_body()
+            .read_json()
+            .map_err(|e| SkillsError::InvalidIndex(e.to_string())),
+        Err(ureq::Error::StatusCode(status)) => Err(status_error(status, url)),
+        Err(e) => Err(SkillsError::NetworkError(e.to_string())),
+    }
+}
+
+/// Case-insensitive substring match of `query` against names and descriptions.
+pub fn search<'a>(entries: &'a [IndexEntry], query: &str) -> Vec<&'a IndexEntry> {
+    let query = query.to_lowercase();
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.name.to_lowercase().contains(&query)
+                || entry.description.to_lowercase().contains(&query)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_matches_name_and_description() {
+        let entries: Vec<IndexEntry> = serde_json::from_str(
+            r#"[
+                {"name": "frontend-design", "description": "Build polished UIs", "url": "https://github.com/a/b/tree/main/frontend-design"},
+                {"name": "pdf", "description": "Read and fill PDF FORMS", "url": "https://github.com/a/b/tree/main/pdf"},
+                {"name": "xlsx", "url": "https://github.com/a/b/tree/main/xlsx"}
+            ]"#,
+        )
+        .unwrap();
+
+        let names = |query| {
+            search(&entries, query)
+                .into_iter()
+                .map(|entry| entry.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("FRONTEND"), vec!["frontend-design"]);
+        assert_eq!(names("forms"), vec!["pdf"]);
+        assert_eq!(names("missing"), Vec::<&str>::new());
+        assert_eq!(names("").len(), 3);
+    }
+}
@@ -0,0 +1,19 @@
+//! Library API for `skills-man`.
+//!
+//! The `skill` binary is a thin wrapper around this crate: it parses the
+//! command line, builds a [`providers::ProviderRegistry`] and dispatches to the
+//! command implementations in [`cli`]. Other programs can use the same entry
+//! points to embed skill management without shelling out.
+
+pub mod archive;
+pub mod cli;
+pub mod errors;
+pub mod http;
+pub mod index;
+pub mod logging;
+pub mod models;
+pub mod providers;
+pub mod style;
+pub mod utils;
+
+pub use errors::{SkillsError, SkillsResult};
@@ -0,0 +1,54 @@
+//! A tiny leveled logger for `-v`/`-vv`. Messages go to stderr and are
+//! silent unless enabled with [`init`].
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Set the verbosity: 0 is silent, 1 enables [`debug!`], 2 also enables
+/// [`trace!`].
+///
+/// [`debug!`]: crate::debug
+/// [`trace!`]: crate::trace
+pub fn init(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are printed.
+pub fn enabled(level: u8) -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= level
+}
+
+/// Print a message at `-v` and above.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::logging::enabled(1) {
+            eprintln!("[debug] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Print a message at `-vv` and above.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::logging::enabled(2) {
+            eprintln!("[trace] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_levels() {
+        init(1);
+        assert!(enabled(1));
+        assert!(!enabled(2));
+        init(0);
+        assert!(!enabled(1));
+    }
+}
@@ -1,79 +1,610 @@
-mod cli;
-mod errors;
-mod models;
-mod providers;
-mod utils;
-
-use crate::models::AppConfig;
-use clap::{Parser, Subcommand};
-use providers::{ProviderRegistry, github::GitHubProvider};
+use clap::{Parser, Subcommand, ValueEnum};
+use skills_man::{
+    cli,
+    http::EtagCache,
+    logging,
+    models::{AppConfig, ArchiveFormat, RefKind},
+    providers::{
+        ProviderRegistry,
+        git::GitProvider,
+        github::{DEFAULT_API_JOBS, DEFAULT_REPO_INFO_TTL, GitHubProvider},
+    },
+    style,
+    utils::{self, ChecksumAlgorithm},
+};
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
-    path::PathBuf,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 #[derive(Parser)]
 #[command(name = "skills-man")]
 #[command(version)]
 #[command(about = "Manage Agents skills")]
 struct Cli {
-    /// Use global directory (~/.skills-man)
+    /// Use global directory (~/.skills-man); same as --config-scope global
     #[arg(short, long, global = true)]
     global: bool,
 
+    /// Where to find skills.toml: the current directory (project), ~/.skills-man
+    /// (global), or the nearest directory up from here that has one (auto)
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "SCOPE",
+        conflicts_with = "global"
+    )]
+    config_scope: Option<ConfigScope>,
+
+    /// Path to the skills.toml manifest (overrides the directory mode)
+    #[arg(long, global = true, value_name = "PATH", value_parser = parse_path)]
+    manifest: Option<PathBuf>,
+
+    /// Directory to install skills into (defaults to `skills/` next to the manifest)
+    #[arg(long, global = true, value_name = "PATH", value_parser = parse_path)]
+    skills_dir: Option<PathBuf>,
+
+    /// Print debug output; repeat (-vv) to also show HTTP requests
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Leave this many GitHub API requests per rate-limit window unused
+    #[arg(long, global = true, value_name = "N", default_value_t = 0)]
+    rate_limit_reserve: u64,
+
+    /// Ignore cached GitHub directory listings and fetch them again
+    #[arg(long, global = true)]
+    force_revalidate: bool,
+
+    /// Reuse cached repository details such as the default branch for this many seconds
+    #[arg(long, global = true, value_name = "SECS", default_value_t = DEFAULT_REPO_INFO_TTL.as_secs())]
+    repo_cache_ttl: u64,
+
+    /// Check at most this many collection sub-directories on GitHub at once
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_API_JOBS)]
+    api_jobs: usize,
+
+    /// Fetch single GitHub skills file by file instead of downloading the
+    /// whole repository archive (automatic for very large repositories)
+    #[arg(long, global = true)]
+    sparse: bool,
+
+    /// Disable colored output (also honors the NO_COLOR env var)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Print errors to stderr as a single JSON object
+    #[arg(long, global = true)]
+    json_errors: bool,
+
+    /// Leave out hints about skills with updates and the sync transfer summary
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Don't contact upstream for anything the command does not need, such as
+    /// the background check for skill updates
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Output format of read-only commands (resolve, outdated, browse)
+    #[arg(long, global = true, value_enum, default_value_t)]
+    output: cli::OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    /// The scope asked for, where `--global` is short for
+    /// `--config-scope global`.
+    fn config_scope(&self) -> ConfigScope {
+        if self.global {
+            ConfigScope::Global
+        } else {
+            self.config_scope.unwrap_or_default()
+        }
+    }
+}
+
+/// Which skills.toml a command works on, unless `--manifest` names one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum ConfigScope {
+    /// `skills.toml` in the current directory.
+    #[default]
+    Project,
+    /// `~/.skills-man/skills.toml`.
+    Global,
+    /// The nearest `skills.toml` in the current directory or one of its
+    /// parents, falling back to the global one.
+    Auto,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Install a skill or skill collection from GitHub
     #[command(visible_alias = "i")]
     Install {
         /// GitHub URL of the skill to install
-        url: String,
+        #[arg(required_unless_present = "from_file", conflicts_with = "from_file")]
+        url: Option<String>,
+        /// Install every URL listed in this file (one per line, # comments allowed)
+        #[arg(long, value_name = "FILE", value_parser = parse_path)]
+        from_file: Option<PathBuf>,
         /// Automatically answer yes to prompts (non-interactive mode)
         #[arg(short, long)]
         yes: bool,
+        /// Fail instead of replacing a skill from another source or an unmanaged directory
+        #[arg(long, conflicts_with = "yes")]
+        no_overwrite: bool,
+        /// Branch, tag or commit to install from; tried before other splits of the URL
+        #[arg(
+            long = "ref",
+            value_name = "REF",
+            group = "ref_arg",
+            conflicts_with = "from_file"
+        )]
+        r#ref: Option<String>,
+        /// Install from this branch, never from a tag of the same name
+        #[arg(
+            long,
+            value_name = "NAME",
+            group = "ref_arg",
+            conflicts_with = "from_file"
+        )]
+        branch: Option<String>,
+        /// Install from this tag, never from a branch of the same name
+        #[arg(
+            long,
+            value_name = "NAME",
+            group = "ref_arg",
+            conflicts_with = "from_file"
+        )]
+        tag: Option<String>,
+        /// Path of the skill in the repository; with --ref, --branch or --tag, only the repository is taken from the URL
+        #[arg(long, value_name = "PATH", requires = "ref_arg")]
+        path: Option<String>,
+        /// Record the skill in skills.toml and skills.lock without downloading it; `sync` fetches it later
+        #[arg(long, conflicts_with_all = ["prefer_cache", "skip_checksum"])]
+        manifest_only: bool,
+        /// Install the skill as it existed on this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        at: Option<String>,
+        /// Re-download the skill even if it is already up to date
+        #[arg(long)]
+        refresh: bool,
+        /// Trust installed skills that match their checksum without checking upstream
+        #[arg(long, conflicts_with = "refresh")]
+        prefer_cache: bool,
+        /// Archive format to download from GitHub
+        #[arg(long, value_enum, default_value_t)]
+        archive_format: ArchiveFormat,
+        /// Install into this root declared under [roots] in skills.toml
+        #[arg(long, value_name = "NAME")]
+        root: Option<String>,
+        /// When the URL is ambiguous, try tags before other refs
+        #[arg(long, conflicts_with = "prefer_branches")]
+        prefer_tags: bool,
+        /// When the URL is ambiguous, try branches before other refs
+        #[arg(long)]
+        prefer_branches: bool,
+        /// Place each skill directly in the skills directory (default)
+        #[arg(long, conflicts_with = "nested")]
+        flat: bool,
+        /// Keep each skill under its upstream parent directory, e.g. <category>/<skill>
+        #[arg(long)]
+        nested: bool,
+        /// Prepend this to each installed skill's name, e.g. `acme-` installs `acme-<skill>`
+        #[arg(long, value_name = "STR")]
+        prefix: Option<String>,
+        /// Hash algorithm for the recorded checksum
+        #[arg(long, value_enum, value_name = "ALGORITHM")]
+        checksum_algorithm: Option<ChecksumAlgorithm>,
+        /// Don't hash installed files; local changes then go undetected
+        #[arg(long, conflicts_with = "checksum_algorithm")]
+        skip_checksum: bool,
+        /// Skip collection sub-directories without a SKILL.md instead of failing
+        #[arg(long)]
+        continue_on_manifest_error: bool,
+        /// Install the path as one skill, even without a SKILL.md; never treat it as a collection
+        #[arg(long, conflicts_with_all = ["continue_on_manifest_error", "interactive", "confirm_each"])]
+        no_subdir: bool,
+        /// Also treat directories containing this file as skills (repeatable)
+        #[arg(long = "manifest-name", value_name = "FILE")]
+        manifest_names: Vec<String>,
+        /// Check that the GitHub token can read the repository before installing
+        #[arg(long = "refresh-token-check")]
+        token_check: bool,
+        /// Finish with a one-line JSON summary of each skill's outcome
+        #[arg(long)]
+        summary: bool,
+        /// Choose which skills of a collection to install from a list
+        #[arg(short = 'I', long)]
+        interactive: bool,
+        /// Ask about each skill of a collection in turn (y/N/q)
+        #[arg(long, conflicts_with = "interactive")]
+        confirm_each: bool,
+        /// Write a JSON report of each skill's before/after state and outcome to this file
+        #[arg(long, value_name = "PATH", value_parser = parse_path)]
+        report_file: Option<PathBuf>,
+        /// Require name, description and [config] required_fields in each skill's front matter
+        #[arg(long)]
+        strict_manifest: bool,
+        /// Keep the temp directory of a failed download and print its path
+        #[arg(long)]
+        keep_temp: bool,
+        /// Refuse skills whose files are not signed by one of [config] trusted_keys
+        #[arg(long)]
+        verify_signatures: bool,
+        /// Only update skills already in skills.toml; skip skills new to the source
+        #[arg(long)]
+        update_existing_only: bool,
+        /// Stop at the first skill that fails instead of reporting all failures at the end
+        #[arg(long)]
+        fail_fast: bool,
+    },
+    /// Download a skill's files into a directory without installing it
+    Fetch {
+        /// GitHub URL of the skill to download
+        url: String,
+        /// Directory to write the skill directories into
+        #[arg(long, value_name = "DIR", value_parser = parse_path)]
+        output_dir: PathBuf,
+        /// Download the skill as it existed on this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        at: Option<String>,
+        /// Archive format to download from GitHub
+        #[arg(long, value_enum, default_value_t)]
+        archive_format: ArchiveFormat,
+        /// Treat directories containing this file as skills (repeatable; default SKILL.md)
+        #[arg(long = "manifest-name", value_name = "FILE")]
+        manifest_names: Vec<String>,
+    },
+    /// List the skills at a GitHub URL without installing them
+    Browse {
+        /// GitHub URL of a skill or skill collection
+        url: String,
+        /// Treat directories containing this file as skills (repeatable; default SKILL.md)
+        #[arg(long = "manifest-name", value_name = "FILE")]
+        manifest_names: Vec<String>,
+    },
+    /// Show how a GitHub URL resolves to a ref, commit and path, without downloading
+    Resolve {
+        /// GitHub URL of a skill or skill collection
+        url: String,
+        /// Resolve to the commit as of this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        at: Option<String>,
+        /// When the URL is ambiguous, try tags before other refs
+        #[arg(long, conflicts_with = "prefer_branches")]
+        prefer_tags: bool,
+        /// When the URL is ambiguous, try branches before other refs
+        #[arg(long)]
+        prefer_branches: bool,
+        /// Treat directories containing this file as skills (repeatable; default SKILL.md)
+        #[arg(long = "manifest-name", value_name = "FILE")]
+        manifest_names: Vec<String>,
     },
     /// Sync installed skills from skills.toml
-    Sync,
+    Sync {
+        /// Continue syncing remaining skills after a failure
+        #[arg(long)]
+        keep_going: bool,
+        /// Re-download every skill, even if its checksum matches
+        #[arg(long)]
+        refresh: bool,
+        /// Install exactly the commits in skills.lock; fail if it is out of date or a download doesn't match its checksum
+        #[arg(long)]
+        frozen: bool,
+        /// Hash every skill directory instead of trusting unchanged modification times
+        #[arg(long)]
+        no_cache_checksum: bool,
+        /// Only sync this skill (repeatable)
+        #[arg(long, value_name = "NAME")]
+        only: Vec<String>,
+        /// Only download skills missing from disk; leave existing ones unchecked
+        #[arg(long, conflicts_with = "refresh")]
+        only_missing: bool,
+        /// Replace skill directories that are symlinks instead of skipping them
+        #[arg(long)]
+        force: bool,
+        /// Keep skills whose files differ from skills.lock instead of asking
+        #[arg(long, conflicts_with = "overwrite_dirty")]
+        allow_dirty: bool,
+        /// Replace skills whose files differ from skills.lock without asking
+        #[arg(long)]
+        overwrite_dirty: bool,
+        /// Finish by listing skills whose upstream has moved past skills.lock
+        #[arg(long)]
+        report_drift: bool,
+        /// Only report which skills sync would download; exits with 3 if any skill is out of sync
+        #[arg(long, conflicts_with_all = ["report_drift", "report_file"])]
+        check: bool,
+        /// Require name, description and [config] required_fields in each skill's front matter
+        #[arg(long)]
+        strict_manifest: bool,
+        /// Refuse downloads whose files are not signed by one of [config] trusted_keys
+        #[arg(long)]
+        verify_signatures: bool,
+        /// Write a JSON report of each skill's before/after state and outcome to this file
+        #[arg(long, value_name = "PATH", value_parser = parse_path)]
+        report_file: Option<PathBuf>,
+        /// Hash skill directories on this many threads (default: one per CPU)
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            default_value_t = 0,
+            hide_default_value = true
+        )]
+        jobs: usize,
+    },
     #[command(visible_alias = "up")]
     /// Check upstream and update a skill
     Update {
         /// Name of the skill to update
-        name: String,
+        #[arg(required_unless_present = "all")]
+        name: Option<String>,
+        /// Check every skill (requires --check)
+        #[arg(long, conflicts_with = "name", requires = "check")]
+        all: bool,
+        /// Only report whether an update is available; exits with 3 if one is
+        #[arg(long, conflicts_with_all = ["collection", "refresh"])]
+        check: bool,
         /// Update the collection containing this skill
         #[arg(short = 'c', long)]
         collection: bool,
         /// Automatically answer yes to prompts (non-interactive mode)
         #[arg(short, long)]
         yes: bool,
+        /// Re-download the skill even if it is already up to date
+        #[arg(long)]
+        refresh: bool,
+        /// Overwrite local edits to the skill without asking
+        #[arg(long, conflicts_with = "keep_local_changes")]
+        ignore_local_changes: bool,
+        /// Leave skills with local edits untouched
+        #[arg(long)]
+        keep_local_changes: bool,
+        /// Write a JSON report of each skill's before/after state and outcome to this file
+        #[arg(long, value_name = "PATH", value_parser = parse_path, conflicts_with = "check")]
+        report_file: Option<PathBuf>,
     },
     /// Remove an installed skill
     #[command(visible_alias = "rm")]
     Uninstall {
-        /// Name of the skill to uninstall
+        /// Names of the skills to uninstall; globs like 'legacy-*' are expanded
+        #[arg(required_unless_present = "all")]
+        names: Vec<String>,
+        /// Uninstall every skill
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+        /// Also remove skill directories missing from skills.toml and leftover temp directories
+        #[arg(long)]
+        force: bool,
+        /// Automatically answer yes to prompts (non-interactive mode)
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Remove a skill and install it again from its recorded source
+    Reinstall {
+        /// Name of the skill to reinstall
         name: String,
+        /// Automatically answer yes to prompts (non-interactive mode)
+        #[arg(short, long)]
+        yes: bool,
+        /// Install from this branch, tag or commit instead of the recorded one
+        #[arg(long = "ref", value_name = "REF")]
+        r#ref: Option<String>,
     },
+    /// Add skills found in the skills directory to skills.toml as local skills
+    Adopt {
+        /// Scan this root declared under [roots] in skills.toml
+        #[arg(long, value_name = "NAME")]
+        root: Option<String>,
+    },
+    /// Rewrite skills.toml written by older versions in the current format
+    Migrate,
     /// List all installed skills
     #[command(visible_alias = "ls")]
+    List {
+        /// Show how many files each skill has and how much disk it uses
+        #[arg(long)]
+        sizes: bool,
+        /// Order skills by name, size (largest first), last update (newest first) or repository
+        #[arg(long, value_enum, default_value_t)]
+        sort: cli::ListSort,
+        /// Only show skills whose name, repository or path contains TEXT (ignoring case)
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+        /// Also show whether each skill is behind upstream (queries upstream)
+        #[arg(long)]
+        outdated: bool,
+    },
+    /// Check skills.toml for problems
+    Doctor {
+        /// Finish or clean up installs that were interrupted part-way
+        #[arg(long)]
+        fix: bool,
+    },
+    /// List skills whose upstream has moved past the locked commit
+    Outdated {
+        /// Only show skills whose newest upstream commit is at least DAYS old
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Only show skills whose newest upstream commit is at most DAYS old
+        #[arg(long, value_name = "DAYS")]
+        newer_than: Option<u64>,
+    },
+    /// Read or change the [config] settings in skills.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Search the skills index (set SKILLS_MAN_INDEX to its URL)
+    Search {
+        /// Case-insensitive text to match against names and descriptions
+        query: String,
+    },
+}
+
+impl Commands {
+    /// Whether the command was asked to `--refresh`, which also bypasses
+    /// cached GitHub responses.
+    fn refreshes(&self) -> bool {
+        match self {
+            Commands::Install { refresh, .. }
+            | Commands::Sync { refresh, .. }
+            | Commands::Update { refresh, .. } => *refresh,
+            _ => false,
+        }
+    }
+
+    /// The command's name and the `--report-file` it should write, if any.
+    fn report_file(&self) -> Option<(&'static str, &Path)> {
+        let (command, path) = match self {
+            Commands::Install { report_file, .. } => ("install", report_file),
+            Commands::Sync { report_file, .. } => ("sync", report_file),
+            Commands::Update { report_file, .. } => ("update", report_file),
+            _ => return None,
+        };
+        Some((command, path.as_deref()?))
+    }
+
+    /// Whether the command may end with a hint about skills with upstream
+    /// updates. Commands that look upstream themselves do not.
+    fn shows_update_hint(&self) -> bool {
+        match self {
+            Commands::List { outdated, .. } => !outdated,
+            Commands::Update { .. } | Commands::Outdated { .. } => false,
+            _ => true,
+        }
+    }
+
+    /// Whether the command writes skills.toml and must hold its lock.
+    fn mutates_manifest(&self) -> bool {
+        match self {
+            Commands::Update { check, .. } | Commands::Sync { check, .. } => !check,
+            Commands::Install { .. }
+            | Commands::Uninstall { .. }
+            | Commands::Reinstall { .. }
+            | Commands::Adopt { .. }
+            | Commands::Migrate => true,
+            Commands::Doctor { fix } => *fix,
+            Commands::Config { action } => matches!(action, ConfigAction::Set { .. }),
+            Commands::Fetch { .. }
+            | Commands::Browse { .. }
+            | Commands::Resolve { .. }
+            | Commands::List { .. }
+            | Commands::Outdated { .. }
+            | Commands::Search { .. } => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective value of a setting
+    Get {
+        /// Setting name, e.g. manifest_names
+        key: String,
+    },
+    /// Change a setting; lists are comma-separated and an empty value resets it
+    Set {
+        /// Setting name, e.g. manifest_names
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Print every setting with its value and source
     List,
 }
 
+fn local_changes(discard: bool, keep: bool) -> cli::LocalChanges {
+    if discard {
+        cli::LocalChanges::Discard
+    } else if keep {
+        cli::LocalChanges::Keep
+    } else {
+        cli::LocalChanges::Prompt
+    }
+}
+
+fn explicit_ref_kind(branch: bool, tag: bool) -> Option<RefKind> {
+    if branch {
+        Some(RefKind::Branch)
+    } else if tag {
+        Some(RefKind::Tag)
+    } else {
+        None
+    }
+}
+
+fn prefer_ref(prefer_tags: bool, prefer_branches: bool) -> Option<RefKind> {
+    if prefer_tags {
+        Some(RefKind::Tag)
+    } else if prefer_branches {
+        Some(RefKind::Branch)
+    } else {
+        None
+    }
+}
+
+/// Expand `~` and environment variables in a path argument.
+fn parse_path(value: &str) -> Result<PathBuf, String> {
+    Ok(utils::expand_path(value))
+}
+
 fn get_global_dir() -> Option<PathBuf> {
-    std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .ok()
-        .map(|home| PathBuf::from(home).join(".skills-man"))
+    utils::home_dir().map(|home| home.join(".skills-man"))
 }
 
-fn get_base_dir(global: bool) -> Result<PathBuf, String> {
-    if global {
-        get_global_dir().ok_or_else(|| "Unable to determine home directory".to_string())
+fn get_base_dir(scope: ConfigScope) -> Result<PathBuf, String> {
+    let global_dir =
+        || get_global_dir().ok_or_else(|| "Unable to determine home directory".to_string());
+    match scope {
+        ConfigScope::Project => Ok(PathBuf::from(".")),
+        ConfigScope::Global => global_dir(),
+        ConfigScope::Auto => match std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_project_dir(&dir))
+        {
+            Some(dir) => Ok(dir),
+            None => global_dir(),
+        },
+    }
+}
+
+/// The nearest of `start` and its parents that has a `skills.toml`, the way
+/// git looks for `.git`.
+fn find_project_dir(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("skills.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Exit code of `update --check` when an update is available, and of
+/// `sync --check` when a skill is out of sync.
+const CHANGES_PENDING_EXIT_CODE: i32 = 3;
+
+/// Print an error to stderr and exit with `exit_code`. With `--json-errors`
+/// the error is a single JSON object whose `error` field names the
+/// `SkillsError` variant.
+fn exit_with_error(kind: &str, message: &str, exit_code: i32, json: bool) -> ! {
+    if json {
+        let error = serde_json::json!({
+            "error": kind,
+            "message": message,
+            "exit_code": exit_code,
+        });
+        eprintln!("{error}");
     } else {
-        Ok(PathBuf::from("."))
+        eprintln!("{} {}", style::error("Error:"), message);
     }
+    std::process::exit(exit_code);
 }
 
 fn load_config_env(config: &AppConfig) {
@@ -99,20 +630,25 @@ fn load_config_env(config: &AppConfig) {
 
 fn main() {
     let cli = Cli::parse();
+    style::init(cli.no_color);
+    logging::init(cli.verbose);
 
-    let base_dir = match get_base_dir(cli.global) {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        }
+    let mut paths = match cli.manifest {
+        Some(manifest) => cli::SkillsPaths::from_manifest(manifest),
+        None => match get_base_dir(cli.config_scope()) {
+            Ok(dir) => cli::SkillsPaths::from_base_dir(&dir),
+            Err(e) => exit_with_error("HomeDirNotFound", &e, 1, cli.json_errors),
+        },
     };
+    if let Some(skills_dir) = cli.skills_dir {
+        paths.skills_dir = skills_dir;
+    }
 
     let app_config = match get_global_dir() {
         Some(global_dir) => match AppConfig::from_file(global_dir.join("config.toml")) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!("Warning: {e}");
+                eprintln!("{} {e}", style::warning_err("Warning:"));
                 AppConfig::default()
             }
         },
@@ -120,34 +656,306 @@ fn main() {
     };
     load_config_env(&app_config);
 
-    let registry = match GitHubProvider::new() {
-        Ok(github) => ProviderRegistry::new(vec![Box::new(github)]),
-        Err(e) => {
-            eprintln!("Error: {e}");
-            std::process::exit(1);
+    // Without waits, rate limits fail requests quietly, as the background
+    // update check needs.
+    let build_registry = |wait_on_rate_limit: bool| {
+        GitHubProvider::new().map(|github| {
+            let mut github = github
+                .with_rate_limit_reserve(cli.rate_limit_reserve)
+                .with_sparse(cli.sparse)
+                .with_api_jobs(cli.api_jobs)
+                .with_repo_info_ttl(Duration::from_secs(cli.repo_cache_ttl))
+                .with_rate_limit_waits(wait_on_rate_limit);
+            if let Some(global_dir) = get_global_dir() {
+                let cache = EtagCache::new(global_dir.join("cache").join("contents"))
+                    .force_revalidate(cli.force_revalidate || cli.command.refreshes());
+                github = github.with_cache(cache);
+            }
+            ProviderRegistry::new(vec![Box::new(github), Box::new(GitProvider::new())])
+        })
+    };
+    let registry = match build_registry(true) {
+        Ok(registry) => registry,
+        Err(e) => exit_with_error(e.kind(), &e.to_string(), e.exit_code(), cli.json_errors),
+    };
+
+    let lock = if cli.command.mutates_manifest() {
+        match cli::ManifestLock::acquire(&paths.manifest) {
+            Ok(lock) => Some(lock),
+            Err(e) => exit_with_error(e.kind(), &e.to_string(), e.exit_code(), cli.json_errors),
         }
+    } else {
+        None
     };
 
+    let report = cli
+        .command
+        .report_file()
+        .map(|(command, path)| (cli::RunReport::start(command, &paths), path.to_path_buf()));
+
+    // Only hint at updates to someone reading the terminal who asked for
+    // neither quiet output nor to stay offline.
+    let update_check = if cli.command.shows_update_hint()
+        && !cli.quiet
+        && !cli.offline
+        && cli.output == cli::OutputFormat::Human
+        && std::io::stderr().is_terminal()
+    {
+        get_global_dir()
+            .zip(build_registry(false).ok())
+            .and_then(|(global_dir, registry)| {
+                cli::UpdateCheck::start(&paths, &global_dir.join("update-check"), registry)
+            })
+    } else {
+        None
+    };
+
+    let mut changes_pending = false;
     let result = match cli.command {
-        Commands::Install { url, yes } => cli::install_skill(&url, &base_dir, yes, &registry),
-        Commands::Sync => cli::sync_skills(&base_dir, &registry),
+        Commands::Install {
+            url,
+            from_file,
+            yes,
+            at,
+            refresh,
+            prefer_cache,
+            archive_format,
+            root,
+            prefer_tags,
+            prefer_branches,
+            flat: _,
+            nested,
+            prefix,
+            checksum_algorithm,
+            skip_checksum,
+            continue_on_manifest_error,
+            manifest_names,
+            token_check,
+            summary,
+            interactive,
+            confirm_each,
+            update_existing_only,
+            no_overwrite,
+            r#ref,
+            branch,
+            tag,
+            path,
+            manifest_only,
+            fail_fast,
+            report_file: _,
+            strict_manifest,
+            keep_temp,
+            verify_signatures,
+            no_subdir,
+        } => {
+            let prefer_ref = prefer_ref(prefer_tags, prefer_branches);
+            let options = cli::InstallOptions {
+                yes,
+                at,
+                refresh,
+                archive_format,
+                root,
+                prefer_ref,
+                nested,
+                prefix,
+                checksum_algorithm,
+                continue_on_manifest_error,
+                manifest_names,
+                token_check,
+                ref_name: r#ref.or(branch.clone()).or(tag.clone()),
+                ref_kind: explicit_ref_kind(branch.is_some(), tag.is_some()),
+                path,
+                manifest_only,
+                summary,
+                interactive,
+                confirm_each,
+                update_existing_only,
+                no_overwrite,
+                fail_fast,
+                skip_checksum,
+                strict_manifest,
+                prefer_cache,
+                local_changes: cli::LocalChanges::Prompt,
+                keep_temp,
+                verify_signatures,
+                no_subdir,
+            };
+            match (url, from_file) {
+                (_, Some(list)) => cli::install_from_file(&list, &paths, &options, &registry),
+                (Some(url), None) => cli::install_skill(&url, &paths, &options, &registry),
+                (None, None) => unreachable!("clap requires a URL or --from-file"),
+            }
+        }
+        Commands::Fetch {
+            url,
+            output_dir,
+            at,
+            archive_format,
+            manifest_names,
+        } => {
+            let options = cli::FetchOptions {
+                output_dir,
+                at,
+                archive_format,
+                manifest_names,
+            };
+            cli::fetch_skill(&url, &options, &registry)
+        }
+        Commands::Browse {
+            url,
+            manifest_names,
+        } => cli::browse_skills(&url, &manifest_names, cli.output, &registry),
+        Commands::Resolve {
+            url,
+            at,
+            prefer_tags,
+            prefer_branches,
+            manifest_names,
+        } => {
+            let options = cli::ResolveUrlOptions {
+                at,
+                prefer_ref: prefer_ref(prefer_tags, prefer_branches),
+                manifest_names,
+                output: cli.output,
+            };
+            cli::resolve_url(&url, &options, &registry)
+        }
+        Commands::Sync {
+            keep_going,
+            refresh,
+            frozen,
+            no_cache_checksum,
+            only,
+            only_missing,
+            force,
+            allow_dirty,
+            overwrite_dirty,
+            report_drift,
+            check,
+            strict_manifest,
+            verify_signatures,
+            jobs,
+            report_file: _,
+        } => {
+            let local_changes = local_changes(overwrite_dirty, allow_dirty);
+            let options = cli::SyncOptions {
+                keep_going,
+                refresh,
+                frozen,
+                quiet: cli.quiet,
+                no_cache_checksum,
+                only,
+                only_missing,
+                force,
+                jobs,
+                local_changes,
+                report_drift,
+                strict_manifest,
+                verify_signatures,
+            };
+            if check {
+                cli::check_sync(&paths, &options).map(|changes| changes_pending = changes > 0)
+            } else {
+                cli::sync_skills(&paths, &options, &registry)
+            }
+        }
         Commands::Update {
             name,
+            all: _,
+            check,
             collection,
             yes,
+            refresh,
+            ignore_local_changes,
+            keep_local_changes,
+            report_file: _,
         } => {
-            if collection {
-                cli::update_collection_for_skill(&name, &base_dir, yes, &registry)
+            let local_changes = local_changes(ignore_local_changes, keep_local_changes);
+            let options = cli::UpdateOptions {
+                yes,
+                refresh,
+                local_changes,
+            };
+            let name = name.unwrap_or_default();
+            if check {
+                let name = (!name.is_empty()).then_some(name.as_str());
+                cli::check_updates(name, &paths, &registry)
+                    .map(|available| changes_pending = available > 0)
+            } else if collection {
+                cli::update_collection_for_skill(&name, &paths, &options, &registry)
             } else {
-                cli::update_skill(&name, &base_dir, yes, &registry)
+                cli::update_skill(&name, &paths, &options, &registry)
             }
         }
-        Commands::Uninstall { name } => cli::uninstall_skill(&name, &base_dir),
-        Commands::List => cli::list_skills(&base_dir),
+        Commands::Uninstall {
+            names,
+            all,
+            force,
+            yes,
+        } => {
+            let options = cli::UninstallOptions { all, yes, force };
+            cli::uninstall_skills(&names, &paths, &options)
+        }
+        Commands::Reinstall { name, yes, r#ref } => {
+            let options = cli::ReinstallOptions { yes, r#ref };
+            cli::reinstall_skill(&name, &paths, &options, &registry)
+        }
+        Commands::Adopt { root } => {
+            let options = cli::AdoptOptions { root };
+            cli::adopt_skills(&paths, &options)
+        }
+        Commands::Migrate => cli::migrate(&paths, &registry),
+        Commands::List {
+            sizes,
+            sort,
+            filter,
+            outdated,
+        } => {
+            let options = cli::ListOptions {
+                sizes,
+                sort,
+                outdated,
+                filter,
+            };
+            cli::list_skills(&paths, &options, &registry)
+        }
+        Commands::Doctor { fix } => cli::doctor(&paths, &cli::DoctorOptions { fix }),
+        Commands::Outdated {
+            older_than,
+            newer_than,
+        } => {
+            let options = cli::OutdatedOptions {
+                older_than,
+                newer_than,
+                output: cli.output,
+            };
+            cli::outdated_skills(&paths, &options, &registry)
+        }
+        Commands::Config { action } => match action {
+            ConfigAction::Get { key } => cli::config_get(&key, &paths),
+            ConfigAction::Set { key, value } => cli::config_set(&key, &value, &paths),
+            ConfigAction::List => cli::config_list(&paths),
+        },
+        Commands::Search { query } => cli::search_skills(&query),
     };
 
+    let result = match report {
+        Some((report, path)) => {
+            let written = report.write(&paths, result.as_ref().err(), &path);
+            result.and(written)
+        }
+        None => result,
+    };
+
+    // `process::exit` skips destructors, so release the lock first.
+    drop(lock);
     if let Err(e) = result {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
+        exit_with_error(e.kind(), &e.to_string(), e.exit_code(), cli.json_errors);
+    }
+    if let Some(update_check) = update_check {
+        update_check.finish();
+    }
+    if changes_pending {
+        std::process::exit(CHANGES_PENDING_EXIT_CODE);
     }
 }
@@ -1,8 +1,15 @@
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use clap::ValueEnum;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item, Table, Value};
 
 use crate::errors::SkillsError;
 use crate::errors::SkillsResult;
+use crate::utils::{DEFAULT_MANIFEST_NAME, glob_regex, normalize_skill_name, source_id};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -11,7 +18,7 @@ pub struct AppConfig {
 }
 
 impl AppConfig {
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> SkillsResult<Self> {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> SkillsResult<Self> {
         let path = path.as_ref();
         if !path.exists() {
             return Ok(AppConfig::default());
@@ -23,37 +30,690 @@ impl AppConfig {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// Skills declared in `skills.toml`, merged with their resolved state from
+/// the generated `skills.lock` next to it.
+#[derive(Debug, Default)]
 pub struct SkillsConfig {
-    #[serde(default)]
+    /// Skills with both a manifest entry and matching lock state.
     pub skills: BTreeMap<String, SkillEntry>,
+    /// Skills declared in the manifest that the lock has not resolved yet.
+    pub unresolved: BTreeMap<String, ManifestEntry>,
+    /// Mismatches between `skills.toml` and `skills.lock` found on load.
+    pub lock_issues: Vec<String>,
+    /// Named skill directories declared under `[roots]`, relative to the
+    /// manifest. Skills without a `root` use the default skills directory.
+    pub roots: BTreeMap<String, String>,
+    /// Commands from `[hooks]` to run around installs.
+    pub hooks: Hooks,
+    /// Project-wide settings from `[config]`.
+    pub settings: Settings,
+}
+
+/// Settings under `[config]` in `skills.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    /// File names that mark a directory as a skill (case-insensitive). Empty
+    /// means just `SKILL.md`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manifest_names: Vec<String>,
+    /// Patterns over `host/owner/repo` that skill sources must match: globs
+    /// such as `github.com/acme-org/*`, or regexes prefixed with `re:`.
+    /// Empty allows every source.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_sources: Vec<String>,
+    /// Front-matter fields every installed skill's manifest must set, on top
+    /// of `name` and `description`. Empty only checks them with
+    /// `--strict-manifest`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_fields: Vec<String>,
+    /// Base64 minisign public keys that `--verify-signatures` accepts
+    /// skill signatures from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
+    /// Whether commands check once a day, in the background, for skills
+    /// with upstream updates. Unset means yes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_check: Option<bool>,
+    /// `allowed_sources` compiled by [`Settings::validate`].
+    #[serde(skip)]
+    source_patterns: SourcePatterns,
+}
+
+/// Case-insensitive regexes compiled from `allowed_sources`, in order.
+#[derive(Debug, Clone, Default)]
+struct SourcePatterns(Vec<Regex>);
+
+impl PartialEq for SourcePatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .iter()
+            .map(Regex::as_str)
+            .eq(other.0.iter().map(Regex::as_str))
+    }
+}
+
+impl Settings {
+    /// Keys accepted under `[config]`.
+    pub const KEYS: &[&str] = &[
+        "manifest_names",
+        "allowed_sources",
+        "required_fields",
+        "trusted_keys",
+        "update_check",
+    ];
+
+    fn is_empty(&self) -> bool {
+        self == &Settings::default()
+    }
+
+    /// The value of `key` in command-line form, or `None` when it is unset.
+    pub fn get(&self, key: &str) -> SkillsResult<Option<String>> {
+        match key {
+            "manifest_names" => {
+                Ok((!self.manifest_names.is_empty()).then(|| self.manifest_names.join(",")))
+            }
+            "allowed_sources" => {
+                Ok((!self.allowed_sources.is_empty()).then(|| self.allowed_sources.join(",")))
+            }
+            "required_fields" => {
+                Ok((!self.required_fields.is_empty()).then(|| self.required_fields.join(",")))
+            }
+            "trusted_keys" => {
+                Ok((!self.trusted_keys.is_empty()).then(|| self.trusted_keys.join(",")))
+            }
+            "update_check" => Ok(self.update_check.map(|enabled| enabled.to_string())),
+            _ => Err(SkillsError::UnknownSetting(key.to_string())),
+        }
+    }
+
+    /// The value `key` takes when it is unset.
+    pub fn default_value(key: &str) -> SkillsResult<String> {
+        match key {
+            "manifest_names" => Ok(DEFAULT_MANIFEST_NAME.to_string()),
+            "allowed_sources" => Ok("*".to_string()),
+            "required_fields" | "trusted_keys" => Ok(String::new()),
+            "update_check" => Ok("true".to_string()),
+            _ => Err(SkillsError::UnknownSetting(key.to_string())),
+        }
+    }
+
+    /// Parse and store `value` for `key`. Lists are comma-separated; an empty
+    /// value resets the key to its default.
+    pub fn set(&mut self, key: &str, value: &str) -> SkillsResult<()> {
+        match key {
+            "manifest_names" => {
+                let names: Vec<String> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if let Some(name) = names.iter().find(|name| name.contains(['/', '\\'])) {
+                    return Err(SkillsError::InvalidSetting {
+                        key: key.to_string(),
+                        reason: format!("'{name}' is not a file name"),
+                    });
+                }
+                self.manifest_names = names;
+                Ok(())
+            }
+            "allowed_sources" => {
+                let patterns: Vec<String> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let mut settings = Settings {
+                    allowed_sources: patterns,
+                    ..Settings::default()
+                };
+                settings.validate()?;
+                self.allowed_sources = settings.allowed_sources;
+                self.source_patterns = settings.source_patterns;
+                Ok(())
+            }
+            "required_fields" => {
+                self.required_fields = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|field| !field.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                Ok(())
+            }
+            "trusted_keys" => {
+                let mut settings = Settings {
+                    trusted_keys: value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    ..Settings::default()
+                };
+                settings.validate()?;
+                self.trusted_keys = settings.trusted_keys;
+                Ok(())
+            }
+            "update_check" => {
+                self.update_check = match value.trim() {
+                    "" => None,
+                    "true" => Some(true),
+                    "false" => Some(false),
+                    other => {
+                        return Err(SkillsError::InvalidSetting {
+                            key: key.to_string(),
+                            reason: format!("'{other}' is not true or false"),
+                        });
+                    }
+                };
+                Ok(())
+            }
+            _ => Err(SkillsError::UnknownSetting(key.to_string())),
+        }
+    }
+
+    /// Check the settings read from `skills.toml`, and compile
+    /// `allowed_sources` for [`Settings::check_source`].
+    fn validate(&mut self) -> SkillsResult<()> {
+        let mut patterns = Vec::with_capacity(self.allowed_sources.len());
+        for pattern in &self.allowed_sources {
+            let regex = match pattern.strip_prefix("re:") {
+                Some(regex) => regex.to_string(),
+                None => glob_regex(pattern),
+            };
+            let compiled = RegexBuilder::new(&regex)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| SkillsError::InvalidSetting {
+                    key: "allowed_sources".to_string(),
+                    reason: format!("'{pattern}' is not a valid regex: {e}"),
+                })?;
+            patterns.push(compiled);
+        }
+        self.source_patterns = SourcePatterns(patterns);
+        for key in &self.trusted_keys {
+            if let Err(e) = minisign_verify::PublicKey::from_base64(key) {
+                return Err(SkillsError::InvalidSetting {
+                    key: "trusted_keys".to_string(),
+                    reason: format!("'{key}' is not a minisign public key: {e}"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail with [`SkillsError::SourceNotAllowed`] unless `url` belongs to a
+    /// repository matched by `allowed_sources`, ignoring case. Patterns not
+    /// compiled by [`Settings::validate`] match nothing.
+    pub fn check_source(&self, url: &str) -> SkillsResult<()> {
+        if self.allowed_sources.is_empty() {
+            return Ok(());
+        }
+        let allowed = source_id(url)
+            .is_some_and(|id| self.source_patterns.0.iter().any(|re| re.is_match(&id)));
+        if allowed {
+            return Ok(());
+        }
+        Err(SkillsError::SourceNotAllowed {
+            url: url.to_string(),
+            allowed: self.allowed_sources.clone(),
+        })
+    }
+
+    /// Front-matter fields each skill's manifest must set: `name`,
+    /// `description` and `required_fields` when `strict` is set or
+    /// `required_fields` is configured, otherwise none.
+    pub fn required_front_matter(&self, strict: bool) -> Vec<String> {
+        if !strict && self.required_fields.is_empty() {
+            return Vec::new();
+        }
+        let mut fields = vec!["name".to_string(), "description".to_string()];
+        for field in &self.required_fields {
+            if !fields.contains(field) {
+                fields.push(field.clone());
+            }
+        }
+        fields
+    }
+
+    /// Accept `names` as skill manifests too, keeping the ones accepted so far.
+    pub fn add_manifest_names(&mut self, names: &[String]) {
+        if names.is_empty() {
+            return;
+        }
+        if self.manifest_names.is_empty() {
+            self.manifest_names.push(DEFAULT_MANIFEST_NAME.to_string());
+        }
+        for name in names {
+            if !self
+                .manifest_names
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(name))
+            {
+                self.manifest_names.push(name.clone());
+            }
+        }
+    }
+}
+
+/// Opt-in commands that run after skills are written to disk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Hooks {
+    /// Shell command run in each skill's directory after it is installed,
+    /// updated or synced.
+    #[serde(default)]
+    pub post_install: Option<String>,
+}
+
+impl Hooks {
+    fn is_empty(&self) -> bool {
+        self == &Hooks::default()
+    }
+}
+
+/// Schema version of `skills.toml` written by this build. Bump it when the
+/// format changes in a way older versions would misread.
+pub const SCHEMA_VERSION: u32 = 1;
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+/// Reads just the schema version, so a newer manifest is rejected with a
+/// clear message before its contents fail to parse.
+#[derive(Debug, Deserialize)]
+struct VersionProbe {
+    #[serde(default = "default_schema_version")]
+    version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestFile<E> {
+    #[serde(default = "default_schema_version")]
+    version: u32,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    roots: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    hooks: Hooks,
+    #[serde(default, rename = "config", skip_serializing_if = "Settings::is_empty")]
+    settings: Settings,
+    #[serde(default = "BTreeMap::new")]
+    skills: BTreeMap<String, E>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawManifestEntry {
+    #[serde(flatten)]
+    manifest: ManifestEntry,
+    // Resolved fields stored inline by versions that predate skills.lock.
+    #[serde(flatten)]
+    legacy: Option<LegacyLockFields>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyLockFields {
+    slug: String,
+    path: String,
+    sha: String,
+    checksum: String,
+    #[serde(default)]
+    default_branch: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LockFile {
+    #[serde(default)]
+    skills: BTreeMap<String, LockEntry>,
+}
+
+const LOCK_HEADER: &str = "# This file is generated by skills-man. Do not edit it by hand.\n\n";
+
+/// Path of the lock file that accompanies the manifest at `manifest_path`.
+pub fn lock_path(manifest_path: &Path) -> PathBuf {
+    manifest_path.with_extension("lock")
+}
+
+fn read_toml<T: DeserializeOwned>(path: &Path) -> SkillsResult<T> {
+    let bytes = std::fs::read(path)?;
+    toml::from_slice(&bytes).map_err(|e| {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        SkillsError::ConfigParseError(format!("{file}: {e}"))
+    })
+}
+
+/// Write `value` to `path`. An existing file is edited in place rather than
+/// rewritten, so comments, blank lines and key order around the entries
+/// that did not change survive.
+fn write_toml<T: Serialize>(path: &Path, header: &str, value: &T) -> SkillsResult<()> {
+    let content =
+        toml::to_string_pretty(value).map_err(|e| SkillsError::ConfigParseError(e.to_string()))?;
+    let existing = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|old| old.parse::<DocumentMut>().ok());
+    let content = match (existing, content.parse::<DocumentMut>()) {
+        (Some(mut old), Ok(new)) => {
+            merge_table(old.as_table_mut(), new.as_table());
+            old.to_string()
+        }
+        _ => format!("{header}{content}"),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Make `old` hold the same entries as `new`, touching only what differs.
+fn merge_table(old: &mut Table, new: &Table) {
+    let stale: Vec<String> = old
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !new.contains_key(key))
+        .collect();
+    for key in stale {
+        old.remove(&key);
+    }
+    for (key, new_item) in new.iter() {
+        match old.get_mut(key) {
+            Some(old_item) => merge_item(old_item, new_item),
+            None => {
+                old.insert(key, detached(new_item));
+            }
+        }
+    }
+}
+
+fn merge_item(old: &mut Item, new: &Item) {
+    match (old, new) {
+        (Item::Table(old), Item::Table(new)) => merge_table(old, new),
+        (Item::Value(old), Item::Value(new)) => {
+            if !same_value(old, new) {
+                let decor = old.decor().clone();
+                *old = new.clone();
+                *old.decor_mut() = decor;
+            }
+        }
+        (old, new) => *old = detached(new),
+    }
+}
+
+/// A copy of `item` without its position in the document it came from, so
+/// that it is written after its siblings in the document it is added to.
+fn detached(item: &Item) -> Item {
+    match item {
+        Item::Table(table) => {
+            let mut copy = Table::new();
+            copy.set_implicit(table.is_implicit());
+            for (key, value) in table.iter() {
+                copy.insert(key, detached(value));
+            }
+            Item::Table(copy)
+        }
+        item => item.clone(),
+    }
+}
+
+/// Whether two values are equal, however they are written.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+        }
+        _ => false,
+    }
 }
 
 impl SkillsConfig {
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> SkillsResult<Self> {
+    /// Load `skills.toml` and its `skills.lock`. Manifests written before the
+    /// lock file existed carry resolved fields inline; those are used when no
+    /// lock file is present and split out on the next [`SkillsConfig::save`].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> SkillsResult<Self> {
         let path = path.as_ref();
         if !path.exists() {
             return Ok(SkillsConfig::default());
         }
 
-        let bytes = std::fs::read(path)?;
-        let config: SkillsConfig =
-            toml::from_slice(&bytes).map_err(|e| SkillsError::ConfigParseError(e.to_string()))?;
+        let VersionProbe { version } = read_toml(path)?;
+        if version > SCHEMA_VERSION {
+            return Err(SkillsError::UnsupportedSchemaVersion {
+                found: version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        let mut manifest: ManifestFile<RawManifestEntry> = read_toml(path)?;
+        manifest.settings.validate()?;
+        let lock_path = lock_path(path);
+        let lock: Option<LockFile> = if lock_path.exists() {
+            Some(read_toml(&lock_path)?)
+        } else {
+            None
+        };
+
+        let mut config = SkillsConfig {
+            roots: manifest.roots,
+            hooks: manifest.hooks,
+            settings: manifest.settings,
+            ..Default::default()
+        };
+        for (name, raw) in manifest.skills {
+            let locked = match &lock {
+                Some(lock) => lock.skills.get(&name).cloned(),
+                None => raw.legacy.map(|legacy| LockEntry {
+                    source_url: raw.manifest.source_url.clone(),
+                    slug: legacy.slug,
+                    path: legacy.path,
+                    sha: legacy.sha,
+                    checksum: legacy.checksum,
+                    default_branch: legacy.default_branch,
+                    ref_name: None,
+                    updated_at: None,
+                    tree_mtime: None,
+                }),
+            };
+
+            match locked {
+                Some(locked) if locked.source_url == raw.manifest.source_url => {
+                    config
+                        .skills
+                        .insert(name, SkillEntry::from_parts(raw.manifest, locked));
+                }
+                Some(_) => {
+                    config
+                        .lock_issues
+                        .push(format!("'{name}' changed source since it was locked"));
+                    config.unresolved.insert(name, raw.manifest);
+                }
+                None => {
+                    config.lock_issues.push(format!("'{name}' is not locked"));
+                    config.unresolved.insert(name, raw.manifest);
+                }
+            }
+        }
+
+        if let Some(lock) = lock {
+            for name in lock.skills.keys() {
+                if !config.skills.contains_key(name) && !config.unresolved.contains_key(name) {
+                    config.lock_issues.push(format!(
+                        "'{name}' is locked but no longer declared in skills.toml"
+                    ));
+                }
+            }
+        }
+
         Ok(config)
     }
 
-    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> SkillsResult<()> {
-        let content = toml::to_string_pretty(self)
-            .map_err(|e| SkillsError::ConfigParseError(e.to_string()))?;
+    /// Groups of skills that would be written to the same directory, given
+    /// the directory `root_dir` resolves each root to: skills whose names
+    /// match on a case-insensitive filesystem (e.g. `Foo` and `foo`) in roots
+    /// that point at the same place. Skills in unknown roots are left out.
+    pub fn directory_conflicts(
+        &self,
+        root_dir: impl Fn(Option<&str>) -> Option<PathBuf>,
+    ) -> Vec<Vec<&str>> {
+        let mut groups: BTreeMap<PathBuf, Vec<&str>> = BTreeMap::new();
+        for name in self.skills.keys().chain(self.unresolved.keys()) {
+            let Some(dir) = root_dir(self.root(name)) else {
+                continue;
+            };
+            groups
+                .entry(dir.join(normalize_skill_name(name)))
+                .or_default()
+                .push(name);
+        }
+        groups
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .collect()
+    }
+
+    /// Source URL recorded for `name`, whether or not it is locked yet.
+    pub fn source_url(&self, name: &str) -> Option<&str> {
+        self.skills
+            .get(name)
+            .map(|entry| entry.source_url.as_str())
+            .or_else(|| {
+                self.unresolved
+                    .get(name)
+                    .map(|entry| entry.source_url.as_str())
+            })
+    }
+
+    /// Root recorded for `name`, or `None` for the default skills directory.
+    pub fn root(&self, name: &str) -> Option<&str> {
+        self.skills
+            .get(name)
+            .and_then(|entry| entry.root.as_deref())
+            .or_else(|| {
+                self.unresolved
+                    .get(name)
+                    .and_then(|entry| entry.root.as_deref())
+            })
+    }
+
+    /// Write the manifest to `path` and the resolved state to its lock file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> SkillsResult<()> {
         let path = path.as_ref();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
+
+        let mut manifest = ManifestFile {
+            version: SCHEMA_VERSION,
+            roots: self.roots.clone(),
+            hooks: self.hooks.clone(),
+            settings: self.settings.clone(),
+            skills: self.unresolved.clone(),
+        };
+        let mut lock = LockFile::default();
+        for (name, entry) in &self.skills {
+            let (manifest_entry, lock_entry) = entry.split();
+            manifest.skills.insert(name.clone(), manifest_entry);
+            lock.skills.insert(name.clone(), lock_entry);
         }
-        std::fs::write(path, content)?;
+
+        write_toml(path, "", &manifest)?;
+        write_toml(&lock_path(path), LOCK_HEADER, &lock)?;
         Ok(())
     }
 }
 
+/// Archive format used to download a repository snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tarball.
+    #[default]
+    Tar,
+    /// Zip archive.
+    Zip,
+}
+
+/// Kind of git ref: one to try first when a tree URL could be split several
+/// ways, or the only one a ref given with `--branch`/`--tag` is looked up as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefKind {
+    Tag,
+    Branch,
+}
+
+impl RefKind {
+    /// Namespace of this kind under `refs/`.
+    pub fn namespace(self) -> &'static str {
+        match self {
+            RefKind::Tag => "tags",
+            RefKind::Branch => "heads",
+        }
+    }
+
+    /// Name of this kind in messages.
+    pub fn label(self) -> &'static str {
+        match self {
+            RefKind::Tag => "tag",
+            RefKind::Branch => "branch",
+        }
+    }
+}
+
+/// What `skills.toml` records for a skill: where it comes from and how it is
+/// pinned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub source_url: String,
+    #[serde(default)]
+    pub collection_url: Option<String>,
+    #[serde(default)]
+    pub until: Option<String>,
+    #[serde(default)]
+    pub archive_format: Option<ArchiveFormat>,
+    #[serde(default)]
+    pub root: Option<String>,
+    #[serde(default)]
+    pub prefer_ref: Option<RefKind>,
+    /// Whether the tracked ref was given as a branch or a tag
+    /// (`install --branch`/`--tag`); it is only looked up as that kind.
+    #[serde(default)]
+    pub ref_kind: Option<RefKind>,
+    /// Prepended to the upstream directory name to form the skill's name
+    /// (`install --prefix`).
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// The path is one skill whether or not it has a manifest
+    /// (`install --no-subdir`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_subdir: bool,
+}
+
+/// What `skills.lock` records for a skill: the state it was resolved to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub source_url: String,
+    pub slug: String,
+    pub path: String,
+    pub sha: String,
+    pub checksum: String,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub ref_name: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub tree_mtime: Option<u64>,
+}
+
+/// A fully resolved skill: its manifest entry merged with its lock entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillEntry {
     pub source_url: String,
@@ -63,6 +723,160 @@ pub struct SkillEntry {
     pub path: String,
     pub sha: String,
     pub checksum: String,
+    #[serde(default)]
+    pub until: Option<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub archive_format: Option<ArchiveFormat>,
+    #[serde(default)]
+    pub root: Option<String>,
+    #[serde(default)]
+    pub prefer_ref: Option<RefKind>,
+    /// Prepended to the upstream directory name to form the skill's name.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Branch or tag the skill tracks (e.g. `main`, `v1.2.0`), as opposed to
+    /// the commit it resolved to.
+    #[serde(default)]
+    pub ref_name: Option<String>,
+    /// Whether `ref_name` is a branch or a tag, when it was given with
+    /// `install --branch` or `--tag`.
+    #[serde(default)]
+    pub ref_kind: Option<RefKind>,
+    /// Installed with `install --no-subdir`: the path is downloaded as one
+    /// skill, without requiring a manifest.
+    #[serde(default)]
+    pub no_subdir: bool,
+    /// When the skill was last written to disk (RFC 3339).
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Newest modification time in the skill directory (ns since the epoch)
+    /// when `checksum` was last verified; `sync` skips hashing while it holds.
+    #[serde(default)]
+    pub tree_mtime: Option<u64>,
+}
+
+/// The ref part of a `.../tree/<ref>/<path>` source URL, given the path it
+/// points at.
+fn ref_name_from_url(source_url: &str, path: &str) -> Option<String> {
+    let (_, tail) = source_url.split_once("/tree/")?;
+    tail.trim_end_matches('/')
+        .strip_suffix(path.trim_matches('/'))?
+        .strip_suffix('/')
+        .filter(|ref_name| !ref_name.is_empty())
+        .map(str::to_string)
+}
+
+/// `source_url` of skills adopted from local files; they have no upstream.
+pub const LOCAL_SOURCE: &str = "local";
+
+impl SkillEntry {
+    /// An entry for a skill adopted from local files, without upstream
+    /// metadata.
+    pub fn local(checksum: String, root: Option<String>) -> Self {
+        Self {
+            source_url: LOCAL_SOURCE.to_string(),
+            collection_url: None,
+            slug: String::new(),
+            path: String::new(),
+            sha: String::new(),
+            checksum,
+            until: None,
+            default_branch: None,
+            archive_format: None,
+            root,
+            prefer_ref: None,
+            prefix: None,
+            ref_name: None,
+            ref_kind: None,
+            no_subdir: false,
+            updated_at: None,
+            tree_mtime: None,
+        }
+    }
+
+    /// The name of the skill `name` without its `install --prefix`.
+    /// For nested skills the prefix is on the last segment, as
+    /// [`prefixed_name`](crate::utils::prefixed_name) puts it.
+    pub fn upstream_name(&self, name: &str) -> String {
+        let (parent, skill) = match name.rsplit_once('/') {
+            Some((parent, skill)) => (Some(parent), skill),
+            None => (None, name),
+        };
+        let skill = self
+            .prefix
+            .as_deref()
+            .and_then(|prefix| skill.strip_prefix(prefix))
+            .unwrap_or(skill);
+        match parent {
+            Some(parent) => format!("{}/{}", parent, skill),
+            None => skill.to_string(),
+        }
+    }
+
+    /// Whether the skill was adopted from local files and has no upstream.
+    pub fn is_local(&self) -> bool {
+        self.source_url == LOCAL_SOURCE
+    }
+
+    /// Whether the skill was recorded by `install --manifest-only` and has
+    /// not been downloaded yet, so it has no checksum.
+    pub fn is_pending(&self) -> bool {
+        self.checksum.is_empty()
+    }
+
+    pub fn from_parts(manifest: ManifestEntry, lock: LockEntry) -> Self {
+        // Entries locked before `ref_name` was recorded take it from the URL.
+        let ref_name = lock
+            .ref_name
+            .or_else(|| ref_name_from_url(&manifest.source_url, &lock.path));
+        Self {
+            source_url: manifest.source_url,
+            collection_url: manifest.collection_url,
+            slug: lock.slug,
+            path: lock.path,
+            sha: lock.sha,
+            checksum: lock.checksum,
+            until: manifest.until,
+            default_branch: lock.default_branch,
+            archive_format: manifest.archive_format,
+            root: manifest.root,
+            prefer_ref: manifest.prefer_ref,
+            prefix: manifest.prefix,
+            ref_name,
+            ref_kind: manifest.ref_kind,
+            no_subdir: manifest.no_subdir,
+            updated_at: lock.updated_at,
+            tree_mtime: lock.tree_mtime,
+        }
+    }
+
+    pub fn split(&self) -> (ManifestEntry, LockEntry) {
+        let manifest = ManifestEntry {
+            source_url: self.source_url.clone(),
+            collection_url: self.collection_url.clone(),
+            until: self.until.clone(),
+            archive_format: self.archive_format,
+            root: self.root.clone(),
+            prefer_ref: self.prefer_ref,
+            ref_kind: self.ref_kind,
+            prefix: self.prefix.clone(),
+            no_subdir: self.no_subdir,
+        };
+        let lock = LockEntry {
+            source_url: self.source_url.clone(),
+            slug: self.slug.clone(),
+            path: self.path.clone(),
+            sha: self.sha.clone(),
+            checksum: self.checksum.clone(),
+            default_branch: self.default_branch.clone(),
+            ref_name: self.ref_name.clone(),
+            updated_at: self.updated_at.clone(),
+            tree_mtime: self.tree_mtime,
+        };
+        (manifest, lock)
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +961,17 @@ checksum = "sha256:abc123"
                 sha: "main".to_string(),
                 path: "path".to_string(),
                 checksum: "sha256:abc123".to_string(),
+                until: None,
+                default_branch: None,
+                archive_format: None,
+                root: None,
+                prefer_ref: None,
+                prefix: None,
+                ref_name: None,
+                ref_kind: None,
+                no_subdir: false,
+                updated_at: None,
+                tree_mtime: None,
             },
         );
 
@@ -165,4 +990,384 @@ checksum = "sha256:abc123"
 
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    #[test]
+    fn test_legacy_config_is_split_on_save() {
+        let temp_dir = std::env::temp_dir().join("skills_test_lock_migration");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("skills.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+[skills.test-skill]
+source_url = "https://github.com/owner/repo/tree/main/path"
+slug = "owner/repo"
+path = "path"
+sha = "abc123"
+checksum = "sha256:abc123"
+"#,
+        )
+        .unwrap();
+
+        let config = SkillsConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.skills["test-skill"].sha, "abc123");
+        assert_eq!(
+            config.skills["test-skill"].ref_name.as_deref(),
+            Some("main")
+        );
+        assert!(config.lock_issues.is_empty());
+        config.save(&config_path).unwrap();
+
+        let manifest = fs::read_to_string(&config_path).unwrap();
+        assert!(manifest.contains("source_url"));
+        assert!(!manifest.contains("checksum"));
+        let lock = fs::read_to_string(lock_path(&config_path)).unwrap();
+        assert!(lock.contains("sha = \"abc123\""));
+        assert!(lock.contains("ref_name = \"main\""));
+
+        let reloaded = SkillsConfig::from_file(&config_path).unwrap();
+        assert_eq!(reloaded.skills["test-skill"].checksum, "sha256:abc123");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_preserves_comments() {
+        let temp_dir = std::env::temp_dir().join("skills_test_preserve_comments");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("skills.toml");
+
+        fs::write(
+            &config_path,
+            r#"# Skills for this project.
+version = 1
+
+# Writing helpers.
+[skills.first]
+source_url = "https://github.com/owner/repo/tree/main/first" # pinned by team
+
+[skills.second]
+source_url = "https://github.com/owner/repo/tree/main/second"
+"#,
+        )
+        .unwrap();
+
+        let mut config = SkillsConfig::from_file(&config_path).unwrap();
+        config.unresolved.remove("second");
+        let mut third = config.unresolved["first"].clone();
+        third.source_url = "https://github.com/owner/repo/tree/main/third".to_string();
+        config.unresolved.insert("third".to_string(), third);
+        config.save(&config_path).unwrap();
+
+        let manifest = fs::read_to_string(&config_path).unwrap();
+        assert!(manifest.starts_with("# Skills for this project.\nversion = 1\n\n"));
+        assert!(manifest.contains("# Writing helpers.\n[skills.first]\n"));
+        assert!(manifest.contains("tree/main/first\" # pinned by team\n"));
+        assert!(!manifest.contains("second"));
+        let first = manifest.find("[skills.first]").unwrap();
+        let third = manifest.find("[skills.third]").unwrap();
+        assert!(first < third);
+
+        let reloaded = SkillsConfig::from_file(&config_path).unwrap();
+        assert_eq!(
+            reloaded.unresolved["third"].source_url,
+            "https://github.com/owner/repo/tree/main/third"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_ref_name_from_url() {
+        let url = "https://github.com/owner/repo/tree/release/v1/skills/demo";
+        assert_eq!(
+            ref_name_from_url(url, "skills/demo").as_deref(),
+            Some("release/v1")
+        );
+        assert_eq!(ref_name_from_url(url, "other/demo"), None);
+        assert_eq!(ref_name_from_url(LOCAL_SOURCE, ""), None);
+    }
+
+    #[test]
+    fn test_lock_issues_for_unlocked_and_changed_entries() {
+        let temp_dir = std::env::temp_dir().join("skills_test_lock_issues");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("skills.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+[skills.changed]
+source_url = "https://github.com/owner/repo/tree/main/new"
+
+[skills.fresh]
+source_url = "https://github.com/owner/repo/tree/main/fresh"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            lock_path(&config_path),
+            r#"
+[skills.changed]
+source_url = "https://github.com/owner/repo/tree/main/old"
+slug = "owner/repo"
+path = "old"
+sha = "abc123"
+checksum = "sha256:abc123"
+
+[skills.removed]
+source_url = "https://github.com/owner/repo/tree/main/removed"
+slug = "owner/repo"
+path = "removed"
+sha = "abc123"
+checksum = "sha256:abc123"
+"#,
+        )
+        .unwrap();
+
+        let config = SkillsConfig::from_file(&config_path).unwrap();
+        assert!(config.skills.is_empty());
+        assert_eq!(
+            config.unresolved.keys().collect::<Vec<_>>(),
+            vec!["changed", "fresh"]
+        );
+        assert_eq!(config.lock_issues.len(), 3);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_directory_conflicts() {
+        let entry = |url: &str| SkillEntry {
+            source_url: url.to_string(),
+            collection_url: None,
+            slug: "owner/repo".to_string(),
+            path: "path".to_string(),
+            sha: "abc123".to_string(),
+            checksum: "sha256:abc123".to_string(),
+            until: None,
+            default_branch: None,
+            archive_format: None,
+            root: None,
+            prefer_ref: None,
+            prefix: None,
+            ref_name: None,
+            ref_kind: None,
+            no_subdir: false,
+            updated_at: None,
+            tree_mtime: None,
+        };
+
+        let mut config = SkillsConfig::default();
+        config.skills.insert(
+            "Foo".to_string(),
+            entry("https://github.com/a/b/tree/main/Foo"),
+        );
+        config.skills.insert(
+            "foo".to_string(),
+            entry("https://github.com/c/d/tree/main/foo"),
+        );
+        config.skills.insert(
+            "bar".to_string(),
+            entry("https://github.com/a/b/tree/main/bar"),
+        );
+
+        let root_dir = |root: Option<&str>| match root {
+            None | Some("default") => Some(PathBuf::from("skills")),
+            Some(root) => Some(PathBuf::from(root)),
+        };
+        assert_eq!(
+            config.directory_conflicts(root_dir),
+            vec![vec!["Foo", "foo"]]
+        );
+
+        config.skills.get_mut("foo").unwrap().root = Some("shared".to_string());
+        assert!(config.directory_conflicts(root_dir).is_empty());
+
+        // A named root pointing at the default directory still conflicts.
+        config.skills.get_mut("foo").unwrap().root = Some("default".to_string());
+        assert_eq!(
+            config.directory_conflicts(root_dir),
+            vec![vec!["Foo", "foo"]]
+        );
+        assert!(config.directory_conflicts(|_| None).is_empty());
+    }
+
+    #[test]
+    fn test_upstream_name() {
+        let mut entry = SkillEntry::local("sha256:abc123".to_string(), None);
+        assert_eq!(entry.upstream_name("acme-editor"), "acme-editor");
+
+        entry.prefix = Some("acme-".to_string());
+        assert_eq!(entry.upstream_name("acme-editor"), "editor");
+        assert_eq!(
+            entry.upstream_name(&crate::utils::prefixed_name("writing/editor", "acme-")),
+            "writing/editor"
+        );
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let temp_dir = std::env::temp_dir().join("skills_test_schema_version");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("skills.toml");
+
+        SkillsConfig::default().save(&config_path).unwrap();
+        assert!(
+            fs::read_to_string(&config_path)
+                .unwrap()
+                .starts_with("version = 1")
+        );
+
+        fs::write(
+            &config_path,
+            "version = 99\n\n[skills.future]\nsource = { kind = \"new\" }\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            SkillsConfig::from_file(&config_path),
+            Err(SkillsError::UnsupportedSchemaVersion {
+                found: 99,
+                supported: SCHEMA_VERSION
+            })
+        ));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_roots_round_trip() {
+        let temp_dir = std::env::temp_dir().join("skills_test_roots");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("skills.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+[roots]
+agents = "agents/skills"
+shared = "shared/skills"
+
+[skills.helper]
+source_url = "https://github.com/owner/repo/tree/main/helper"
+root = "shared"
+"#,
+        )
+        .unwrap();
+
+        let config = SkillsConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.roots["agents"], "agents/skills");
+        assert_eq!(config.root("helper"), Some("shared"));
+        config.save(&config_path).unwrap();
+
+        let reloaded = SkillsConfig::from_file(&config_path).unwrap();
+        assert_eq!(reloaded.roots, config.roots);
+        assert!(reloaded.hooks.post_install.is_none());
+        assert!(
+            !fs::read_to_string(&config_path)
+                .unwrap()
+                .contains("[hooks]")
+        );
+        assert_eq!(reloaded.root("helper"), Some("shared"));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_names_setting() {
+        let config: ManifestFile<ManifestEntry> = toml::from_str(
+            r#"
+[config]
+manifest_names = ["SKILL.md", "AGENT.md"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.settings.manifest_names, ["SKILL.md", "AGENT.md"]);
+
+        let mut settings = Settings::default();
+        settings.add_manifest_names(&[]);
+        assert!(settings.is_empty());
+        settings.add_manifest_names(&["agent.md".to_string(), "skill.md".to_string()]);
+        assert_eq!(settings.manifest_names, ["SKILL.md", "agent.md"]);
+    }
+
+    #[test]
+    fn test_settings_get_set() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.get("manifest_names").unwrap(), None);
+
+        settings
+            .set("manifest_names", "SKILL.md, AGENT.md")
+            .unwrap();
+        assert_eq!(
+            settings.get("manifest_names").unwrap().as_deref(),
+            Some("SKILL.md,AGENT.md")
+        );
+
+        assert!(matches!(
+            settings.set("manifest_names", "docs/SKILL.md"),
+            Err(SkillsError::InvalidSetting { .. })
+        ));
+        assert!(matches!(
+            settings.set("jobs", "8"),
+            Err(SkillsError::UnknownSetting(_))
+        ));
+
+        settings.set("manifest_names", "").unwrap();
+        assert!(settings.is_empty());
+
+        settings.set("update_check", "false").unwrap();
+        assert_eq!(settings.update_check, Some(false));
+        assert!(matches!(
+            settings.set("update_check", "never"),
+            Err(SkillsError::InvalidSetting { .. })
+        ));
+        settings.set("update_check", "").unwrap();
+        assert!(settings.is_empty());
+    }
+
+    #[test]
+    fn test_allowed_sources() {
+        let mut settings = Settings::default();
+        assert!(
+            settings
+                .check_source("https://github.com/anyone/repo/tree/main/demo")
+                .is_ok()
+        );
+
+        settings
+            .set(
+                "allowed_sources",
+                "github.com/Acme-Org/*, re:^github\\.com/Partner/(docs|tools)$",
+            )
+            .unwrap();
+        for url in [
+            "https://github.com/acme-org/skills/tree/main/demo",
+            "https://github.com/Acme-Org/skills",
+            "https://github.com/ACME-ORG/Skills",
+            "git+ssh://git@github.com/acme-org/private.git#main:skills/demo",
+            "https://github.com/partner/docs/tree/v1/skill",
+        ] {
+            assert!(settings.check_source(url).is_ok(), "{url}");
+        }
+        for url in [
+            "https://github.com/other/skills/tree/main/demo",
+            "https://github.com/partner/docs-fork/tree/main/skill",
+            "https://example.com/acme-org/skills",
+        ] {
+            assert!(
+                matches!(
+                    settings.check_source(url),
+                    Err(SkillsError::SourceNotAllowed { .. })
+                ),
+                "{url}"
+            );
+        }
+
+        assert!(matches!(
+            settings.set("allowed_sources", "re:(unclosed"),
+            Err(SkillsError::InvalidSetting { .. })
+        ));
+    }
 }
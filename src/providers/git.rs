@@ -0,0 +1,671 @@
+//! Skills in any repository the system `git` can reach over SSH, addressed
+//! as `git+ssh://git@github.com/owner/repo#ref:path`. Useful for private
+//! repositories when SSH keys are set up but a token is not.
+
+use crate::{
+    archive::is_safe_relative_path,
+    debug,
+    errors::{SkillsError, SkillsResult},
+    models::SkillEntry,
+    providers::{
+        CandidateAttempt, CandidateOutcome, ExtractStats, ExtractTarget, InstallPlan,
+        ResolveOptions, ResolveReport, ResolvedSkill, ResolvedSource, SkillListing, SkillProvider,
+        UpstreamCommit,
+    },
+    utils::{is_manifest_name, manifest_description, manifest_name, unique_temp_name},
+};
+use std::{
+    collections::BTreeSet,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use walkdir::WalkDir;
+
+const SCHEME: &str = "git+ssh://";
+
+/// A parsed `git+ssh://<remote>#<ref>:<path>` URL. Both the ref and the path
+/// are optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitUrlSpec {
+    /// Remote handed to `git`, i.e. the URL without its `git+` prefix.
+    pub remote: String,
+    /// Branch, tag or full commit SHA; `None` follows the remote's HEAD.
+    pub r#ref: Option<String>,
+    /// Directory inside the repository; empty for the repository root.
+    pub path: String,
+}
+
+impl GitUrlSpec {
+    pub fn parse(url: &str) -> SkillsResult<Self> {
+        let invalid = || SkillsError::InvalidGitUrl(url.to_string());
+        let rest = url.strip_prefix(SCHEME).ok_or_else(invalid)?;
+        let (repo, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+        let (r#ref, path) = fragment.split_once(':').unwrap_or((fragment, ""));
+
+        let repo = repo.trim_end_matches('/');
+        let path = path.trim_matches('/');
+        if !repo.contains('/') || (!path.is_empty() && !is_safe_relative_path(path)) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            remote: format!("ssh://{repo}"),
+            r#ref: (!r#ref.is_empty()).then(|| r#ref.to_string()),
+            path: path.to_string(),
+        })
+    }
+
+    /// The repository path on the host without `.git`, e.g. `owner/repo`.
+    pub fn slug(&self) -> &str {
+        let repo = self.remote.strip_prefix("ssh://").unwrap_or(&self.remote);
+        let path = repo.split_once('/').map_or(repo, |(_, path)| path);
+        path.strip_suffix(".git").unwrap_or(path)
+    }
+
+    /// The same repository at `r#ref` and `path`.
+    pub fn url_with(&self, r#ref: Option<&str>, path: &str) -> String {
+        let mut url = format!("git+{}", self.remote);
+        if r#ref.is_some() || !path.is_empty() {
+            url.push('#');
+            url.push_str(r#ref.unwrap_or_default());
+        }
+        if !path.is_empty() {
+            url.push(':');
+            url.push_str(path);
+        }
+        url
+    }
+
+    /// Name of the skill directory: the last path segment, or the repository
+    /// name for the root.
+    fn directory_name(&self) -> &str {
+        match self.path.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name,
+            _ => self.slug().rsplit('/').next().unwrap_or_default(),
+        }
+    }
+
+    fn child_path(&self, name: &str) -> String {
+        if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.path, name)
+        }
+    }
+}
+
+/// Run `git` with `args` (inside `dir`, if given) and return its stdout.
+fn git(dir: Option<&Path>, args: &[&str]) -> SkillsResult<Vec<u8>> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .env("GIT_TERMINAL_PROMPT", "0");
+    debug!("git {}", args.join(" "));
+
+    let output = command.output().map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => SkillsError::GitNotFound,
+        _ => SkillsError::IoError(e),
+    })?;
+    if !output.status.success() {
+        return Err(SkillsError::GitFailed {
+            command: format!("git {}", args.join(" ")),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+fn is_full_sha(value: &str) -> bool {
+    value.len() == 40 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The commit `r#ref` names in `git ls-remote` output. Exact matches win over
+/// the suffix matches `ls-remote` also prints; tags resolve to the commit
+/// they point at.
+fn parse_ls_remote(output: &str, r#ref: &str) -> Option<String> {
+    let refs: Vec<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect();
+    [
+        r#ref.to_string(),
+        format!("refs/heads/{ref}"),
+        format!("refs/tags/{ref}^{{}}"),
+        format!("refs/tags/{ref}"),
+    ]
+    .iter()
+    .find_map(|wanted| {
+        refs.iter()
+            .find(|(_, name)| name == wanted)
+            .map(|(sha, _)| sha.to_string())
+    })
+}
+
+/// How the files under a path are laid out.
+#[derive(Debug, PartialEq)]
+enum Layout {
+    Single,
+    /// Sub-directories with a manifest, and those without one.
+    Batch {
+        skills: Vec<String>,
+        others: Vec<String>,
+    },
+}
+
+/// Work out the layout from the paths of every file under the directory,
+/// relative to it.
+fn detect_layout(files: &[String], manifest_names: &[String]) -> Layout {
+    let is_manifest = |file: &str| !file.contains('/') && is_manifest_name(file, manifest_names);
+    if files.iter().any(|file| is_manifest(file)) {
+        return Layout::Single;
+    }
+
+    let subdirs: BTreeSet<&str> = files
+        .iter()
+        .filter_map(|file| file.split_once('/').map(|(dir, _)| dir))
+        .collect();
+    let (skills, others): (Vec<&str>, Vec<&str>) = subdirs.into_iter().partition(|dir| {
+        files.iter().any(|file| {
+            file.strip_prefix(dir)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .is_some_and(is_manifest)
+        })
+    });
+    Layout::Batch {
+        skills: skills.into_iter().map(str::to_string).collect(),
+        others: others.into_iter().map(str::to_string).collect(),
+    }
+}
+
+/// A scratch repository holding one commit fetched from a remote, removed
+/// when dropped. Only trees are fetched up front; file contents follow when
+/// something is checked out or read.
+struct Checkout {
+    dir: PathBuf,
+}
+
+impl Checkout {
+    fn fetch(remote: &str, sha: &str) -> SkillsResult<Self> {
+        let checkout = Self {
+            dir: env::temp_dir().join(unique_temp_name("skills-man-git")),
+        };
+        fs::create_dir_all(&checkout.dir)?;
+        git(Some(&checkout.dir), &["init", "-q"])?;
+        git(
+            Some(&checkout.dir),
+            &[
+                "fetch",
+                "-q",
+                "--depth",
+                "1",
+                "--filter=blob:none",
+                remote,
+                sha,
+            ],
+        )?;
+        Ok(checkout)
+    }
+
+    /// Every file under `path` at `sha`, relative to `path`.
+    fn files(&self, sha: &str, path: &str) -> SkillsResult<Vec<String>> {
+        let mut args = vec!["ls-tree", "-r", "--name-only", sha];
+        let prefix = format!("{path}/");
+        if !path.is_empty() {
+            args.extend(["--", &prefix]);
+        }
+        let output = git(Some(&self.dir), &args)?;
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .map(|file| file.strip_prefix(&prefix).unwrap_or(file).to_string())
+            .collect())
+    }
+
+    /// Check out `sha`, limited to `paths` unless one of them is the root.
+    fn checkout(&self, sha: &str, paths: &[&str]) -> SkillsResult<()> {
+        if !paths.iter().any(|path| path.is_empty()) {
+            let mut args = vec!["sparse-checkout", "set"];
+            args.extend(paths);
+            git(Some(&self.dir), &args)?;
+        }
+        git(Some(&self.dir), &["checkout", "-q", "--detach", sha])?;
+        Ok(())
+    }
+
+    /// Contents of the manifest in the directory `path`, if it has one.
+    fn manifest(&self, sha: &str, path: &str, manifest_names: &[String]) -> Option<String> {
+        let files = self.files(sha, path).ok()?;
+        let manifest = files
+            .iter()
+            .find(|file| !file.contains('/') && is_manifest_name(file, manifest_names))?;
+        let object = if path.is_empty() {
+            format!("{sha}:{manifest}")
+        } else {
+            format!("{sha}:{path}/{manifest}")
+        };
+        let content = git(Some(&self.dir), &["show", &object]).ok()?;
+        Some(String::from_utf8_lossy(&content).into_owned())
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Copy the files under `src` into `dest`, skipping `.git` and symlinks.
+/// Returns the number of files and bytes copied.
+fn copy_tree(src: &Path, dest: &Path) -> io::Result<(usize, u64)> {
+    let mut files = 0;
+    let mut bytes = 0;
+    let walker = WalkDir::new(src)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry.path().strip_prefix(src).map_err(io::Error::other)?;
+        let target = dest.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            bytes += fs::copy(entry.path(), &target)?;
+            files += 1;
+        }
+    }
+    Ok((files, bytes))
+}
+
+/// Installs skills by shelling out to the system `git`.
+#[derive(Debug, Default)]
+pub struct GitProvider;
+
+impl GitProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The commit the spec's ref names on the remote, or `None` when the
+    /// remote has no such ref.
+    fn resolve_ref(&self, spec: &GitUrlSpec) -> SkillsResult<Option<String>> {
+        let r#ref = spec.r#ref.as_deref().unwrap_or("HEAD");
+        if is_full_sha(r#ref) {
+            return Ok(Some(r#ref.to_string()));
+        }
+        let output = git(None, &["ls-remote", &spec.remote, r#ref])?;
+        Ok(parse_ls_remote(&String::from_utf8_lossy(&output), r#ref))
+    }
+
+    fn resolve_checkout(&self, url: &str) -> SkillsResult<(GitUrlSpec, String, Checkout)> {
+        let spec = GitUrlSpec::parse(url)?;
+        let Some(sha) = self.resolve_ref(&spec)? else {
+            return Err(SkillsError::PathNotFound(vec![url.to_string()]));
+        };
+        let checkout = Checkout::fetch(&spec.remote, &sha)?;
+        Ok((spec, sha, checkout))
+    }
+}
+
+impl SkillProvider for GitProvider {
+    fn handles(&self, url: &str) -> bool {
+        url.starts_with(SCHEME)
+    }
+
+    /// Resolve the ref with `git ls-remote`, then fetch the trees of that
+    /// commit (no file contents) to tell a single skill from a collection.
+    fn resolve_install_plan(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<InstallPlan> {
+        if options.until.is_some() {
+            return Err(SkillsError::UnsupportedOption {
+                source: SCHEME.to_string(),
+                option: "--at".to_string(),
+            });
+        }
+        let (spec, sha, checkout) = self.resolve_checkout(url)?;
+        let files = checkout.files(&sha, &spec.path)?;
+        if files.is_empty() {
+            return Err(SkillsError::PathNotFound(vec![url.to_string()]));
+        }
+
+        let skill =
+            |name: &str, source_url: String, path: String, collection: bool| ResolvedSkill {
+                name: name.to_string(),
+                source_url,
+                collection_url: collection.then(|| url.to_string()),
+                slug: spec.slug().to_string(),
+                sha: sha.clone(),
+                path,
+                default_branch: None,
+                ref_name: spec.r#ref.clone(),
+            };
+        let archive_url = spec.url_with(Some(&sha), "");
+        let layout = if options.no_subdir {
+            Layout::Single
+        } else {
+            detect_layout(&files, &options.manifest_names)
+        };
+        let plan = match layout {
+            Layout::Single => InstallPlan {
+                archive_url,
+                is_batch: false,
+                non_skill_dirs: Vec::new(),
+                skills: vec![skill(
+                    spec.directory_name(),
+                    url.to_string(),
+                    spec.path.clone(),
+                    false,
+                )],
+            },
+            Layout::Batch { skills, .. } if skills.is_empty() => {
+                return Err(SkillsError::NoSkillsFound(spec.path.clone()));
+            }
+            Layout::Batch { skills, others } => InstallPlan {
+                archive_url,
+                is_batch: true,
+                non_skill_dirs: others,
+                skills: skills
+                    .iter()
+                    .map(|name| {
+                        let path = spec.child_path(name);
+                        let source_url = spec.url_with(spec.r#ref.as_deref(), &path);
+                        skill(name, source_url, path, true)
+                    })
+                    .collect(),
+            },
+        };
+        Ok(plan)
+    }
+
+    fn explain_resolution(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<ResolveReport> {
+        let spec = GitUrlSpec::parse(url)?;
+        let r#ref = spec.r#ref.clone().unwrap_or_else(|| "HEAD".to_string());
+        let (outcome, resolved) = match self.resolve_ref(&spec) {
+            Ok(Some(sha)) => {
+                let checkout = Checkout::fetch(&spec.remote, &sha)?;
+                let files = checkout.files(&sha, &spec.path)?;
+                let collection = match detect_layout(&files, &options.manifest_names) {
+                    Layout::Single => Vec::new(),
+                    Layout::Batch { skills, .. } => skills,
+                };
+                let resolved = ResolvedSource {
+                    slug: spec.slug().to_string(),
+                    r#ref: r#ref.clone(),
+                    sha: sha.clone(),
+                    path: spec.path.clone(),
+                    collection,
+                };
+                (CandidateOutcome::Resolved(sha), Some(resolved))
+            }
+            Ok(None) => (CandidateOutcome::NotFound, None),
+            Err(e) => (CandidateOutcome::Failed(e.to_string()), None),
+        };
+        Ok(ResolveReport {
+            candidates: vec![CandidateAttempt {
+                r#ref,
+                path: spec.path.clone(),
+                outcome,
+            }],
+            resolved,
+        })
+    }
+
+    fn browse(&self, url: &str, manifest_names: &[String]) -> SkillsResult<Vec<SkillListing>> {
+        let (spec, sha, checkout) = self.resolve_checkout(url)?;
+        let files = checkout.files(&sha, &spec.path)?;
+        let listing = |name: &str, path: &str, has_manifest: bool| {
+            let manifest = has_manifest
+                .then(|| checkout.manifest(&sha, path, manifest_names))
+                .flatten();
+            SkillListing {
+                name: name.to_string(),
+                has_manifest,
+                description: manifest.as_deref().and_then(manifest_description),
+                manifest_name: manifest.as_deref().and_then(manifest_name),
+            }
+        };
+
+        match detect_layout(&files, manifest_names) {
+            Layout::Single => Ok(vec![listing(spec.directory_name(), &spec.path, true)]),
+            Layout::Batch { skills, others } if skills.is_empty() && others.is_empty() => {
+                Err(SkillsError::NoSkillsFound(spec.path.clone()))
+            }
+            Layout::Batch { skills, others } => {
+                let mut listings: Vec<SkillListing> = skills
+                    .iter()
+                    .map(|name| listing(name, &spec.child_path(name), true))
+                    .chain(
+                        others
+                            .iter()
+                            .map(|name| listing(name, &spec.child_path(name), false)),
+                    )
+                    .collect();
+                listings.sort_by(|a, b| a.name.cmp(&b.name));
+                Ok(listings)
+            }
+        }
+    }
+
+    /// Fetch the commit named by `archive_url` (`git+ssh://...#<sha>`), check
+    /// out only the targets' directories and copy each into place.
+    fn fetch_and_extract(
+        &self,
+        archive_url: &str,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats> {
+        let spec = GitUrlSpec::parse(archive_url)?;
+        let Some(sha) = spec.r#ref.as_deref() else {
+            return Err(SkillsError::InvalidGitUrl(archive_url.to_string()));
+        };
+        let checkout = Checkout::fetch(&spec.remote, sha)?;
+        let paths: Vec<&str> = targets.iter().map(|target| target.path.as_str()).collect();
+        checkout.checkout(sha, &paths)?;
+
+        let mut stats = ExtractStats::default();
+        for target in targets {
+            let src = checkout.dir.join(&target.path);
+            if !src.is_dir() {
+                return Err(SkillsError::PathNotFound(vec![target.path.clone()]));
+            }
+            let (files, bytes) = copy_tree(&src, &target.dest_dir)?;
+            stats.files.push(files);
+            stats.bytes += bytes;
+        }
+        Ok(stats)
+    }
+
+    fn expected_file_counts(&self, _skills: &[ResolvedSkill]) -> SkillsResult<Option<Vec<usize>>> {
+        // The files are copied from a checkout, not streamed from an archive.
+        Ok(None)
+    }
+
+    fn archive_url_for_entry(&self, entry: &SkillEntry) -> String {
+        match GitUrlSpec::parse(&entry.source_url) {
+            Ok(spec) => spec.url_with(Some(&entry.sha), ""),
+            Err(_) => entry.source_url.clone(),
+        }
+    }
+
+    fn source_url_with_ref(&self, entry: &SkillEntry, new_ref: &str) -> SkillsResult<String> {
+        let spec = GitUrlSpec::parse(&entry.source_url)?;
+        Ok(spec.url_with(Some(new_ref), &spec.path))
+    }
+
+    /// The commit the tracked ref points at now. Unlike GitHub sources this
+    /// moves with any commit to the ref, not only those touching the skill.
+    fn latest_commit(&self, entry: &SkillEntry) -> SkillsResult<Option<UpstreamCommit>> {
+        let spec = GitUrlSpec::parse(&entry.source_url)?;
+        Ok(self
+            .resolve_ref(&spec)?
+            .map(|sha| UpstreamCommit { sha, date: None }))
+    }
+
+    fn relocate_default_branch(
+        &self,
+        _url: &str,
+        _old_branch: &str,
+    ) -> SkillsResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_url() {
+        let spec =
+            GitUrlSpec::parse("git+ssh://git@github.com/owner/repo.git#v1.0:skills/demo/").unwrap();
+        assert_eq!(spec.remote, "ssh://git@github.com/owner/repo.git");
+        assert_eq!(spec.r#ref.as_deref(), Some("v1.0"));
+        assert_eq!(spec.path, "skills/demo");
+        assert_eq!(spec.slug(), "owner/repo");
+        assert_eq!(spec.directory_name(), "demo");
+
+        let root = GitUrlSpec::parse("git+ssh://git@github.com/owner/repo").unwrap();
+        assert_eq!(root.r#ref, None);
+        assert_eq!(root.path, "");
+        assert_eq!(root.directory_name(), "repo");
+        assert_eq!(
+            root.url_with(None, "demo"),
+            "git+ssh://git@github.com/owner/repo#:demo"
+        );
+        assert_eq!(
+            GitUrlSpec::parse(&root.url_with(None, "demo"))
+                .unwrap()
+                .path,
+            "demo"
+        );
+
+        assert!(GitUrlSpec::parse("https://github.com/owner/repo").is_err());
+        let err = GitUrlSpec::parse("git+ssh://git@github.com/repo#main:../x").unwrap_err();
+        assert_eq!(err.kind(), "InvalidGitUrl");
+        assert!(
+            err.to_string()
+                .contains("git+ssh://{host}/{owner}/{repo}#{ref}:{path}")
+        );
+
+        let options = ResolveOptions {
+            until: Some("2024-01-01".to_string()),
+            ..Default::default()
+        };
+        let err = GitProvider::new()
+            .resolve_install_plan("git+ssh://git@github.com/owner/repo", &options)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("--at is not supported for git+ssh:// sources")
+        );
+    }
+
+    #[test]
+    fn test_parse_ls_remote() {
+        let output = "\
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\tHEAD
+bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\trefs/heads/feature/main
+cccccccccccccccccccccccccccccccccccccccc\trefs/heads/main
+dddddddddddddddddddddddddddddddddddddddd\trefs/tags/v1
+eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee\trefs/tags/v1^{}
+";
+        assert_eq!(
+            parse_ls_remote(output, "HEAD").as_deref(),
+            Some("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+        );
+        assert_eq!(
+            parse_ls_remote(output, "main").as_deref(),
+            Some("cccccccccccccccccccccccccccccccccccccccc")
+        );
+        assert_eq!(
+            parse_ls_remote(output, "v1").as_deref(),
+            Some("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee")
+        );
+        assert_eq!(parse_ls_remote(output, "missing"), None);
+    }
+
+    #[test]
+    fn test_detect_layout() {
+        let names = Vec::new();
+        let files = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            detect_layout(&files(&["SKILL.md", "scripts/run.sh"]), &names),
+            Layout::Single
+        );
+        assert_eq!(
+            detect_layout(
+                &files(&[
+                    "a/SKILL.md",
+                    "a/x.md",
+                    "b/SKILL.md",
+                    "docs/README.md",
+                    "LICENSE"
+                ]),
+                &names
+            ),
+            Layout::Batch {
+                skills: vec!["a".to_string(), "b".to_string()],
+                others: vec!["docs".to_string()],
+            }
+        );
+        // A manifest nested deeper doesn't make its grandparent a skill.
+        assert_eq!(
+            detect_layout(&files(&["a/b/SKILL.md"]), &names),
+            Layout::Batch {
+                skills: Vec::new(),
+                others: vec!["a".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_checkout_copies_only_target() {
+        let temp_dir = env::temp_dir().join("skills_test_git_checkout");
+        fs::remove_dir_all(&temp_dir).ok();
+        let upstream = temp_dir.join("upstream");
+        fs::create_dir_all(upstream.join("skills/demo")).unwrap();
+        fs::write(upstream.join("skills/demo/SKILL.md"), b"# Demo").unwrap();
+        fs::write(upstream.join("README.md"), b"readme").unwrap();
+        let run = |args: &[&str]| git(Some(&upstream), args).unwrap();
+        run(&["init", "-q"]);
+        run(&["add", "."]);
+        run(&[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "init",
+        ]);
+        let sha = String::from_utf8(run(&["rev-parse", "HEAD"])).unwrap();
+        let sha = sha.trim();
+
+        let remote = format!("file://{}", upstream.display());
+        let checkout = Checkout::fetch(&remote, sha).unwrap();
+        assert_eq!(checkout.files(sha, "skills").unwrap(), ["demo/SKILL.md"]);
+        checkout.checkout(sha, &["skills/demo"]).unwrap();
+
+        let dest = temp_dir.join("dest");
+        let (files, bytes) = copy_tree(&checkout.dir.join("skills/demo"), &dest).unwrap();
+        assert_eq!((files, bytes), (1, 6));
+        assert_eq!(fs::read(dest.join("SKILL.md")).unwrap(), b"# Demo");
+
+        let scratch = checkout.dir.clone();
+        drop(checkout);
+        assert!(!scratch.exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
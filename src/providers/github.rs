@@ -1,16 +1,55 @@
 use crate::{
+    archive::{
+        apply_executable_bits, body_snippet, extract_archive, is_safe_relative_path,
+        looks_like_archive,
+    },
+    debug,
     errors::{SkillsError, SkillsResult},
-    models::SkillEntry,
-    providers::{ExtractTarget, InstallPlan, ResolvedSkill, SkillProvider},
+    http::{
+        CountingReader, EtagCache, MAX_RATE_LIMIT_WAIT_SECS, RateLimiter, create_agent,
+        request_error, secondary_rate_limit_wait, status_error,
+    },
+    models::{ArchiveFormat, RefKind, SkillEntry},
+    providers::{
+        CandidateAttempt, CandidateOutcome, ExtractStats, ExtractTarget, InstallPlan,
+        ResolveOptions, ResolveReport, ResolvedSkill, ResolvedSource, SkillListing, SkillProvider,
+        UpstreamCommit,
+    },
+    style, trace,
+    utils::{is_manifest_name, manifest_description, manifest_name, parallel_map},
 };
-use flate2::read::GzDecoder;
 use regex::Regex;
 use serde::Deserialize;
-use std::sync::OnceLock;
-use std::{env, fs};
-use tar::Archive;
+use std::collections::{HashMap, hash_map::Entry};
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    thread,
+    time::Duration,
+};
+use ureq::RequestBuilder;
 use ureq::typestate::WithoutBody;
-use ureq::{RequestBuilder, config::Config};
+
+/// Delays between retries of an archive download that returned 404.
+const ARCHIVE_RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(5)];
+
+/// Repositories larger than this many KiB have single skills fetched file
+/// by file through the Trees API rather than as a whole archive.
+const SPARSE_REPO_SIZE_KIB: u64 = 100 * 1024;
+
+/// How long a repository's details, such as its default branch, are reused
+/// from the cache without asking GitHub.
+pub const DEFAULT_REPO_INFO_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How many Contents API requests run at once when checking the
+/// sub-directories of a collection for a manifest.
+pub const DEFAULT_API_JOBS: usize = 8;
+
+/// How many times a request hitting the secondary rate limit is retried.
+const SECONDARY_RATE_LIMIT_RETRIES: usize = 2;
 
 const GITHUB_API_VERSION: &str = "2026-03-10";
 
@@ -21,6 +60,10 @@ pub struct GitHubUrlSpec {
 }
 
 impl GitHubUrlSpec {
+    pub fn repo_url(&self) -> String {
+        format!("https://api.github.com/repos/{}", self.slug)
+    }
+
     pub fn parse(url: &str) -> SkillsResult<Self> {
         static RE: OnceLock<Regex> = OnceLock::new();
         let re = RE.get_or_init(|| {
@@ -48,10 +91,71 @@ impl GitHubUrlSpec {
         })
     }
 
+    /// A spec for the repository of the GitHub `url` with `ref` and `path`
+    /// given explicitly instead of read from the URL.
+    pub fn explicit(url: &str, r#ref: &str, path: &str) -> SkillsResult<Self> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| {
+            Regex::new(r"^https://github\.com/([^/]+/[^/]+?)(?:\.git)?(?:/.*)?$").unwrap()
+        });
+        let captures = re
+            .captures(url)
+            .ok_or_else(|| SkillsError::InvalidUrl(url.to_string()))?;
+        let tail: Vec<String> = r#ref
+            .split('/')
+            .chain(path.split('/'))
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect();
+        if tail.len() < 2 {
+            return SkillsError::InvalidUrl(url.to_string()).into();
+        }
+        Ok(Self {
+            slug: captures[1].to_string(),
+            tail,
+        })
+    }
+
+    /// The tree URL this spec was parsed from.
+    pub fn url(&self) -> String {
+        format!(
+            "https://github.com/{}/tree/{}",
+            self.slug,
+            self.tail.join("/")
+        )
+    }
+
     pub fn directory_name(&self) -> &str {
         self.tail.last().map(String::as_str).unwrap()
     }
 
+    /// Rebuild the tree URL with `old_ref` swapped for `new_ref`, or `None`
+    /// when the URL does not start with `old_ref`.
+    pub fn replace_ref(&self, old_ref: &str, new_ref: &str) -> Option<String> {
+        let old_parts: Vec<&str> = old_ref.split('/').collect();
+        if self.tail.len() <= old_parts.len()
+            || !self.tail.iter().zip(&old_parts).all(|(a, b)| a == b)
+        {
+            return None;
+        }
+        Some(format!(
+            "https://github.com/{}/tree/{}/{}",
+            self.slug,
+            new_ref,
+            self.tail[old_parts.len()..].join("/")
+        ))
+    }
+
+    /// The ref part of the URL, given the repository path it points at.
+    pub fn ref_for_path(&self, path: &str) -> Option<String> {
+        let path_parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        let ref_len = self.tail.len().checked_sub(path_parts.len())?;
+        if ref_len == 0 || self.tail[ref_len..] != path_parts[..] {
+            return None;
+        }
+        Some(self.tail[..ref_len].join("/"))
+    }
+
     pub fn candidates(&self) -> Vec<GitHubUrl> {
         (1..self.tail.len())
             .map(|split| {
@@ -81,6 +185,18 @@ impl GitHubUrl {
         self
     }
 
+    /// This URL with commits looked up from `head` rather than by ref name,
+    /// when it is given.
+    fn pinned_to(&self, head: Option<&str>) -> Self {
+        match head {
+            Some(head) => Self {
+                r#ref: head.to_string(),
+                ..self.clone()
+            },
+            None => self.clone(),
+        }
+    }
+
     pub fn child(&self, child_name: &str) -> Self {
         Self {
             slug: self.slug.clone(),
@@ -91,29 +207,37 @@ impl GitHubUrl {
     }
 
     pub fn tarball_url(&self) -> String {
-        format!(
-            "https://api.github.com/repos/{}/tarball/{}",
-            self.slug,
-            urlencoding::encode(&self.sha)
-        )
+        self.archive_url(ArchiveFormat::Tar)
     }
 
-    pub fn commits_url(&self) -> String {
-        format!(
+    pub fn archive_url(&self, format: ArchiveFormat) -> String {
+        archive_url(&self.slug, &self.sha, format)
+    }
+
+    pub fn commits_url(&self, until: Option<&str>) -> String {
+        let mut url = format!(
             "https://api.github.com/repos/{}/commits?sha={}&path={}&per_page=1",
             self.slug,
             urlencoding::encode(&self.r#ref),
             urlencoding::encode(&self.path)
+        );
+        if let Some(until) = until {
+            url.push_str(&format!("&until={}", urlencoding::encode(until)));
+        }
+        url
+    }
+
+    /// Refs API URL that exists only when this URL's ref is a `kind` ref.
+    pub fn git_ref_url(&self, kind: RefKind) -> String {
+        format!(
+            "https://api.github.com/repos/{}/git/ref/{}/{}",
+            self.slug,
+            kind.namespace(),
+            encode_path_segments(&self.r#ref)
         )
     }
 
     pub fn contents_url(&self) -> String {
-        fn encode_path_segments(path: &str) -> String {
-            path.split('/')
-                .map(|part| urlencoding::encode(part).into_owned())
-                .collect::<Vec<_>>()
-                .join("/")
-        }
         format!(
             "https://api.github.com/repos/{}/contents/{}?ref={}",
             self.slug,
@@ -123,23 +247,205 @@ impl GitHubUrl {
     }
 }
 
-fn proxy_from_env() -> Option<String> {
-    for key in [
-        "HTTPS_PROXY",
-        "https_proxy",
-        "ALL_PROXY",
-        "all_proxy",
-        "HTTP_PROXY",
-        "http_proxy",
-    ] {
-        if let Ok(value) = env::var(key) {
-            let trimmed = value.trim();
-            if !trimmed.is_empty() {
-                return Some(trimmed.to_string());
+/// A GitHub release URL: `/releases/tag/<tag>`, `/releases/latest` or
+/// `/releases/download/<tag>/<asset>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseSpec {
+    pub slug: String,
+    /// `None` follows the newest release.
+    pub tag: Option<String>,
+    /// Asset to install; `None` picks the first archive attached.
+    pub asset: Option<String>,
+}
+
+impl ReleaseSpec {
+    pub fn parse(url: &str) -> Option<Self> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| {
+            Regex::new(r"^https://github\.com/([^/]+/[^/]+)/releases/(.+?)/?$").unwrap()
+        });
+        let captures = re.captures(url)?;
+        let slug = captures[1].to_string();
+        let parts: Vec<&str> = captures[2].split('/').collect();
+
+        let (tag, asset) = match parts.as_slice() {
+            ["latest"] => (None, None),
+            ["tag", tag @ ..] if !tag.is_empty() => (Some(tag.join("/")), None),
+            ["download", tag @ .., asset] if !tag.is_empty() => {
+                (Some(tag.join("/")), Some(asset.to_string()))
             }
+            _ => return None,
+        };
+        Some(Self { slug, tag, asset })
+    }
+
+    /// Repository name, used as the skill name.
+    pub fn repo_name(&self) -> &str {
+        self.slug.rsplit('/').next().unwrap_or(&self.slug)
+    }
+
+    pub fn release_api_url(&self) -> String {
+        match &self.tag {
+            Some(tag) => format!(
+                "https://api.github.com/repos/{}/releases/tags/{}",
+                self.slug,
+                encode_path_segments(tag)
+            ),
+            None => format!("https://api.github.com/repos/{}/releases/latest", self.slug),
         }
     }
-    None
+
+    /// The release URL this spec was parsed from.
+    pub fn url(&self) -> String {
+        match &self.tag {
+            Some(tag) => self.url_for_tag(tag),
+            None => format!("https://github.com/{}/releases/latest", self.slug),
+        }
+    }
+
+    /// The same kind of URL, pointing at the release `tag`.
+    pub fn url_for_tag(&self, tag: &str) -> String {
+        match &self.asset {
+            Some(asset) => format!(
+                "https://github.com/{}/releases/download/{}/{}",
+                self.slug, tag, asset
+            ),
+            None => format!("https://github.com/{}/releases/tag/{}", self.slug, tag),
+        }
+    }
+}
+
+/// Git data API URL of the annotated tag object `sha`.
+fn tag_object_url(slug: &str, sha: &str) -> String {
+    format!("https://api.github.com/repos/{}/git/tags/{}", slug, sha)
+}
+
+/// The `(type, sha)` of the object a refs API or tag object response points
+/// at.
+fn git_object(json: &serde_json::Value) -> Option<(&str, &str)> {
+    let object = json.get("object")?;
+    Some((object.get("type")?.as_str()?, object.get("sha")?.as_str()?))
+}
+
+/// Commits API lookup of the commit a release tag points at.
+fn release_commit_url(slug: &str, tag: &str) -> GitHubUrl {
+    GitHubUrl {
+        slug: slug.to_string(),
+        r#ref: tag.to_string(),
+        sha: tag.to_string(),
+        path: String::new(),
+    }
+}
+
+/// File name endings of release assets that can be installed.
+const RELEASE_ASSET_SUFFIXES: [&str; 7] = [
+    ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar.xz", ".txz", ".zip",
+];
+
+fn encode_path_segments(path: &str) -> String {
+    path.split('/')
+        .map(|part| urlencoding::encode(part).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn archive_url(slug: &str, sha: &str, format: ArchiveFormat) -> String {
+    let endpoint = match format {
+        ArchiveFormat::Tar => "tarball",
+        ArchiveFormat::Zip => "zipball",
+    };
+    format!(
+        "https://api.github.com/repos/{}/{}/{}",
+        slug,
+        endpoint,
+        urlencoding::encode(sha)
+    )
+}
+
+/// Split an archive URL built by [`archive_url`] back into its slug and SHA.
+fn parse_archive_url(url: &str) -> Option<(String, String)> {
+    let rest = url.strip_prefix("https://api.github.com/repos/")?;
+    let mut parts = rest.splitn(4, '/');
+    let (owner, repo, _endpoint, sha) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let sha = urlencoding::decode(sha).ok()?;
+    Some((format!("{}/{}", owner, repo), sha.into_owned()))
+}
+
+fn file_contents_url(slug: &str, sha: &str, path: &str) -> String {
+    format!(
+        "https://api.github.com/repos/{}/contents/{}?ref={}",
+        slug,
+        encode_path_segments(path),
+        urlencoding::encode(sha)
+    )
+}
+
+fn trees_url(slug: &str, sha: &str) -> String {
+    format!(
+        "https://api.github.com/repos/{}/git/trees/{}?recursive=1",
+        slug,
+        urlencoding::encode(sha)
+    )
+}
+
+fn blob_url(slug: &str, sha: &str) -> String {
+    format!("https://api.github.com/repos/{}/git/blobs/{}", slug, sha)
+}
+
+/// Number of files (blobs) below `path` in a recursive tree listing.
+fn count_blobs_under(tree: &[TreeItem], path: &str) -> usize {
+    let prefix = format!("{}/", path.trim_end_matches('/'));
+    tree.iter()
+        .filter(|item| item.item_type == "blob" && item.path.starts_with(&prefix))
+        .count()
+}
+
+/// Extract `targets` from the archive downloaded from `url`. A body that is
+/// not an archive, such as a proxy's login page, is reported with its
+/// content type and first bytes instead of as a broken archive.
+fn extract_response(
+    url: &str,
+    response: ureq::http::Response<ureq::Body>,
+    targets: &[ExtractTarget],
+) -> SkillsResult<ExtractStats> {
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut reader = BufReader::new(CountingReader::new(response.into_body().into_reader()));
+    let head = reader
+        .fill_buf()
+        .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
+    if !looks_like_archive(head) {
+        return Err(SkillsError::NotAnArchive {
+            url: url.to_string(),
+            content_type,
+            snippet: body_snippet(head),
+        });
+    }
+    let files = extract_archive(&mut reader, targets)?;
+    Ok(ExtractStats {
+        bytes: reader.get_ref().count(),
+        files,
+    })
+}
+
+/// Extract the newest commit from a commits API response. The SHA always
+/// comes from the response, so an abbreviated SHA in the URL is stored as the
+/// full 40-character SHA.
+fn parse_latest_commit(json: &serde_json::Value) -> Option<UpstreamCommit> {
+    let commit = json.get(0)?;
+    let sha = commit.get("sha")?.as_str()?;
+    let date = commit
+        .pointer("/commit/committer/date")
+        .and_then(|x| x.as_str())
+        .map(str::to_string);
+    Some(UpstreamCommit {
+        sha: sha.to_string(),
+        date,
+    })
 }
 
 fn github_token_from_env() -> Option<String> {
@@ -166,7 +472,11 @@ fn config_github_request(request: RequestBuilder<WithoutBody>) -> RequestBuilder
 #[derive(Debug)]
 enum SkillDetectionResult {
     Single,
-    Batch(Vec<String>),
+    /// Sub-directories with a SKILL.md, and those without one.
+    Batch {
+        skills: Vec<String>,
+        others: Vec<String>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -176,121 +486,338 @@ struct ContentItem {
     item_type: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct TreeResponse {
+    tree: Vec<TreeItem>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeItem {
+    path: String,
+    #[serde(rename = "type")]
+    item_type: String,
+    #[serde(default)]
+    sha: String,
+    /// Git file mode in octal, e.g. `100755` for an executable or `120000`
+    /// for a symlink whose blob holds the link target.
+    #[serde(default)]
+    mode: String,
+}
+
+impl TreeItem {
+    fn is_symlink(&self) -> bool {
+        self.mode == "120000"
+    }
+}
+
+/// Write the downloaded `body` of the tree `item` to `dest` the way archive
+/// extraction would: symlinks are recreated as links and executable bits
+/// are kept.
+fn write_tree_file(dest: &Path, item: &TreeItem, body: &[u8]) -> io::Result<()> {
+    #[cfg(unix)]
+    if item.is_symlink() {
+        if fs::symlink_metadata(dest).is_ok() {
+            fs::remove_file(dest)?;
+        }
+        let target = String::from_utf8_lossy(body);
+        return std::os::unix::fs::symlink(target.as_ref(), dest);
+    }
+    fs::write(dest, body)?;
+    apply_executable_bits(dest, u32::from_str_radix(&item.mode, 8).ok())
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    /// API URL; downloads the asset when asked for `application/octet-stream`.
+    url: String,
+}
+
+impl Release {
+    /// The asset called `name`, or else the first installable archive.
+    fn pick_asset(&self, name: Option<&str>) -> SkillsResult<&ReleaseAsset> {
+        let asset = match name {
+            Some(name) => self.assets.iter().find(|asset| asset.name == name),
+            None => self.assets.iter().find(|asset| {
+                RELEASE_ASSET_SUFFIXES
+                    .iter()
+                    .any(|suffix| asset.name.to_ascii_lowercase().ends_with(suffix))
+            }),
+        };
+        asset.ok_or_else(|| SkillsError::NoReleaseAsset {
+            tag: self.tag_name.clone(),
+            asset: name.map(str::to_string),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RepoInfo {
+    default_branch: String,
+    #[serde(default)]
+    private: bool,
+    /// Repository size in KiB.
+    #[serde(default)]
+    size: u64,
+    /// Only reported for authenticated requests.
+    #[serde(default)]
+    permissions: Option<RepoPermissions>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RepoPermissions {
+    #[serde(default)]
+    pull: bool,
+}
+
 pub struct GitHubProvider {
     agent: ureq::Agent,
+    limiter: RateLimiter,
+    cache: Option<EtagCache>,
+    sparse: bool,
+    repo_info_ttl: Duration,
+    /// Repository details already looked up in this run, by slug.
+    repo_infos: Mutex<HashMap<String, RepoInfo>>,
+    api_jobs: usize,
+    wait_on_rate_limit: bool,
 }
 
 impl GitHubProvider {
     pub fn new() -> SkillsResult<Self> {
-        let agent = if let Some(proxy_url) = proxy_from_env() {
-            let proxy = ureq::Proxy::new(&proxy_url)
-                .map_err(|e| SkillsError::NetworkError(e.to_string()))?;
-            let config = Config::builder().proxy(Some(proxy)).build();
-            ureq::Agent::new_with_config(config)
-        } else {
-            ureq::Agent::new_with_defaults()
-        };
-        Ok(Self { agent })
+        Ok(Self {
+            agent: create_agent()?,
+            limiter: RateLimiter::default(),
+            cache: None,
+            sparse: false,
+            repo_info_ttl: DEFAULT_REPO_INFO_TTL,
+            repo_infos: Mutex::default(),
+            api_jobs: DEFAULT_API_JOBS,
+            wait_on_rate_limit: true,
+        })
     }
 
-    fn download_and_extract(&self, url: &str, targets: &[ExtractTarget]) -> SkillsResult<()> {
-        let response = match config_github_request(self.agent.get(url))
-            .header("Accept", "application/vnd.github+json")
-            .call()
-        {
-            Ok(response) => response,
-            Err(ureq::Error::StatusCode(status)) => {
-                return Err(match status {
-                    404 => SkillsError::NotFound {
-                        url: url.to_string(),
-                    },
-                    403 => SkillsError::Forbidden {
-                        url: url.to_string(),
-                    },
-                    429 => SkillsError::RateLimited,
-                    _ => SkillsError::HttpError {
-                        status,
-                        message: url.to_string(),
-                    },
-                });
-            }
-            Err(e) => return SkillsError::NetworkError(e.to_string()).into(),
-        };
+    /// Leave `reserve` requests of each rate-limit window for other tools.
+    pub fn with_rate_limit_reserve(mut self, reserve: u64) -> Self {
+        self.limiter = RateLimiter::new(reserve);
+        self
+    }
 
-        let decoder = GzDecoder::new(response.into_body().into_reader());
-        let mut archive = Archive::new(decoder);
+    /// Revalidate directory listings against `cache` instead of always
+    /// downloading them again.
+    pub fn with_cache(mut self, cache: EtagCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 
-        let mut found = vec![false; targets.len()];
-        let mut top_level_dir: Option<String> = None;
+    /// Reuse cached repository details for up to `ttl` without asking
+    /// GitHub.
+    pub fn with_repo_info_ttl(mut self, ttl: Duration) -> Self {
+        self.repo_info_ttl = ttl;
+        self
+    }
 
-        for entry in archive
-            .entries()
-            .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?
-        {
-            let mut entry = entry.map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
-            let entry_path = entry
-                .path()
-                .map_err(|e| SkillsError::InvalidArchive(e.to_string()))?;
-            let entry_str = entry_path.to_string_lossy();
-
-            if top_level_dir.is_none()
-                && let Some(slash_pos) = entry_str.find('/')
-            {
-                top_level_dir = Some(entry_str[..slash_pos].to_string());
+    /// Check up to `jobs` sub-directories of a collection at once.
+    pub fn with_api_jobs(mut self, jobs: usize) -> Self {
+        self.api_jobs = jobs;
+        self
+    }
+
+    /// Fetch single skills through the Trees API even from small
+    /// repositories.
+    pub fn with_sparse(mut self, sparse: bool) -> Self {
+        self.sparse = sparse;
+        self
+    }
+
+    /// Whether to wait out rate limits, with a warning on stderr. Without
+    /// it, a rate-limited request fails at once and silently, as background
+    /// work must.
+    pub fn with_rate_limit_waits(mut self, wait: bool) -> Self {
+        self.wait_on_rate_limit = wait;
+        self
+    }
+
+    /// Whether to fetch a single skill of `slug` file by file: when asked
+    /// to, or when the repository is too large to download whole.
+    fn prefer_sparse(&self, slug: &str) -> bool {
+        if self.sparse {
+            return true;
+        }
+        // Without the size, the archive is the safe default.
+        self.repo_info(slug, false)
+            .is_ok_and(|info| info.size > SPARSE_REPO_SIZE_KIB)
+    }
+
+    /// Fetch the only target of `archive_url` through the Trees and Blobs
+    /// APIs instead of downloading the archive. Returns `None` when the
+    /// tarball should be used instead.
+    fn sparse_extract(
+        &self,
+        archive_url: &str,
+        targets: &[ExtractTarget],
+    ) -> Option<SkillsResult<ExtractStats>> {
+        let [target] = targets else {
+            return None;
+        };
+        let (slug, sha) = parse_archive_url(archive_url)?;
+        if !self.prefer_sparse(&slug) {
+            return None;
+        }
+        let tree = match self.fetch_tree(&slug, &sha) {
+            Ok(tree) if !tree.truncated => tree,
+            Ok(_) => {
+                debug!(
+                    "Tree of {} is too large to list, downloading the archive",
+                    slug
+                );
+                return None;
+            }
+            Err(e) => {
+                debug!("Could not list the tree of {}: {}", slug, e);
+                return None;
             }
+        };
+        debug!("Fetching {} through the Trees API", target.path);
+        Some(self.fetch_tree_files(
+            &tree,
+            targets,
+            |item| blob_url(&slug, &item.sha),
+            |url| status_error(404, url),
+        ))
+    }
 
-            if let Some(ref top_dir) = top_level_dir {
-                for (idx, target) in targets.iter().enumerate() {
-                    let expected_prefix = format!("{}/{}/", top_dir, target.path);
-                    if entry_str.starts_with(&expected_prefix) {
-                        let relative = &entry_str[expected_prefix.len()..];
-                        if relative.is_empty() {
-                            break;
-                        }
-                        found[idx] = true;
-                        let dest_path = target.dest_dir.join(relative);
-                        if let Some(parent) = dest_path.parent() {
-                            fs::create_dir_all(parent)?;
-                        }
-                        entry.unpack(&dest_path)?;
-                        break;
+    /// Download the archive at `url` and extract `targets` from it. GitHub
+    /// can briefly 404 on archives of very fresh commits, so a 404 is retried
+    /// a few times before falling back to fetching the files one by one.
+    fn download_and_extract(
+        &self,
+        url: &str,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats> {
+        if let Some(result) = self.sparse_extract(url, targets) {
+            return result;
+        }
+        let mut delays = ARCHIVE_RETRY_DELAYS.iter();
+        let response = loop {
+            match self.get(url) {
+                Ok(response) => break response,
+                Err(ureq::Error::StatusCode(404)) => match delays.next() {
+                    Some(delay) => {
+                        debug!("Archive not found, retrying in {}s", delay.as_secs());
+                        thread::sleep(*delay);
                     }
-                }
+                    None => return self.fetch_files_individually(url, targets),
+                },
+                Err(ureq::Error::StatusCode(status)) => return Err(status_error(status, url)),
+                Err(e) => return Err(request_error(e)),
             }
-        }
-
-        let missing_paths: Vec<_> = targets
-            .iter()
-            .zip(found)
-            .filter_map(|(target, found)| (!found).then_some(target.path.clone()))
-            .collect();
+        };
+        extract_response(url, response, targets)
+    }
 
-        if !missing_paths.is_empty() {
-            return SkillsError::PathNotFound(missing_paths).into();
+    /// Last resort when the archive stays unavailable: list the commit's
+    /// tree and download each file of the targets through the Contents API.
+    fn fetch_files_individually(
+        &self,
+        archive_url: &str,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats> {
+        let not_ready = || SkillsError::ArchiveNotReady {
+            url: archive_url.to_string(),
+        };
+        let Some((slug, sha)) = parse_archive_url(archive_url) else {
+            return Err(status_error(404, archive_url));
+        };
+        // A missing tree means the commit itself is gone, not just its archive.
+        let tree = self.fetch_tree(&slug, &sha)?;
+        if tree.truncated {
+            return Err(not_ready());
         }
+        debug!("Archive unavailable, fetching files individually");
+        // The Contents API serves the file a symlink points to rather than
+        // the link itself, so links are fetched as blobs.
+        self.fetch_tree_files(
+            &tree,
+            targets,
+            |item| {
+                if item.is_symlink() {
+                    blob_url(&slug, &item.sha)
+                } else {
+                    file_contents_url(&slug, &sha, &item.path)
+                }
+            },
+            |_| not_ready(),
+        )
+    }
 
-        Ok(())
+    /// Download every file of `tree` below each target's path from its
+    /// `file_url`, keeping executable bits and symlinks. A 404 is reported
+    /// as `not_found`.
+    fn fetch_tree_files(
+        &self,
+        tree: &TreeResponse,
+        targets: &[ExtractTarget],
+        file_url: impl Fn(&TreeItem) -> String,
+        not_found: impl Fn(&str) -> SkillsError,
+    ) -> SkillsResult<ExtractStats> {
+        let mut stats = ExtractStats::default();
+        for target in targets {
+            let prefix = format!("{}/", target.path.trim_end_matches('/'));
+            let mut files = 0;
+            for item in tree.tree.iter().filter(|item| item.item_type == "blob") {
+                let Some(relative) = item.path.strip_prefix(&prefix) else {
+                    continue;
+                };
+                if !is_safe_relative_path(relative) {
+                    continue;
+                }
+                let url = file_url(item);
+                let mut body = Vec::new();
+                match self.get_with_accept(&url, "application/vnd.github.raw") {
+                    Ok(response) => {
+                        response.into_body().into_reader().read_to_end(&mut body)?;
+                    }
+                    Err(ureq::Error::StatusCode(404)) => return Err(not_found(&url)),
+                    Err(ureq::Error::StatusCode(status)) => {
+                        return Err(status_error(status, &url));
+                    }
+                    Err(e) => return Err(request_error(e)),
+                }
+                let dest = target.dest_dir.join(relative);
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                write_tree_file(&dest, item, &body)?;
+                stats.bytes += body.len() as u64;
+                files += 1;
+            }
+            stats.files.push(files);
+        }
+        Ok(stats)
     }
 
-    fn resolve_commit_sha(&self, github_url: &GitHubUrl) -> SkillsResult<Option<String>> {
-        let url = github_url.commits_url();
-        match config_github_request(self.agent.get(&url))
-            .header("Accept", "application/vnd.github+json")
-            .call()
-        {
+    fn resolve_commit(
+        &self,
+        github_url: &GitHubUrl,
+        until: Option<&str>,
+    ) -> SkillsResult<Option<UpstreamCommit>> {
+        let url = github_url.commits_url(until);
+        match self.get(&url) {
             Ok(response) => {
                 let json: serde_json::Value = response
                     .into_body()
                     .read_json()
                     .map_err(|e| SkillsError::NetworkError(e.to_string()))?;
-                let sha = json
-                    .get(0)
-                    .and_then(|x| x.get("sha"))
-                    .and_then(|x| x.as_str());
-                let Some(sha) = sha else {
-                    return Ok(None);
-                };
-                Ok(Some(sha.to_string()))
+                Ok(parse_latest_commit(&json))
             }
             Err(ureq::Error::StatusCode(status)) => match status {
                 404 | 422 => Ok(None),
@@ -301,33 +828,383 @@ impl GitHubProvider {
                     message: url,
                 }),
             },
-            Err(e) => Err(SkillsError::NetworkError(e.to_string())),
+            Err(e) => Err(request_error(e)),
+        }
+    }
+
+    /// Send an authenticated GET to the GitHub API, logging it at `-vv`.
+    fn get(&self, url: &str) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+        self.get_with_accept(url, "application/vnd.github+json")
+    }
+
+    /// Like [`GitHubProvider::get`], asking for the `accept` media type.
+    fn get_with_accept(
+        &self,
+        url: &str,
+        accept: &str,
+    ) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+        self.send(url, accept, None)
+    }
+
+    /// Like [`GitHubProvider::get`], returning the body as text. With a
+    /// cache, the request carries the cached `ETag` and a `304 Not Modified`
+    /// reuses the cached body.
+    fn get_cached(&self, url: &str) -> Result<String, ureq::Error> {
+        let Some(cache) = &self.cache else {
+            return self.get(url)?.into_body().read_to_string();
+        };
+        let cached = cache.get(url);
+        let etag = cached.as_ref().map(|(etag, _)| etag.as_str());
+        let response = self.send(url, "application/vnd.github+json", etag)?;
+        if response.status() == 304
+            && let Some((_, body)) = cached
+        {
+            trace!("not modified, using cached response: {}", url);
+            return Ok(body);
+        }
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.into_body().read_to_string()?;
+        if let Some(etag) = etag {
+            cache.put(url, &etag, &body);
         }
+        Ok(body)
     }
 
-    fn resolve(&self, spec: &GitHubUrlSpec) -> SkillsResult<Option<GitHubUrl>> {
-        for candidate in spec.candidates() {
-            let sha = self.resolve_commit_sha(&candidate)?;
-            if let Some(sha) = sha {
-                return Ok(Some(candidate.with_sha(sha)));
+    /// Send the request behind [`GitHubProvider::get_with_accept`], with an
+    /// `If-None-Match` header when `if_none_match` is given.
+    fn send(
+        &self,
+        url: &str,
+        accept: &str,
+        if_none_match: Option<&str>,
+    ) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+        let mut retries = 0;
+        loop {
+            let acquired = if self.wait_on_rate_limit {
+                self.limiter.acquire()
+            } else {
+                self.limiter.try_acquire()
+            };
+            if !acquired {
+                // Report an exhausted budget the way GitHub itself would.
+                trace!("rate limit budget exhausted: {}", url);
+                return Err(ureq::Error::StatusCode(429));
+            }
+            trace!("GET {}", url);
+            let mut request = config_github_request(self.agent.get(url))
+                .config()
+                .http_status_as_error(false)
+                .build()
+                .header("Accept", accept);
+            if let Some(etag) = if_none_match {
+                request = request.header("If-None-Match", etag);
+            }
+            let mut response = request
+                .call()
+                .inspect_err(|e| trace!("request failed: {}", e))?;
+            self.limiter.observe(response.headers());
+            let status = response.status().as_u16();
+            trace!("{} {}", status, url);
+            if status < 400 {
+                return Ok(response);
+            }
+            if status != 403 {
+                return Err(ureq::Error::StatusCode(status));
             }
+
+            // A 403 is either missing access or GitHub's secondary rate
+            // limit; only the latter is worth waiting for.
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            let Some(wait) = secondary_rate_limit_wait(response.headers(), &body) else {
+                return Err(ureq::Error::StatusCode(status));
+            };
+            if !self.wait_on_rate_limit
+                || retries == SECONDARY_RATE_LIMIT_RETRIES
+                || wait > MAX_RATE_LIMIT_WAIT_SECS
+            {
+                return Err(ureq::Error::Other(Box::new(
+                    SkillsError::SecondaryRateLimited { retry_after: wait },
+                )));
+            }
+            retries += 1;
+            eprintln!(
+                "{}",
+                style::warning_err(&format!(
+                    "GitHub secondary rate limit hit; waiting {}s before retrying",
+                    wait
+                ))
+            );
+            thread::sleep(Duration::from_secs(wait));
         }
-        Ok(None)
     }
 
-    fn list_directory_contents(&self, github_url: &GitHubUrl) -> SkillsResult<Vec<ContentItem>> {
-        let url = github_url.contents_url();
+    /// Details of the repository `slug`, looked up once per run and reused
+    /// from the cache while younger than the TTL. `revalidate` asks GitHub
+    /// again regardless.
+    fn repo_info(&self, slug: &str, revalidate: bool) -> SkillsResult<RepoInfo> {
+        if !revalidate && let Some(info) = self.repo_infos.lock().unwrap().get(slug) {
+            return Ok(info.clone());
+        }
+        let url = format!("https://api.github.com/repos/{}", slug);
+        let cached = self
+            .cache
+            .as_ref()
+            .filter(|_| !revalidate)
+            .and_then(|cache| cache.fresh(&url, self.repo_info_ttl));
+        let body = match cached {
+            Some(body) => {
+                trace!("using cached repository details: {}", url);
+                body
+            }
+            None => match self.get_cached(&url) {
+                Ok(body) => body,
+                Err(ureq::Error::StatusCode(status)) => return Err(status_error(status, &url)),
+                Err(e) => return Err(request_error(e)),
+            },
+        };
+        let info: RepoInfo =
+            serde_json::from_str(&body).map_err(|e| SkillsError::NetworkError(e.to_string()))?;
+        self.repo_infos
+            .lock()
+            .unwrap()
+            .insert(slug.to_string(), info.clone());
+        Ok(info)
+    }
+
+    /// Confirm the configured token can read the repository of `spec` before
+    /// resolving anything, so access problems surface with a clear message
+    /// instead of as an ambiguous 403/404 later. No-op without a token.
+    fn check_token_access(&self, slug: &str) -> SkillsResult<()> {
+        if github_token_from_env().is_none() {
+            return Ok(());
+        }
+        let denied = |reason: &str| SkillsError::TokenAccessDenied {
+            slug: slug.to_string(),
+            reason: reason.to_string(),
+        };
 
-        match config_github_request(self.agent.get(&url))
-            .header("Accept", "application/vnd.github+json")
-            .call()
+        let url = format!("https://api.github.com/repos/{}", slug);
+        let info: RepoInfo = match self.get(&url) {
+            Ok(response) => response
+                .into_body()
+                .read_json()
+                .map_err(|e| SkillsError::NetworkError(e.to_string()))?,
+            Err(ureq::Error::StatusCode(401)) => {
+                return Err(denied(
+                    "GitHub rejected the token (HTTP 401); it may be expired or revoked.",
+                ));
+            }
+            Err(ureq::Error::StatusCode(403 | 404)) => {
+                return Err(denied(
+                    "The repository does not exist, or it is private and the token lacks access to it.",
+                ));
+            }
+            Err(ureq::Error::StatusCode(status)) => return Err(status_error(status, &url)),
+            Err(e) => return Err(request_error(e)),
+        };
+
+        debug!(
+            "Token can see {} ({})",
+            slug,
+            if info.private { "private" } else { "public" }
+        );
+        if info
+            .permissions
+            .is_some_and(|permissions| !permissions.pull)
         {
-            Ok(response) => {
-                let items: Vec<ContentItem> = response
+            return Err(denied(
+                "The token lacks read (pull) permission on the repository.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tell apart the reasons a 404 while resolving `source_url` can have by
+    /// asking for the repository `slug` itself. GitHub answers 404 rather
+    /// than 403 for a private repository, so without a token a missing
+    /// repository becomes [`SkillsError::RepositoryNotFound`], and with one
+    /// [`SkillsError::TokenAccessDenied`]. When the repository is there, the
+    /// ref or path is what is missing. Other errors, and 404s the lookup
+    /// cannot explain, are returned unchanged.
+    fn explain_not_found(&self, slug: &str, source_url: &str, err: SkillsError) -> SkillsError {
+        if !matches!(
+            err,
+            SkillsError::NotFound { .. } | SkillsError::PathNotFound(..)
+        ) {
+            return err;
+        }
+        match self.repo_info(slug, false) {
+            Ok(_) => match err {
+                SkillsError::NotFound { .. } => {
+                    SkillsError::PathNotFound(vec![source_url.to_string()])
+                }
+                err => err,
+            },
+            Err(SkillsError::NotFound { .. }) if github_token_from_env().is_some() => {
+                SkillsError::TokenAccessDenied {
+                    slug: slug.to_string(),
+                    reason: "The repository does not exist, or it is private and the token lacks access to it.".to_string(),
+                }
+            }
+            Err(SkillsError::NotFound { .. }) => SkillsError::RepositoryNotFound {
+                slug: slug.to_string(),
+            },
+            Err(_) => err,
+        }
+    }
+
+    /// Whether the ref of `github_url` exists as a `kind` ref.
+    fn ref_exists(&self, github_url: &GitHubUrl, kind: RefKind) -> SkillsResult<bool> {
+        let url = github_url.git_ref_url(kind);
+        match self.get(&url) {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::StatusCode(404)) => Ok(false),
+            Err(ureq::Error::StatusCode(status)) => Err(status_error(status, &url)),
+            Err(e) => Err(request_error(e)),
+        }
+    }
+
+    /// The commit the `kind` ref `name` of `slug` points at, peeling
+    /// annotated tags. Unlike the commits API, which takes whichever of a
+    /// branch and a tag of the same name it finds, this only looks in the
+    /// namespace of `kind`.
+    fn ref_head(&self, slug: &str, name: &str, kind: RefKind) -> SkillsResult<String> {
+        let github_url = GitHubUrl {
+            slug: slug.to_string(),
+            r#ref: name.to_string(),
+            sha: name.to_string(),
+            path: String::new(),
+        };
+        let mut url = github_url.git_ref_url(kind);
+        // Tags can point at tags; give up on chains longer than any in practice.
+        for _ in 0..8 {
+            let json: serde_json::Value = match self.get(&url) {
+                Ok(response) => response
                     .into_body()
                     .read_json()
-                    .map_err(|e| SkillsError::NetworkError(e.to_string()))?;
-                Ok(items)
+                    .map_err(|e| SkillsError::NetworkError(e.to_string()))?,
+                Err(ureq::Error::StatusCode(404)) => {
+                    return Err(SkillsError::RefNotFound {
+                        slug: slug.to_string(),
+                        kind: kind.label().to_string(),
+                        name: name.to_string(),
+                    });
+                }
+                Err(ureq::Error::StatusCode(status)) => return Err(status_error(status, &url)),
+                Err(e) => return Err(request_error(e)),
+            };
+            match git_object(&json) {
+                Some(("tag", sha)) => url = tag_object_url(slug, sha),
+                Some((_, sha)) => return Ok(sha.to_string()),
+                None => break,
+            }
+        }
+        Err(SkillsError::NetworkError(format!(
+            "Unexpected response resolving {} '{}': {}",
+            kind.label(),
+            name,
+            url
+        )))
+    }
+
+    /// The commit `ref_name` points at when `ref_kind` says which kind of
+    /// ref it is; `None` leaves resolution to the commits API.
+    fn pinned_head(
+        &self,
+        slug: &str,
+        ref_name: Option<&str>,
+        ref_kind: Option<RefKind>,
+    ) -> SkillsResult<Option<String>> {
+        match (ref_name, ref_kind) {
+            (Some(name), Some(kind)) => self.ref_head(slug, name, kind).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Candidate splits of `spec`, with refs of the `prefer` kind moved to the
+    /// front. Without a preference the greedy shortest-ref-first order is kept.
+    /// A `known_ref` recorded at install time decides the split outright and
+    /// goes first.
+    fn ordered_candidates(
+        &self,
+        spec: &GitHubUrlSpec,
+        prefer: Option<RefKind>,
+        known_ref: Option<&str>,
+    ) -> SkillsResult<Vec<GitHubUrl>> {
+        let mut candidates = spec.candidates();
+        if let Some(known_ref) = known_ref
+            && let Some(pos) = candidates.iter().position(|c| c.r#ref == known_ref)
+        {
+            let known = candidates.remove(pos);
+            candidates.insert(0, known);
+            return Ok(candidates);
+        }
+        let Some(kind) = prefer else {
+            return Ok(candidates);
+        };
+
+        let mut preferred = Vec::new();
+        let mut others = Vec::new();
+        for candidate in candidates {
+            if self.ref_exists(&candidate, kind)? {
+                preferred.push(candidate);
+            } else {
+                others.push(candidate);
+            }
+        }
+        preferred.extend(others);
+        Ok(preferred)
+    }
+
+    /// Resolve the first candidate split of `spec` that names a commit. With
+    /// a `head` commit for `known_ref`, only the split using `known_ref` is
+    /// tried, and its commits are looked up from `head`.
+    fn resolve(
+        &self,
+        spec: &GitHubUrlSpec,
+        until: Option<&str>,
+        prefer: Option<RefKind>,
+        known_ref: Option<&str>,
+        head: Option<&str>,
+    ) -> SkillsResult<Option<(GitHubUrl, UpstreamCommit)>> {
+        for candidate in self.ordered_candidates(spec, prefer, known_ref)? {
+            if head.is_some() && known_ref != Some(candidate.r#ref.as_str()) {
+                continue;
+            }
+            debug!(
+                "Trying ref '{}' with path '{}'",
+                candidate.r#ref, candidate.path
+            );
+            if let Some(commit) = self.resolve_commit(&candidate.pinned_to(head), until)? {
+                debug!("Resolved '{}' to commit {}", candidate.r#ref, commit.sha);
+                return Ok(Some((candidate.with_sha(commit.sha.clone()), commit)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn fetch_tree(&self, slug: &str, sha: &str) -> SkillsResult<TreeResponse> {
+        let url = trees_url(slug, sha);
+        match self.get(&url) {
+            Ok(response) => response
+                .into_body()
+                .read_json()
+                .map_err(|e| SkillsError::NetworkError(e.to_string())),
+            Err(ureq::Error::StatusCode(status)) => Err(status_error(status, &url)),
+            Err(e) => Err(request_error(e)),
+        }
+    }
+
+    fn list_directory_contents(&self, github_url: &GitHubUrl) -> SkillsResult<Vec<ContentItem>> {
+        let url = github_url.contents_url();
+
+        match self.get_cached(&url) {
+            Ok(body) => {
+                serde_json::from_str(&body).map_err(|e| SkillsError::NetworkError(e.to_string()))
             }
             Err(ureq::Error::StatusCode(status)) => match status {
                 404 => Err(SkillsError::PathNotFound(vec![github_url.path.clone()])),
@@ -338,16 +1215,133 @@ impl GitHubProvider {
                     message: url,
                 }),
             },
-            Err(e) => Err(SkillsError::NetworkError(e.to_string())),
+            Err(e) => Err(request_error(e)),
+        }
+    }
+
+    fn fetch_release(&self, release: &ReleaseSpec) -> SkillsResult<Release> {
+        let url = release.release_api_url();
+        match self.get(&url) {
+            Ok(response) => response
+                .into_body()
+                .read_json()
+                .map_err(|e| SkillsError::NetworkError(e.to_string())),
+            Err(ureq::Error::StatusCode(404)) => {
+                Err(SkillsError::PathNotFound(vec![release.url()]))
+            }
+            Err(ureq::Error::StatusCode(status)) => Err(status_error(status, &url)),
+            Err(e) => Err(request_error(e)),
+        }
+    }
+
+    /// Plan installing a release asset as a single skill named after the
+    /// repository. The archive URL names the exact tag and asset.
+    fn resolve_release_plan(
+        &self,
+        source_url: &str,
+        release: &ReleaseSpec,
+        options: &ResolveOptions,
+    ) -> SkillsResult<InstallPlan> {
+        if options.check_token {
+            self.check_token_access(&release.slug)?;
+        }
+        let info = self.fetch_release(release)?;
+        let asset = info.pick_asset(release.asset.as_deref())?;
+        let Some(commit) =
+            self.resolve_commit(&release_commit_url(&release.slug, &info.tag_name), None)?
+        else {
+            return Err(SkillsError::PathNotFound(vec![source_url.to_string()]));
+        };
+        debug!(
+            "Release '{}' asset '{}' at commit {}",
+            info.tag_name, asset.name, commit.sha
+        );
+
+        let archive = ReleaseSpec {
+            slug: release.slug.clone(),
+            tag: Some(info.tag_name.clone()),
+            asset: Some(asset.name.clone()),
+        };
+        Ok(InstallPlan {
+            archive_url: archive.url_for_tag(&info.tag_name),
+            is_batch: false,
+            non_skill_dirs: Vec::new(),
+            skills: vec![ResolvedSkill {
+                name: release.repo_name().to_string(),
+                source_url: source_url.to_string(),
+                collection_url: None,
+                slug: release.slug.clone(),
+                sha: commit.sha,
+                path: String::new(),
+                default_branch: None,
+                ref_name: Some(info.tag_name.clone()),
+            }],
+        })
+    }
+
+    /// Download the asset a release URL names (or the first archive of the
+    /// release) through the API, so private repositories work with a token.
+    fn download_release_asset(
+        &self,
+        release: &ReleaseSpec,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats> {
+        let info = self.fetch_release(release)?;
+        let asset = info.pick_asset(release.asset.as_deref())?;
+        let response = match self.get_with_accept(&asset.url, "application/octet-stream") {
+            Ok(response) => response,
+            Err(ureq::Error::StatusCode(status)) => return Err(status_error(status, &asset.url)),
+            Err(e) => return Err(request_error(e)),
+        };
+        extract_response(&asset.url, response, targets)
+    }
+
+    /// Describe the directory at `github_url` from its listing `contents`.
+    fn listing_for(
+        &self,
+        github_url: &GitHubUrl,
+        name: &str,
+        contents: &[ContentItem],
+        manifest_names: &[String],
+    ) -> SkillsResult<SkillListing> {
+        let manifest = contents
+            .iter()
+            .find(|item| item.item_type == "file" && is_manifest_name(&item.name, manifest_names));
+        let content = match manifest {
+            Some(item) => Some(self.fetch_manifest(github_url, &item.name)?),
+            None => None,
+        };
+        Ok(SkillListing {
+            name: name.to_string(),
+            has_manifest: manifest.is_some(),
+            description: content.as_deref().and_then(manifest_description),
+            manifest_name: content.as_deref().and_then(manifest_name),
+        })
+    }
+
+    fn fetch_manifest(&self, github_url: &GitHubUrl, file_name: &str) -> SkillsResult<String> {
+        let path = format!("{}/{}", github_url.path, file_name);
+        let url = file_contents_url(&github_url.slug, &github_url.sha, &path);
+        match self.get_with_accept(&url, "application/vnd.github.raw") {
+            Ok(response) => response
+                .into_body()
+                .read_to_string()
+                .map_err(|e| SkillsError::NetworkError(e.to_string())),
+            Err(ureq::Error::StatusCode(status)) => Err(status_error(status, &url)),
+            Err(e) => Err(request_error(e)),
         }
     }
 
-    fn detect_skill_type(&self, github_url: &GitHubUrl) -> SkillsResult<SkillDetectionResult> {
+    fn detect_skill_type(
+        &self,
+        github_url: &GitHubUrl,
+        manifest_names: &[String],
+    ) -> SkillsResult<SkillDetectionResult> {
         let contents = self.list_directory_contents(github_url)?;
 
         let has_skill_manifest = contents
             .iter()
-            .any(|item| item.item_type == "file" && item.name.eq_ignore_ascii_case("SKILL.md"));
+            .any(|item| item.item_type == "file" && is_manifest_name(&item.name, manifest_names));
 
         if has_skill_manifest {
             return Ok(SkillDetectionResult::Single);
@@ -358,31 +1352,41 @@ impl GitHubProvider {
             .filter(|item| item.item_type == "dir")
             .collect();
 
-        let mut skill_dirs = Vec::new();
-
-        for subdir in subdirs {
+        // Each listing is its own request; the rate limiter is shared, so
+        // running them side by side still respects the budget.
+        let checks = parallel_map(&subdirs, self.api_jobs, |subdir| -> SkillsResult<bool> {
             let child_url = GitHubUrl {
                 slug: github_url.slug.clone(),
                 r#ref: github_url.r#ref.clone(),
                 sha: github_url.sha.clone(),
                 path: format!("{}/{}", github_url.path, subdir.name),
             };
-
             let child_contents = self.list_directory_contents(&child_url)?;
-            let has_skill = child_contents
-                .iter()
-                .any(|item| item.item_type == "file" && item.name.eq_ignore_ascii_case("SKILL.md"));
+            Ok(child_contents.iter().any(|item| {
+                item.item_type == "file" && is_manifest_name(&item.name, manifest_names)
+            }))
+        });
 
-            if has_skill {
+        let mut skill_dirs = Vec::new();
+        let mut other_dirs = Vec::new();
+        for (subdir, has_skill) in subdirs.iter().zip(checks) {
+            if has_skill? {
                 skill_dirs.push(subdir.name.clone());
+            } else {
+                other_dirs.push(subdir.name.clone());
             }
         }
+        skill_dirs.sort();
+        other_dirs.sort();
 
         if skill_dirs.is_empty() {
             return Err(SkillsError::NoSkillsFound(github_url.path.clone()));
         }
 
-        Ok(SkillDetectionResult::Batch(skill_dirs))
+        Ok(SkillDetectionResult::Batch {
+            skills: skill_dirs,
+            others: other_dirs,
+        })
     }
 }
 
@@ -394,18 +1398,91 @@ impl SkillProvider for GitHubProvider {
     /// Parse a GitHub tree URL, resolve refs to SHAs via the commits API,
     /// detect single vs batch skill layout via the contents API, and return
     /// an [`InstallPlan`] with a tarball URL for the resolved SHA.
-    fn resolve_install_plan(&self, url: &str) -> SkillsResult<InstallPlan> {
-        let source_url = url.trim_end_matches('/');
-        let spec = GitHubUrlSpec::parse(source_url)?;
-
-        let Some(resolved) = self.resolve(&spec)? else {
-            return Err(SkillsError::PathNotFound(vec![source_url.to_string()]));
+    /// When `options.until` is set, refs resolve to the last commit at or
+    /// before that timestamp; `options.prefer_ref` biases ambiguous splits
+    /// towards tags or branches.
+    fn resolve_install_plan(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<InstallPlan> {
+        let mut source_url = url.trim_end_matches('/');
+        if let Some(release) = ReleaseSpec::parse(source_url) {
+            return self.resolve_release_plan(source_url, &release, options);
+        }
+        let explicit = match (&options.ref_name, &options.path) {
+            (Some(r#ref), Some(path)) => {
+                let spec = GitHubUrlSpec::explicit(source_url, r#ref, path)?;
+                let candidate = GitHubUrl {
+                    slug: spec.slug.clone(),
+                    r#ref: r#ref.clone(),
+                    sha: r#ref.clone(),
+                    path: path.trim_matches('/').to_string(),
+                };
+                Some((spec, candidate))
+            }
+            _ => None,
         };
-
-        let plan = match self.detect_skill_type(&resolved)? {
+        let explicit_url = explicit.as_ref().map(|(spec, _)| spec.url());
+        if let Some(url) = &explicit_url {
+            source_url = url;
+        }
+        let spec = match &explicit {
+            Some((spec, _)) => spec.clone(),
+            None => GitHubUrlSpec::parse(source_url)?,
+        };
+        let until = options.until.as_deref();
+        if options.check_token {
+            self.check_token_access(&spec.slug)?;
+        }
+        let head = self.pinned_head(&spec.slug, options.ref_name.as_deref(), options.ref_kind)?;
+
+        let resolved = match explicit {
+            // The ref and path were given, so there is nothing to guess.
+            Some((_, candidate)) => {
+                debug!(
+                    "Using ref '{}' with path '{}'",
+                    candidate.r#ref, candidate.path
+                );
+                self.resolve_commit(&candidate.pinned_to(head.as_deref()), until)
+                    .map_err(|e| self.explain_not_found(&spec.slug, source_url, e))?
+                    .map(|commit| candidate.with_sha(commit.sha))
+            }
+            None => self
+                .resolve(
+                    &spec,
+                    until,
+                    options.prefer_ref,
+                    options.ref_name.as_deref(),
+                    head.as_deref(),
+                )
+                .map_err(|e| self.explain_not_found(&spec.slug, source_url, e))?
+                .map(|(resolved, _)| resolved),
+        };
+        let Some(resolved) = resolved else {
+            return Err(self.explain_not_found(
+                &spec.slug,
+                source_url,
+                SkillsError::PathNotFound(vec![source_url.to_string()]),
+            ));
+        };
+        let default_branch = self
+            .repo_info(&spec.slug, false)
+            .ok()
+            .map(|info| info.default_branch)
+            .filter(|branch| *branch == resolved.r#ref);
+
+        let detected = if options.no_subdir {
+            SkillDetectionResult::Single
+        } else {
+            self.detect_skill_type(&resolved, &options.manifest_names)
+                .map_err(|e| self.explain_not_found(&spec.slug, source_url, e))?
+        };
+        let plan = match detected {
             SkillDetectionResult::Single => InstallPlan {
-                archive_url: resolved.tarball_url(),
+                archive_url: resolved.archive_url(options.archive_format),
                 is_batch: false,
+                non_skill_dirs: Vec::new(),
                 skills: vec![ResolvedSkill {
                     name: spec.directory_name().to_string(),
                     source_url: source_url.to_string(),
@@ -413,17 +1490,22 @@ impl SkillProvider for GitHubProvider {
                     slug: resolved.slug,
                     sha: resolved.sha,
                     path: resolved.path,
+                    default_branch,
+                    ref_name: Some(resolved.r#ref),
                 }],
             },
-            SkillDetectionResult::Batch(subdirs) => {
+            SkillDetectionResult::Batch {
+                skills: subdirs,
+                others,
+            } => {
                 let mut skills = Vec::new();
                 for subdir in subdirs {
                     let child_source_url = format!("{}/{}", source_url, subdir);
                     let child_candidate = resolved.child(&subdir);
-                    let Some(child_sha) = self.resolve_commit_sha(&child_candidate)? else {
+                    let Some(child_commit) = self.resolve_commit(&child_candidate, until)? else {
                         return Err(SkillsError::PathNotFound(vec![child_source_url]));
                     };
-                    let child_resolved = child_candidate.with_sha(child_sha);
+                    let child_resolved = child_candidate.with_sha(child_commit.sha);
 
                     skills.push(ResolvedSkill {
                         name: subdir,
@@ -432,12 +1514,15 @@ impl SkillProvider for GitHubProvider {
                         slug: child_resolved.slug,
                         sha: child_resolved.sha,
                         path: child_resolved.path,
+                        default_branch: default_branch.clone(),
+                        ref_name: Some(resolved.r#ref.clone()),
                     });
                 }
 
                 InstallPlan {
-                    archive_url: resolved.tarball_url(),
+                    archive_url: resolved.archive_url(options.archive_format),
                     is_batch: true,
+                    non_skill_dirs: others,
                     skills,
                 }
             }
@@ -446,17 +1531,182 @@ impl SkillProvider for GitHubProvider {
         Ok(plan)
     }
 
-    fn fetch_and_extract(&self, archive_url: &str, targets: &[ExtractTarget]) -> SkillsResult<()> {
+    fn explain_resolution(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<ResolveReport> {
+        let spec = GitHubUrlSpec::parse(url.trim_end_matches('/'))?;
+        let candidates =
+            self.ordered_candidates(&spec, options.prefer_ref, options.ref_name.as_deref())?;
+
+        let mut report = ResolveReport::default();
+        let mut winner = None;
+        for candidate in candidates {
+            let outcome = if winner.is_some() {
+                CandidateOutcome::Skipped
+            } else {
+                match self.resolve_commit(&candidate, options.until.as_deref()) {
+                    Ok(Some(commit)) => {
+                        winner = Some(candidate.clone().with_sha(commit.sha.clone()));
+                        CandidateOutcome::Resolved(commit.sha)
+                    }
+                    Ok(None) => CandidateOutcome::NotFound,
+                    // Keep the headline and its first detail (e.g. the reason).
+                    Err(e) => CandidateOutcome::Failed(
+                        e.to_string()
+                            .lines()
+                            .filter(|line| !line.is_empty())
+                            .take(2)
+                            .collect::<Vec<_>>()
+                            .join(" - "),
+                    ),
+                }
+            };
+            report.candidates.push(CandidateAttempt {
+                r#ref: candidate.r#ref,
+                path: candidate.path,
+                outcome,
+            });
+        }
+
+        if let Some(resolved) = winner {
+            let collection = match self.detect_skill_type(&resolved, &options.manifest_names)? {
+                SkillDetectionResult::Single => Vec::new(),
+                SkillDetectionResult::Batch { skills, .. } => skills,
+            };
+            report.resolved = Some(ResolvedSource {
+                slug: resolved.slug,
+                r#ref: resolved.r#ref,
+                sha: resolved.sha,
+                path: resolved.path,
+                collection,
+            });
+        }
+        Ok(report)
+    }
+
+    fn browse(&self, url: &str, manifest_names: &[String]) -> SkillsResult<Vec<SkillListing>> {
+        let source_url = url.trim_end_matches('/');
+        let spec = GitHubUrlSpec::parse(source_url)?;
+        let Some((resolved, _)) = self.resolve(&spec, None, None, None, None)? else {
+            return Err(SkillsError::PathNotFound(vec![source_url.to_string()]));
+        };
+
+        let contents = self.list_directory_contents(&resolved)?;
+        let listing =
+            self.listing_for(&resolved, spec.directory_name(), &contents, manifest_names)?;
+        if listing.has_manifest {
+            return Ok(vec![listing]);
+        }
+
+        let mut listings = Vec::new();
+        for subdir in contents.iter().filter(|item| item.item_type == "dir") {
+            let child = resolved.child(&subdir.name);
+            let child_contents = self.list_directory_contents(&child)?;
+            listings.push(self.listing_for(
+                &child,
+                &subdir.name,
+                &child_contents,
+                manifest_names,
+            )?);
+        }
+        if listings.is_empty() {
+            return Err(SkillsError::NoSkillsFound(resolved.path));
+        }
+        Ok(listings)
+    }
+
+    fn fetch_and_extract(
+        &self,
+        archive_url: &str,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats> {
+        if let Some(release) = ReleaseSpec::parse(archive_url) {
+            return self.download_release_asset(&release, targets);
+        }
         self.download_and_extract(archive_url, targets)
     }
 
+    /// Count files via the Trees API at each skill's SHA. Trees too large for
+    /// GitHub to list in one response are reported as unknown.
+    fn expected_file_counts(&self, skills: &[ResolvedSkill]) -> SkillsResult<Option<Vec<usize>>> {
+        // Release assets are not part of the repository tree.
+        if skills
+            .iter()
+            .any(|skill| ReleaseSpec::parse(&skill.source_url).is_some())
+        {
+            return Ok(None);
+        }
+        let mut trees: HashMap<(&str, &str), TreeResponse> = HashMap::new();
+        let mut counts = Vec::with_capacity(skills.len());
+
+        for skill in skills {
+            let key = (skill.slug.as_str(), skill.sha.as_str());
+            let tree = match trees.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.fetch_tree(&skill.slug, &skill.sha)?),
+            };
+            if tree.truncated {
+                return Ok(None);
+            }
+            counts.push(count_blobs_under(&tree.tree, &skill.path));
+        }
+
+        Ok(Some(counts))
+    }
+
     fn archive_url_for_entry(&self, entry: &SkillEntry) -> String {
-        format!(
-            "https://api.github.com/repos/{}/tarball/{}",
-            entry.slug,
-            urlencoding::encode(&entry.sha)
+        if let Some(release) = ReleaseSpec::parse(&entry.source_url) {
+            return match &entry.ref_name {
+                Some(tag) => release.url_for_tag(tag),
+                None => entry.source_url.clone(),
+            };
+        }
+        archive_url(
+            &entry.slug,
+            &entry.sha,
+            entry.archive_format.unwrap_or_default(),
         )
     }
+
+    fn source_url_with_ref(&self, entry: &SkillEntry, new_ref: &str) -> SkillsResult<String> {
+        if let Some(release) = ReleaseSpec::parse(&entry.source_url) {
+            return Ok(release.url_for_tag(new_ref));
+        }
+        let spec = GitHubUrlSpec::parse(&entry.source_url)?;
+        spec.ref_for_path(&entry.path)
+            .and_then(|old_ref| spec.replace_ref(&old_ref, new_ref))
+            .ok_or_else(|| SkillsError::InvalidUrl(entry.source_url.clone()))
+    }
+
+    fn latest_commit(&self, entry: &SkillEntry) -> SkillsResult<Option<UpstreamCommit>> {
+        if let Some(release) = ReleaseSpec::parse(&entry.source_url) {
+            let release = self.fetch_release(&release)?;
+            return self.resolve_commit(&release_commit_url(&entry.slug, &release.tag_name), None);
+        }
+        let spec = GitHubUrlSpec::parse(&entry.source_url)?;
+        let head = self.pinned_head(&spec.slug, entry.ref_name.as_deref(), entry.ref_kind)?;
+        Ok(self
+            .resolve(
+                &spec,
+                entry.until.as_deref(),
+                entry.prefer_ref,
+                entry.ref_name.as_deref(),
+                head.as_deref(),
+            )?
+            .map(|(_, commit)| commit))
+    }
+
+    fn relocate_default_branch(&self, url: &str, old_branch: &str) -> SkillsResult<Option<String>> {
+        let spec = GitHubUrlSpec::parse(url)?;
+        // The branch was just found missing, so a cached answer is suspect.
+        let info = self.repo_info(&spec.slug, true)?;
+        if info.default_branch == old_branch {
+            return Ok(None);
+        }
+        Ok(spec.replace_ref(old_branch, &info.default_branch))
+    }
 }
 
 #[cfg(test)]
@@ -518,6 +1768,60 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn test_replace_ref() {
+        let spec = GitHubUrlSpec::parse("https://github.com/owner/repo/tree/release/v1/skills/foo")
+            .unwrap();
+
+        assert_eq!(
+            spec.replace_ref("release/v1", "main").as_deref(),
+            Some("https://github.com/owner/repo/tree/main/skills/foo")
+        );
+        assert_eq!(spec.replace_ref("master", "main"), None);
+        assert_eq!(spec.replace_ref("release/v1/skills/foo", "main"), None);
+    }
+
+    #[test]
+    fn test_parse_latest_commit_uses_full_sha() {
+        let full = "0123456789abcdef0123456789abcdef01234567";
+        let json = serde_json::json!([{
+            "sha": full,
+            "commit": { "committer": { "date": "2024-06-01T12:00:00Z" } }
+        }]);
+
+        let commit = parse_latest_commit(&json).unwrap();
+        assert_eq!(commit.sha, full);
+        assert_eq!(commit.date.as_deref(), Some("2024-06-01T12:00:00Z"));
+
+        let resolved = GitHubUrlSpec::parse("https://github.com/owner/repo/tree/0123456/skills")
+            .unwrap()
+            .candidates()
+            .remove(0)
+            .with_sha(commit.sha);
+        assert_eq!(resolved.r#ref, "0123456");
+        assert_eq!(resolved.sha, full);
+        assert_eq!(resolved.child("demo").r#ref, full);
+
+        assert!(parse_latest_commit(&serde_json::json!([])).is_none());
+    }
+
+    #[test]
+    fn test_ref_for_path() {
+        let spec = GitHubUrlSpec::parse("https://github.com/owner/repo/tree/release/v1/skills/foo")
+            .unwrap();
+
+        assert_eq!(
+            spec.ref_for_path("skills/foo").as_deref(),
+            Some("release/v1")
+        );
+        assert_eq!(
+            spec.ref_for_path("foo").as_deref(),
+            Some("release/v1/skills")
+        );
+        assert_eq!(spec.ref_for_path("other/foo"), None);
+        assert_eq!(spec.ref_for_path("release/v1/skills/foo"), None);
+    }
+
     #[test]
     fn test_parse_invalid_url_missing_tree() {
         let url = "https://github.com/owner/repo";
@@ -545,6 +1849,27 @@ mod tests {
         assert!(matches!(result.unwrap_err(), SkillsError::InvalidUrl(_)));
     }
 
+    #[test]
+    fn test_explicit_spec() {
+        let spec = GitHubUrlSpec::explicit(
+            "https://github.com/owner/repo/tree/release/v1/skill",
+            "release/v1",
+            "skill",
+        )
+        .unwrap();
+        assert_eq!(spec.slug, "owner/repo");
+        assert_eq!(
+            spec.url(),
+            "https://github.com/owner/repo/tree/release/v1/skill"
+        );
+        assert_eq!(spec.ref_for_path("skill").as_deref(), Some("release/v1"));
+
+        let spec =
+            GitHubUrlSpec::explicit("https://github.com/owner/repo.git", "main", "/a/b/").unwrap();
+        assert_eq!(spec.url(), "https://github.com/owner/repo/tree/main/a/b");
+        assert!(GitHubUrlSpec::explicit("https://gitlab.com/owner/repo", "main", "a").is_err());
+    }
+
     #[test]
     fn test_directory_name() {
         let github_url = GitHubUrlSpec {
@@ -582,6 +1907,106 @@ mod tests {
             github_url.tarball_url(),
             "https://api.github.com/repos/anthropics/skills/tarball/main"
         );
+        assert_eq!(
+            github_url.archive_url(ArchiveFormat::Zip),
+            "https://api.github.com/repos/anthropics/skills/zipball/main"
+        );
+    }
+
+    #[test]
+    fn test_repo_info_permissions() {
+        let info: RepoInfo = serde_json::from_str(
+            r#"{"default_branch": "main", "private": true, "permissions": {"pull": false}}"#,
+        )
+        .unwrap();
+        assert!(info.private);
+        assert!(info.permissions.is_some_and(|p| !p.pull));
+
+        let info: RepoInfo = serde_json::from_str(r#"{"default_branch": "main"}"#).unwrap();
+        assert!(!info.private);
+        assert!(info.permissions.is_none());
+    }
+
+    #[test]
+    fn test_parse_archive_url() {
+        let url = archive_url("owner/repo", "release/v1", ArchiveFormat::Tar);
+        assert_eq!(
+            parse_archive_url(&url),
+            Some(("owner/repo".to_string(), "release/v1".to_string()))
+        );
+        assert_eq!(
+            parse_archive_url("https://example.com/archive.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_release_url() {
+        let latest = ReleaseSpec::parse("https://github.com/owner/demo/releases/latest").unwrap();
+        assert_eq!(latest.slug, "owner/demo");
+        assert_eq!(latest.tag, None);
+        assert_eq!(latest.asset, None);
+        assert_eq!(latest.repo_name(), "demo");
+        assert_eq!(
+            latest.release_api_url(),
+            "https://api.github.com/repos/owner/demo/releases/latest"
+        );
+
+        let tag = ReleaseSpec::parse("https://github.com/owner/demo/releases/tag/v1.0/").unwrap();
+        assert_eq!(tag.tag.as_deref(), Some("v1.0"));
+        assert_eq!(
+            tag.url_for_tag("v1.1"),
+            "https://github.com/owner/demo/releases/tag/v1.1"
+        );
+
+        let download =
+            ReleaseSpec::parse("https://github.com/owner/demo/releases/download/v1.0/demo.tar.gz")
+                .unwrap();
+        assert_eq!(download.tag.as_deref(), Some("v1.0"));
+        assert_eq!(download.asset.as_deref(), Some("demo.tar.gz"));
+        assert_eq!(
+            download.url_for_tag("v2.0"),
+            "https://github.com/owner/demo/releases/download/v2.0/demo.tar.gz"
+        );
+
+        assert_eq!(
+            ReleaseSpec::parse("https://github.com/owner/demo/releases"),
+            None
+        );
+        assert_eq!(
+            ReleaseSpec::parse("https://github.com/owner/demo/releases/tag"),
+            None
+        );
+        assert_eq!(
+            ReleaseSpec::parse("https://github.com/owner/demo/tree/main/skills"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pick_release_asset() {
+        let release = Release {
+            tag_name: "v1.0".to_string(),
+            assets: vec![
+                ReleaseAsset {
+                    name: "checksums.txt".to_string(),
+                    url: "https://api.github.com/assets/1".to_string(),
+                },
+                ReleaseAsset {
+                    name: "demo.zip".to_string(),
+                    url: "https://api.github.com/assets/2".to_string(),
+                },
+            ],
+        };
+        assert_eq!(release.pick_asset(None).unwrap().name, "demo.zip");
+        assert_eq!(
+            release.pick_asset(Some("checksums.txt")).unwrap().name,
+            "checksums.txt"
+        );
+        assert!(matches!(
+            release.pick_asset(Some("missing.zip")),
+            Err(SkillsError::NoReleaseAsset { .. })
+        ));
     }
 
     #[test]
@@ -594,11 +2019,144 @@ mod tests {
         };
 
         assert_eq!(
-            github_url.commits_url(),
+            github_url.commits_url(None),
             "https://api.github.com/repos/owner/repo/commits?sha=feature%2Ffoo&path=skills%2Fmy%20skill&per_page=1"
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_write_tree_file_keeps_modes_and_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = env::temp_dir().join("skills_test_write_tree_file");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let tree: TreeResponse = serde_json::from_str(
+            r#"{"tree": [
+                {"path": "demo/SKILL.md", "type": "blob", "sha": "a", "mode": "100644"},
+                {"path": "demo/run.sh", "type": "blob", "sha": "b", "mode": "100755"},
+                {"path": "demo/latest.sh", "type": "blob", "sha": "c", "mode": "120000"}
+            ]}"#,
+        )
+        .unwrap();
+        let [manifest, script, link] = &tree.tree[..] else {
+            panic!("expected three items");
+        };
+
+        write_tree_file(&temp_dir.join("SKILL.md"), manifest, b"# Demo").unwrap();
+        write_tree_file(&temp_dir.join("run.sh"), script, b"echo hi").unwrap();
+        write_tree_file(&temp_dir.join("latest.sh"), link, b"run.sh").unwrap();
+
+        let mode = |name: &str| {
+            fs::metadata(temp_dir.join(name))
+                .unwrap()
+                .permissions()
+                .mode()
+        };
+        assert_eq!(mode("run.sh") & 0o111, 0o111);
+        assert_eq!(mode("SKILL.md") & 0o111, 0);
+        assert_eq!(
+            fs::read_link(temp_dir.join("latest.sh")).unwrap(),
+            Path::new("run.sh")
+        );
+        assert_eq!(fs::read(temp_dir.join("latest.sh")).unwrap(), b"echo hi");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_count_blobs_under() {
+        let item = |path: &str, item_type: &str| TreeItem {
+            path: path.to_string(),
+            item_type: item_type.to_string(),
+            sha: String::new(),
+            mode: String::new(),
+        };
+        let tree = vec![
+            item("skills", "tree"),
+            item("skills/demo", "tree"),
+            item("skills/demo/SKILL.md", "blob"),
+            item("skills/demo/scripts", "tree"),
+            item("skills/demo/scripts/run.sh", "blob"),
+            item("skills/demo-two/SKILL.md", "blob"),
+            item("skills/demo/vendor", "commit"),
+        ];
+
+        assert_eq!(count_blobs_under(&tree, "skills/demo"), 2);
+        assert_eq!(count_blobs_under(&tree, "skills"), 3);
+        assert_eq!(
+            trees_url("owner/repo", "abc123"),
+            "https://api.github.com/repos/owner/repo/git/trees/abc123?recursive=1"
+        );
+        assert_eq!(
+            blob_url("owner/repo", "def456"),
+            "https://api.github.com/repos/owner/repo/git/blobs/def456"
+        );
+    }
+
+    #[test]
+    fn test_git_ref_url() {
+        let github_url = GitHubUrl {
+            slug: "owner/repo".to_string(),
+            r#ref: "release/v1.0".to_string(),
+            sha: "release/v1.0".to_string(),
+            path: "skills".to_string(),
+        };
+
+        assert_eq!(
+            github_url.git_ref_url(RefKind::Tag),
+            "https://api.github.com/repos/owner/repo/git/ref/tags/release/v1.0"
+        );
+        assert_eq!(
+            github_url.git_ref_url(RefKind::Branch),
+            "https://api.github.com/repos/owner/repo/git/ref/heads/release/v1.0"
+        );
+    }
+
+    #[test]
+    fn test_pinned_ref_lookup() {
+        let annotated = serde_json::json!({
+            "ref": "refs/tags/v1.0",
+            "object": { "type": "tag", "sha": "abc123" }
+        });
+        assert_eq!(git_object(&annotated), Some(("tag", "abc123")));
+        assert_eq!(
+            tag_object_url("owner/repo", "abc123"),
+            "https://api.github.com/repos/owner/repo/git/tags/abc123"
+        );
+        assert_eq!(git_object(&serde_json::json!({ "message": "oops" })), None);
+
+        let github_url = GitHubUrl {
+            slug: "owner/repo".to_string(),
+            r#ref: "v1.0".to_string(),
+            sha: "v1.0".to_string(),
+            path: "skills/demo".to_string(),
+        };
+        let pinned = github_url.pinned_to(Some("def456"));
+        assert!(
+            pinned
+                .commits_url(None)
+                .contains("sha=def456&path=skills%2Fdemo")
+        );
+        assert_eq!(github_url.pinned_to(None).r#ref, "v1.0");
+    }
+
+    #[test]
+    fn test_commits_url_with_until() {
+        let github_url = GitHubUrl {
+            slug: "owner/repo".to_string(),
+            r#ref: "main".to_string(),
+            sha: "main".to_string(),
+            path: "skills".to_string(),
+        };
+
+        assert_eq!(
+            github_url.commits_url(Some("2024-06-01T23:59:59Z")),
+            "https://api.github.com/repos/owner/repo/commits?sha=main&path=skills&per_page=1&until=2024-06-01T23%3A59%3A59Z"
+        );
+    }
+
     #[test]
     fn test_contents_url_encodes_path_segments() {
         let github_url = GitHubUrl {
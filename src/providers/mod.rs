@@ -1,8 +1,9 @@
+pub mod git;
 pub mod github;
 
 use crate::{
     errors::{SkillsError, SkillsResult},
-    models::SkillEntry,
+    models::{ArchiveFormat, RefKind, SkillEntry},
 };
 use std::path::PathBuf;
 
@@ -21,6 +22,10 @@ pub struct ResolvedSkill {
     pub sha: String,
     /// Path within the repository archive where the skill files live.
     pub path: String,
+    /// The repository's default branch, recorded when the skill tracks it.
+    pub default_branch: Option<String>,
+    /// Branch, tag or commit the source URL names.
+    pub ref_name: Option<String>,
 }
 
 /// The result of resolving a source URL into one or more skills.
@@ -30,10 +35,109 @@ pub struct InstallPlan {
     pub archive_url: String,
     /// Whether this plan covers multiple skills from a single archive.
     pub is_batch: bool,
+    /// Sub-directories of a batch that contain no manifest and are left out.
+    pub non_skill_dirs: Vec<String>,
     /// Skills to install from the archive.
     pub skills: Vec<ResolvedSkill>,
 }
 
+/// The newest upstream commit that touched a skill's path.
+#[derive(Debug, Clone)]
+pub struct UpstreamCommit {
+    /// Full commit SHA.
+    pub sha: String,
+    /// Committer date as an ISO 8601 timestamp, when the provider reports it.
+    pub date: Option<String>,
+}
+
+/// A directory found while browsing a source URL.
+#[derive(Debug, Clone)]
+pub struct SkillListing {
+    /// Directory name.
+    pub name: String,
+    /// Whether the directory contains a skill manifest.
+    pub has_manifest: bool,
+    /// `description` from the manifest's front matter, when present.
+    pub description: Option<String>,
+    /// `name` from the manifest's front matter, when present.
+    pub manifest_name: Option<String>,
+}
+
+/// How resolving a URL went, candidate by candidate.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveReport {
+    /// Every way the URL could be split, in the order they are tried.
+    pub candidates: Vec<CandidateAttempt>,
+    /// The winning candidate, if any.
+    pub resolved: Option<ResolvedSource>,
+}
+
+/// One `(ref, path)` split of a URL and what happened when it was tried.
+#[derive(Debug, Clone)]
+pub struct CandidateAttempt {
+    pub r#ref: String,
+    pub path: String,
+    pub outcome: CandidateOutcome,
+}
+
+#[derive(Debug, Clone)]
+pub enum CandidateOutcome {
+    /// Resolved to this commit SHA.
+    Resolved(String),
+    /// The ref or path does not exist.
+    NotFound,
+    /// The request failed for another reason (network, 403, ...).
+    Failed(String),
+    /// Not tried because an earlier candidate resolved.
+    Skipped,
+}
+
+/// Where a URL resolved to and what it contains.
+#[derive(Debug, Clone)]
+pub struct ResolvedSource {
+    pub slug: String,
+    pub r#ref: String,
+    pub sha: String,
+    pub path: String,
+    /// Skill directories of a collection; empty for a single skill.
+    pub collection: Vec<String>,
+}
+
+/// Options that influence how a source URL is resolved into skills.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Only consider commits made at or before this ISO 8601 timestamp.
+    pub until: Option<String>,
+    /// Archive format to request when downloading.
+    pub archive_format: ArchiveFormat,
+    /// Try refs of this kind first when the URL is ambiguous.
+    pub prefer_ref: Option<RefKind>,
+    /// File names that mark a directory as a skill; empty means `SKILL.md`.
+    pub manifest_names: Vec<String>,
+    /// Ref recorded for the skill; the URL split using it is tried first.
+    pub ref_name: Option<String>,
+    /// Whether `ref_name` is a branch or a tag. When set, the ref is looked
+    /// up in that namespace only and no other split of the URL is tried.
+    pub ref_kind: Option<RefKind>,
+    /// Repository path of the skill. Together with `ref_name` it replaces
+    /// whatever the URL says, and no other split is tried.
+    pub path: Option<String>,
+    /// Treat the path as one skill, without looking for a manifest in it or
+    /// for skills in its subdirectories.
+    pub no_subdir: bool,
+    /// Check up front that the configured token can read the repository.
+    pub check_token: bool,
+}
+
+/// What [`SkillProvider::fetch_and_extract`] transferred and unpacked.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractStats {
+    /// Bytes downloaded.
+    pub bytes: u64,
+    /// Files unpacked for each target, in target order.
+    pub files: Vec<usize>,
+}
+
 /// Describes which path to extract from an archive and where to put it.
 pub struct ExtractTarget {
     /// Path prefix inside the archive to extract.
@@ -49,15 +153,54 @@ pub trait SkillProvider: Send + Sync {
 
     /// Parse the URL, resolve refs to SHAs, detect single vs batch skill layout,
     /// and return an [`InstallPlan`] ready for download.
-    fn resolve_install_plan(&self, url: &str) -> SkillsResult<InstallPlan>;
+    fn resolve_install_plan(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<InstallPlan>;
+
+    /// Resolve `url` like [`SkillProvider::resolve_install_plan`] but record
+    /// every candidate split that was tried and why it failed, without
+    /// downloading anything.
+    fn explain_resolution(
+        &self,
+        url: &str,
+        options: &ResolveOptions,
+    ) -> SkillsResult<ResolveReport>;
+
+    /// List the skill directories at `url` without downloading them: the
+    /// directory itself when it holds a manifest, otherwise each of its
+    /// sub-directories.
+    fn browse(&self, url: &str, manifest_names: &[String]) -> SkillsResult<Vec<SkillListing>>;
 
     /// Download `archive_url` and extract each target into its destination.
     /// `archive_url` is opaque to callers — only the provider that produced it
     /// knows how to fetch it.
-    fn fetch_and_extract(&self, archive_url: &str, targets: &[ExtractTarget]) -> SkillsResult<()>;
+    fn fetch_and_extract(
+        &self,
+        archive_url: &str,
+        targets: &[ExtractTarget],
+    ) -> SkillsResult<ExtractStats>;
+
+    /// Number of files upstream for each skill at its resolved commit, used
+    /// to check that an extraction is complete. Returns `None` when the
+    /// provider cannot tell.
+    fn expected_file_counts(&self, skills: &[ResolvedSkill]) -> SkillsResult<Option<Vec<usize>>>;
 
     /// Reconstruct the archive URL from a stored [`SkillEntry`] for sync.
     fn archive_url_for_entry(&self, entry: &SkillEntry) -> String;
+
+    /// Rebuild the source URL of a stored [`SkillEntry`] so that it points at
+    /// `new_ref` instead of the ref it was installed from.
+    fn source_url_with_ref(&self, entry: &SkillEntry, new_ref: &str) -> SkillsResult<String>;
+
+    /// Resolve the newest upstream commit for a stored [`SkillEntry`],
+    /// honoring its pin. Returns `None` when the source no longer resolves.
+    fn latest_commit(&self, entry: &SkillEntry) -> SkillsResult<Option<UpstreamCommit>>;
+
+    /// If `url` tracks `old_branch` and the repository's default branch has
+    /// since changed, return the equivalent URL on the new default branch.
+    fn relocate_default_branch(&self, url: &str, old_branch: &str) -> SkillsResult<Option<String>>;
 }
 
 /// Holds all registered [`SkillProvider`] instances and routes URLs to the
